@@ -47,8 +47,131 @@ def transform_to_identifier(str)
   identifier
 end
 
+SERVICE_TYPES = {
+  'MNS' => 'MinimumNodeService',
+  'NV' => 'NodeVariable',
+  'CAN' => 'CanBus',
+  'Teach' => 'EventTeaching',
+  'Event/Teach' => 'EventTeaching',
+  'Producer' => 'EventProducer',
+  'Consumer' => 'EventConsumer',
+  'ENACK' => 'EventAcknowledge',
+  'Boot' => 'Bootloader',
+}
+
+DIRECTIONS = {
+  'To module' => 'ToModule',
+  'From module' => 'FromModule',
+  'Both' => 'Both',
+  'To command station' => 'ToCommandStation',
+  'Cab to command station' => 'ToCommandStation',
+  'Cab to common station' => 'ToCommandStation',
+  'From command station' => 'FromCommandStation',
+  'From command station to cab' => 'FromCommandStation',
+}
+
+# Responses are not part of the specification tables, they are maintained here by mnemonic
+RESPONSES = {
+  'QNN' => %w[PNN],
+  'RQNP' => %w[PARAMS],
+  'RQMN' => %w[NAME],
+  'RQNPN' => %w[PARAN CMDERR GRSP],
+  'NVRD' => %w[NVANS CMDERR GRSP],
+  'NVSET' => %w[WRACK CMDERR GRSP],
+  'NVSETRD' => %w[NVANS CMDERR GRSP],
+  'REVAL' => %w[NEVAL CMDERR GRSP],
+  'RQEVN' => %w[NUMEV],
+  'NNEVN' => %w[EVNLF],
+  'NERD' => %w[ENRSP],
+  'NENRD' => %w[ENRSP CMDERR GRSP],
+  'REQEV' => %w[EVANS CMDERR GRSP],
+  'EVLRN' => %w[WRACK CMDERR GRSP],
+  'EVLRNI' => %w[WRACK CMDERR GRSP],
+  'EVULN' => %w[WRACK CMDERR GRSP],
+  'NNCLR' => %w[WRACK CMDERR GRSP],
+  'SNN' => %w[NNACK],
+  'CANID' => %w[WRACK CMDERR GRSP],
+  'MODE' => %w[GRSP],
+  'RQSD' => %w[SD ESD GRSP],
+  'RDGN' => %w[DGN GRSP],
+  'AREQ' => %w[ARON AROF],
+  'ASRQ' => %w[ARSON ARSOF],
+  'RQDAT' => %w[ARDAT],
+  'RQDDS' => %w[DDRS],
+  'RLOC' => %w[PLOC ERR],
+  'GLOC' => %w[PLOC ERR],
+  'QLOC' => %w[PLOC ERR],
+  'RSTAT' => %w[STAT],
+  'QCVS' => %w[PCVS SSTAT ERR],
+}
+
+def extract_priority(str)
+  transform_to_identifier(str.to_s.sub(/\.$/, ''))
+end
+
+# DCC services have no VLCB service type and are left out
+def extract_services(str)
+  str.to_s.split(/,| or | and /).map(&:strip).filter_map { |s| SERVICE_TYPES[s] }
+end
+
+def extract_direction(str)
+  DIRECTIONS.fetch(str.to_s.strip, 'Unspecified')
+end
+
+# Parameters are listed as "Name (n bytes) description", mostly separated by commas
+def extract_parameters(str)
+  str = str.to_s
+  sizes = str.to_enum(:scan, /\s*\((\d+) bytes?\)/).map { Regexp.last_match }
+  return [] if sizes.empty?
+
+  gaps = [str[0...sizes.first.begin(0)]]
+  sizes.each_cons(2) { |a, b| gaps << str[a.end(0)...b.begin(0)] }
+  gaps << str[sizes.last.end(0)..]
+
+  names = [gaps.first.strip]
+  descriptions = []
+  gaps[1...-1].each do |gap|
+    if gap.include?(',')
+      description, _, name = gap.rpartition(',')
+    elsif gap.include?('. ')
+      description, _, name = gap.rpartition('. ')
+      description += '.'
+    else
+      description, _, name = gap.rstrip.rpartition(' ')
+    end
+    descriptions << description
+    names << name.strip
+  end
+  descriptions << gaps.last
+
+  names.zip(sizes, descriptions).map do |name, size, description|
+    param = { 'name' => name, 'size' => size[1].to_i }
+    description = description.strip.delete_prefix(',').delete_suffix(',').strip
+    param['description'] = description unless description.empty?
+    param
+  end
+end
+
+def extract_metadata(entry)
+  {
+    'priority' => extract_priority(entry['Priority']),
+    'services' => extract_services(entry['Services']),
+    'direction' => extract_direction(entry['Direction']),
+    'modes' => entry['States / Modes'].to_s.split(',').map(&:strip).reject(&:empty?),
+    'parameters' => extract_parameters(entry['Parameters']),
+  }
+end
+
+def resolve_responses(opcodes, names)
+  identifiers = names.zip(opcodes).to_h { |name, opcode| [name, opcode['identifier']] }
+  opcodes.zip(names).each do |opcode, name|
+    opcode['metadata']['responses'] = RESPONSES.fetch(name, []).map { |response| identifiers.fetch(response) }
+  end
+end
+
 def transform_to_opcodes(opcdefs)
   opcodes = []
+  names = []
 
   opcdefs.each do |entry|
     opcode = {}
@@ -66,10 +189,13 @@ def transform_to_opcodes(opcdefs)
     File.open("../"+opcode['commentsFrom'], 'w') do |file|
       file.write(comments)
     end
-    opcodes << opcode      file.write(comments)opcode
-
+    opcode['metadata'] = extract_metadata(entry)
+    opcodes << opcode
+    names << entry['Name']
   end
 
+  resolve_responses(opcodes, names)
+
   file_path = "./opcodes.yaml"
   yml = YAML.load_file(file_path)
   yml['body'] = opcodes
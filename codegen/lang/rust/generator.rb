@@ -106,11 +106,24 @@ def gen_enum(ctx, renderer, enum)
     "repr(#{enum[:data_type]})",
   ]
 
+  if enum[:body].any? { |variant| variant['identifier'].include?('_') }
+    enum[:annotations].append('allow(non_camel_case_types)')
+  end
+
   if ctx[:commentsFrom]
     flags[:annotations].append("doc  = include_str!(\"../#{ctx[:commentsFrom]}\")")
   end
 
   output = renderer.r('enum', enum: enum)
+
+  if enum[:body].any? { |variant| variant['metadata'] }
+    ctx[:imports].add('crate::opcode::OpCodeParameter')
+    enum[:body].each do |variant|
+      variant['mnemonic'] = File.basename(variant['commentsFrom'], '.md').upcase
+    end
+    output += renderer.r('metadata', enum: enum)
+  end
+
   ctx[:body] = "#{output}#{ctx[:body]}"
 
   return ctx
@@ -133,7 +146,7 @@ def gen_flags(ctx, renderer, flags)
   end
 
   flags[:annotations] = [
-    'derive(Debug, Copy, Clone)'
+    'derive(Debug, Copy, Clone, Eq, PartialEq)'
   ]
 
   if ctx[:commentsFrom]
@@ -146,6 +159,10 @@ def gen_flags(ctx, renderer, flags)
   return ctx
 end
 
+def rust_str(value)
+  "\"#{value.to_s.gsub(/["\\]/) { |c| "\\#{c}" }}\""
+end
+
 def parse_comments(comments)
   return comments.is_a?(String) ? comments.split("\n") : []
 end
\ No newline at end of file
@@ -56,6 +56,33 @@ class FlagsBodySchema < Dry::Validation::Contract
   end
 end
 
+class MetadataSchema < Dry::Validation::Contract
+  params do
+    required(:priority).filled(Types::String)
+    required(:services).array(Types::String)
+    required(:direction).filled(Types::String)
+    optional(:modes).array(Types::String)
+    required(:parameters).array(:hash) do
+      required(:name).filled(Types::String)
+      required(:size).filled(Types::Integer)
+      optional(:description).maybe(Types::String)
+    end
+    optional(:responses).array(Types::String)
+  end
+
+  rule(:priority) do
+    unless %w[High AboveNormal Normal Low].include?(value)
+      key.failure('must be one of: High, AboveNormal, Normal, Low')
+    end
+  end
+
+  rule(:direction) do
+    unless %w[Unspecified ToModule FromModule Both ToCommandStation FromCommandStation].include?(value)
+      key.failure('must be one of: Unspecified, ToModule, FromModule, Both, ToCommandStation, FromCommandStation')
+    end
+  end
+end
+
 class EnumBodySchema < Dry::Validation::Contract
   include ValueCanBeIntegerOrChar
 
@@ -65,6 +92,14 @@ class EnumBodySchema < Dry::Validation::Contract
     optional(:is_default).maybe(Types::BoolWithDefaultFalse)
     optional(:comments).maybe(Types::String)
     optional(:commentsFrom).maybe(Types::String)
+    optional(:metadata).maybe(:hash)
+  end
+
+  rule(:metadata) do
+    if key? && value
+      result = MetadataSchema.new.call(value)
+      key.failure(result.errors.to_h.to_s) unless result.success?
+    end
   end
 
   rule(:identifier) do
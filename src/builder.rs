@@ -0,0 +1,757 @@
+use crate::dcc::{LocoAddress, LocoFunctionRange};
+use crate::error::DecodeError;
+use crate::message::{GenericResponseResult, VlcbMessage, MAX_MESSAGE_SIZE};
+use crate::vlcb::{EventId, VlcbNodeNumber};
+use crate::{CommandError, OpCode, ServiceType};
+
+/// An encoded VLCB message ready to be sent
+///
+/// Messages are constructed either with one of the named constructors, such as
+/// [`Message::acon`], or with a [`MessageBuilder`] for opcodes that have no
+/// named constructor.
+///
+/// ```
+/// use vlcb_defs::builder::Message;
+/// use vlcb_defs::vlcb::EventId;
+///
+/// let msg = Message::acon(EventId::new(false, 0x01, 0x02, 0x00, 0x05));
+/// assert_eq!(msg.as_bytes(), &[0x90, 0x01, 0x02, 0x00, 0x05]);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Message {
+    opcode: OpCode,
+    data: [u8; MAX_MESSAGE_SIZE],
+}
+
+impl Message {
+    /// Start building a message with the given opcode
+    pub fn builder(opcode: OpCode) -> MessageBuilder {
+        MessageBuilder::new(opcode)
+    }
+
+    /// Return the opcode of the message
+    pub fn opcode(&self) -> OpCode {
+        self.opcode
+    }
+
+    /// Return the message as a sequence of octets, opcode included
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data[..self.opcode.frame_len()]
+    }
+
+    /// Return the message in a fixed size array
+    ///
+    /// Octets past [`OpCode::frame_len`] are set to 0.
+    pub fn to_array(&self) -> [u8; MAX_MESSAGE_SIZE] {
+        self.data
+    }
+
+    /// Copy the message into the caller buffer
+    ///
+    /// Returns the number of octets written, or [`DecodeError::WrongLength`]
+    /// when the buffer is too short to hold the message.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, DecodeError> {
+        let bytes = self.as_bytes();
+        let available = buf.len();
+        let out = buf.get_mut(..bytes.len()).ok_or(DecodeError::WrongLength {
+            expected: bytes.len(),
+            actual: available,
+        })?;
+        out.copy_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    /// Decode the message into its typed form
+    pub fn to_message(&self) -> Result<VlcbMessage, DecodeError> {
+        VlcbMessage::decode(self.as_bytes())
+    }
+
+    /// Build an [`OpCode::GeneralAck`] (ACK) message
+    pub fn ack() -> Self {
+        Self::builder(OpCode::GeneralAck).finish()
+    }
+
+    /// Build an [`OpCode::GeneralNack`] (NAK) message
+    pub fn nak() -> Self {
+        Self::builder(OpCode::GeneralNack).finish()
+    }
+
+    /// Build an [`OpCode::BusHalt`] (HLT) message
+    pub fn hlt() -> Self {
+        Self::builder(OpCode::BusHalt).finish()
+    }
+
+    /// Build an [`OpCode::BusResume`] (BON) message
+    pub fn bon() -> Self {
+        Self::builder(OpCode::BusResume).finish()
+    }
+
+    /// Build an [`OpCode::DccTrackPoweredOff`] (TOF) message
+    pub fn tof() -> Self {
+        Self::builder(OpCode::DccTrackPoweredOff).finish()
+    }
+
+    /// Build an [`OpCode::DccTrackPoweredOn`] (TON) message
+    pub fn ton() -> Self {
+        Self::builder(OpCode::DccTrackPoweredOn).finish()
+    }
+
+    /// Build an [`OpCode::DccEmergencyStopEngaged`] (ESTOP) message
+    pub fn estop() -> Self {
+        Self::builder(OpCode::DccEmergencyStopEngaged).finish()
+    }
+
+    /// Build an [`OpCode::RestartAllNodes`] (ARST) message
+    pub fn arst() -> Self {
+        Self::builder(OpCode::RestartAllNodes).finish()
+    }
+
+    /// Build an [`OpCode::DccTrackPowerOff`] (RTOF) message
+    pub fn rtof() -> Self {
+        Self::builder(OpCode::DccTrackPowerOff).finish()
+    }
+
+    /// Build an [`OpCode::DccTrackPowerOn`] (RTON) message
+    pub fn rton() -> Self {
+        Self::builder(OpCode::DccTrackPowerOn).finish()
+    }
+
+    /// Build an [`OpCode::DccEmergencyStop`] (RESTP) message
+    pub fn restp() -> Self {
+        Self::builder(OpCode::DccEmergencyStop).finish()
+    }
+
+    /// Build an [`OpCode::DccQueryCommandStationStatus`] (RSTAT) message
+    pub fn rstat() -> Self {
+        Self::builder(OpCode::DccQueryCommandStationStatus).finish()
+    }
+
+    /// Build an [`OpCode::QueryNodeInfo`] (QNN) message
+    pub fn qnn() -> Self {
+        Self::builder(OpCode::QueryNodeInfo).finish()
+    }
+
+    /// Build an [`OpCode::QueryNodeParameters`] (RQNP) message
+    pub fn rqnp() -> Self {
+        Self::builder(OpCode::QueryNodeParameters).finish()
+    }
+
+    /// Build an [`OpCode::QueryModuleName`] (RQMN) message
+    pub fn rqmn() -> Self {
+        Self::builder(OpCode::QueryModuleName).finish()
+    }
+
+    /// Build an [`OpCode::DccReleaseSession`] (KLOC) message
+    pub fn kloc(session: u8) -> Self {
+        Self::builder(OpCode::DccReleaseSession)
+            .u8(session)
+            .finish()
+    }
+
+    /// Build an [`OpCode::DccQueryLocoStatus`] (QLOC) message
+    pub fn qloc(session: u8) -> Self {
+        Self::builder(OpCode::DccQueryLocoStatus)
+            .u8(session)
+            .finish()
+    }
+
+    /// Build an [`OpCode::DccSessionKeepAlive`] (DKEEP) message
+    pub fn dkeep(session: u8) -> Self {
+        Self::builder(OpCode::DccSessionKeepAlive)
+            .u8(session)
+            .finish()
+    }
+
+    /// Build an [`OpCode::DccRequestNewSession`] (RLOC) message
+    pub fn rloc(address: LocoAddress) -> Self {
+        Self::builder(OpCode::DccRequestNewSession)
+            .loco_address(address)
+            .finish()
+    }
+
+    /// Build an [`OpCode::DccQueryLocoSession`] (GLOC) message
+    pub fn gloc(address: LocoAddress, flags: u8) -> Self {
+        Self::builder(OpCode::DccQueryLocoSession)
+            .loco_address(address)
+            .u8(flags)
+            .finish()
+    }
+
+    /// Build an [`OpCode::DccSetLocoThrottle`] (DSPD) message
+    pub fn dspd(session: u8, speed_dir: u8) -> Self {
+        Self::builder(OpCode::DccSetLocoThrottle)
+            .u8(session)
+            .u8(speed_dir)
+            .finish()
+    }
+
+    /// Build an [`OpCode::DccSetLocoFunctions`] (DFUN) message
+    pub fn dfun(session: u8, range: LocoFunctionRange, functions: u8) -> Self {
+        Self::builder(OpCode::DccSetLocoFunctions)
+            .u8(session)
+            .u8(range.into())
+            .u8(functions)
+            .finish()
+    }
+
+    /// Build an [`OpCode::DccLocoReport`] (PLOC) message
+    pub fn ploc(session: u8, address: LocoAddress, speed_dir: u8, functions: [u8; 3]) -> Self {
+        Self::builder(OpCode::DccLocoReport)
+            .u8(session)
+            .loco_address(address)
+            .u8(speed_dir)
+            .bytes(&functions)
+            .finish()
+    }
+
+    /// Build an [`OpCode::SetNodeNumber`] (SNN) message
+    pub fn snn(node_number: VlcbNodeNumber) -> Self {
+        Self::builder(OpCode::SetNodeNumber)
+            .node_number(node_number)
+            .finish()
+    }
+
+    /// Build an [`OpCode::RequestNewNodeNumber`] (RQNN) message
+    pub fn rqnn(node_number: VlcbNodeNumber) -> Self {
+        Self::builder(OpCode::RequestNewNodeNumber)
+            .node_number(node_number)
+            .finish()
+    }
+
+    /// Build an [`OpCode::NodeNumberAck`] (NNACK) message
+    pub fn nnack(node_number: VlcbNodeNumber) -> Self {
+        Self::builder(OpCode::NodeNumberAck)
+            .node_number(node_number)
+            .finish()
+    }
+
+    /// Build an [`OpCode::NodeNumberReleased`] (NNREL) message
+    pub fn nnrel(node_number: VlcbNodeNumber) -> Self {
+        Self::builder(OpCode::NodeNumberReleased)
+            .node_number(node_number)
+            .finish()
+    }
+
+    /// Build an [`OpCode::PutNodeIntoLearnMode`] (NNLRN) message
+    pub fn nnlrn(node_number: VlcbNodeNumber) -> Self {
+        Self::builder(OpCode::PutNodeIntoLearnMode)
+            .node_number(node_number)
+            .finish()
+    }
+
+    /// Build an [`OpCode::ReleaseNodeFromLearnMode`] (NNULN) message
+    pub fn nnuln(node_number: VlcbNodeNumber) -> Self {
+        Self::builder(OpCode::ReleaseNodeFromLearnMode)
+            .node_number(node_number)
+            .finish()
+    }
+
+    /// Build an [`OpCode::ForgetAllLearnedEvents`] (NNCLR) message
+    pub fn nnclr(node_number: VlcbNodeNumber) -> Self {
+        Self::builder(OpCode::ForgetAllLearnedEvents)
+            .node_number(node_number)
+            .finish()
+    }
+
+    /// Build an [`OpCode::QueryAvailableEventSlots`] (NNEVN) message
+    pub fn nnevn(node_number: VlcbNodeNumber) -> Self {
+        Self::builder(OpCode::QueryAvailableEventSlots)
+            .node_number(node_number)
+            .finish()
+    }
+
+    /// Build an [`OpCode::QueryAllLearnedEvents`] (NERD) message
+    pub fn nerd(node_number: VlcbNodeNumber) -> Self {
+        Self::builder(OpCode::QueryAllLearnedEvents)
+            .node_number(node_number)
+            .finish()
+    }
+
+    /// Build an [`OpCode::QueryLearnedEventCount`] (RQEVN) message
+    pub fn rqevn(node_number: VlcbNodeNumber) -> Self {
+        Self::builder(OpCode::QueryLearnedEventCount)
+            .node_number(node_number)
+            .finish()
+    }
+
+    /// Build an [`OpCode::WriteAck`] (WRACK) message
+    pub fn wrack(node_number: VlcbNodeNumber) -> Self {
+        Self::builder(OpCode::WriteAck)
+            .node_number(node_number)
+            .finish()
+    }
+
+    /// Build an [`OpCode::QueryNodeParameterByIndex`] (RQNPN) message
+    pub fn rqnpn(node_number: VlcbNodeNumber, index: u8) -> Self {
+        Self::builder(OpCode::QueryNodeParameterByIndex)
+            .node_number(node_number)
+            .u8(index)
+            .finish()
+    }
+
+    /// Build an [`OpCode::NodeParameterValue`] (PARAN) message
+    pub fn paran(node_number: VlcbNodeNumber, index: u8, value: u8) -> Self {
+        Self::builder(OpCode::NodeParameterValue)
+            .node_number(node_number)
+            .u8(index)
+            .u8(value)
+            .finish()
+    }
+
+    /// Build an [`OpCode::QueryNodeVariable`] (NVRD) message
+    pub fn nvrd(node_number: VlcbNodeNumber, index: u8) -> Self {
+        Self::builder(OpCode::QueryNodeVariable)
+            .node_number(node_number)
+            .u8(index)
+            .finish()
+    }
+
+    /// Build an [`OpCode::LegacySetNodeVariable`] (NVSET) message
+    pub fn nvset(node_number: VlcbNodeNumber, index: u8, value: u8) -> Self {
+        Self::builder(OpCode::LegacySetNodeVariable)
+            .node_number(node_number)
+            .u8(index)
+            .u8(value)
+            .finish()
+    }
+
+    /// Build an [`OpCode::NodeVariableValue`] (NVANS) message
+    pub fn nvans(node_number: VlcbNodeNumber, index: u8, value: u8) -> Self {
+        Self::builder(OpCode::NodeVariableValue)
+            .node_number(node_number)
+            .u8(index)
+            .u8(value)
+            .finish()
+    }
+
+    /// Build an [`OpCode::LearnedEventCount`] (NUMEV) message
+    pub fn numev(node_number: VlcbNodeNumber, count: u8) -> Self {
+        Self::builder(OpCode::LearnedEventCount)
+            .node_number(node_number)
+            .u8(count)
+            .finish()
+    }
+
+    /// Build an [`OpCode::AvailableEventSlots`] (EVNLF) message
+    pub fn evnlf(node_number: VlcbNodeNumber, slots: u8) -> Self {
+        Self::builder(OpCode::AvailableEventSlots)
+            .node_number(node_number)
+            .u8(slots)
+            .finish()
+    }
+
+    /// Build an [`OpCode::NodeConfigurationError`] (CMDERR) message
+    pub fn cmderr(node_number: VlcbNodeNumber, error: CommandError) -> Self {
+        Self::builder(OpCode::NodeConfigurationError)
+            .node_number(node_number)
+            .u8(error.into())
+            .finish()
+    }
+
+    /// Build an [`OpCode::GenericResponse`] (GRSP) message
+    ///
+    /// `result` is either a [`GenericResponseStatus`](crate::GenericResponseStatus) or
+    /// a [`CommandError`].
+    pub fn grsp(
+        node_number: VlcbNodeNumber,
+        opcode: OpCode,
+        service: ServiceType,
+        result: impl Into<GenericResponseResult>,
+    ) -> Self {
+        Self::builder(OpCode::GenericResponse)
+            .node_number(node_number)
+            .u8(opcode.into())
+            .u8(service.into())
+            .u8(result.into().into())
+            .finish()
+    }
+
+    /// Build an [`OpCode::LongEventAccessoryOn`] (ACON) message
+    pub fn acon(event: EventId) -> Self {
+        Self::builder(OpCode::LongEventAccessoryOn)
+            .event(&event)
+            .finish()
+    }
+
+    /// Build an [`OpCode::LongEventAccessoryOff`] (ACOF) message
+    pub fn acof(event: EventId) -> Self {
+        Self::builder(OpCode::LongEventAccessoryOff)
+            .event(&event)
+            .finish()
+    }
+
+    /// Build an [`OpCode::QueryLongEventAccessoryState`] (AREQ) message
+    pub fn areq(event: EventId) -> Self {
+        Self::builder(OpCode::QueryLongEventAccessoryState)
+            .event(&event)
+            .finish()
+    }
+
+    /// Build an [`OpCode::LongEventAccessoryStateOn`] (ARON) message
+    pub fn aron(event: EventId) -> Self {
+        Self::builder(OpCode::LongEventAccessoryStateOn)
+            .event(&event)
+            .finish()
+    }
+
+    /// Build an [`OpCode::LongEventAccessoryStateOff`] (AROF) message
+    pub fn arof(event: EventId) -> Self {
+        Self::builder(OpCode::LongEventAccessoryStateOff)
+            .event(&event)
+            .finish()
+    }
+
+    /// Build an [`OpCode::ShortEventAccessoryOn`] (ASON) message
+    ///
+    /// The node number of a short event, taken from `event`, is the node number of the
+    /// sending node.
+    pub fn ason(event: EventId) -> Self {
+        Self::builder(OpCode::ShortEventAccessoryOn)
+            .event(&event)
+            .finish()
+    }
+
+    /// Build an [`OpCode::ShortEventAccessoryOff`] (ASOF) message
+    ///
+    /// The node number of a short event, taken from `event`, is the node number of the
+    /// sending node.
+    pub fn asof(event: EventId) -> Self {
+        Self::builder(OpCode::ShortEventAccessoryOff)
+            .event(&event)
+            .finish()
+    }
+
+    /// Build an [`OpCode::QueryShortEventAccessoryState`] (ASRQ) message
+    ///
+    /// The node number of a short event, taken from `event`, is the node number of the
+    /// sending node.
+    pub fn asrq(event: EventId) -> Self {
+        Self::builder(OpCode::QueryShortEventAccessoryState)
+            .event(&event)
+            .finish()
+    }
+
+    /// Build an [`OpCode::ShortEventAccessoryStateOn`] (ARSON) message
+    ///
+    /// The node number of a short event, taken from `event`, is the node number of the
+    /// sending node.
+    pub fn arson(event: EventId) -> Self {
+        Self::builder(OpCode::ShortEventAccessoryStateOn)
+            .event(&event)
+            .finish()
+    }
+
+    /// Build an [`OpCode::ShortEventAccessoryStateOff`] (ARSOF) message
+    ///
+    /// The node number of a short event, taken from `event`, is the node number of the
+    /// sending node.
+    pub fn arsof(event: EventId) -> Self {
+        Self::builder(OpCode::ShortEventAccessoryStateOff)
+            .event(&event)
+            .finish()
+    }
+
+    /// Build an [`OpCode::ForgetLearnedEvent`] (EVULN) message
+    pub fn evuln(event: EventId) -> Self {
+        Self::builder(OpCode::ForgetLearnedEvent)
+            .event(&event)
+            .finish()
+    }
+
+    /// Build an [`OpCode::QueryEventVariableInLearnMode`] (REQEV) message
+    pub fn reqev(event: EventId, ev_index: u8) -> Self {
+        Self::builder(OpCode::QueryEventVariableInLearnMode)
+            .event(&event)
+            .u8(ev_index)
+            .finish()
+    }
+
+    /// Build an [`OpCode::TeachEvent`] (EVLRN) message
+    pub fn evlrn(event: EventId, ev_index: u8, value: u8) -> Self {
+        Self::builder(OpCode::TeachEvent)
+            .event(&event)
+            .u8(ev_index)
+            .u8(value)
+            .finish()
+    }
+
+    /// Build an [`OpCode::QueryEventVariable`] (REVAL) message
+    pub fn reval(node_number: VlcbNodeNumber, event_index: u8, ev_index: u8) -> Self {
+        Self::builder(OpCode::QueryEventVariable)
+            .node_number(node_number)
+            .u8(event_index)
+            .u8(ev_index)
+            .finish()
+    }
+}
+
+/// Builder of a [`Message`] for an arbitrary opcode
+///
+/// Parameters are appended in the order they follow the opcode. [`MessageBuilder::build`]
+/// checks that the parameters add up to the data length of the opcode.
+///
+/// ```
+/// use vlcb_defs::builder::Message;
+/// use vlcb_defs::vlcb::VlcbNodeNumber;
+/// use vlcb_defs::OpCode;
+///
+/// let msg = Message::builder(OpCode::SetNodeVariable)
+///     .node_number(VlcbNodeNumber::new(0x01, 0x02))
+///     .u8(3)
+///     .u8(42)
+///     .build()
+///     .unwrap();
+/// assert_eq!(msg.as_bytes(), &[0x8E, 0x01, 0x02, 0x03, 0x2A]);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MessageBuilder {
+    opcode: OpCode,
+    data: [u8; MAX_MESSAGE_SIZE],
+    len: usize,
+}
+
+impl MessageBuilder {
+    /// Start building a message with the given opcode
+    pub fn new(opcode: OpCode) -> Self {
+        let mut data = [0; MAX_MESSAGE_SIZE];
+        data[0] = opcode.into();
+        Self {
+            opcode,
+            data,
+            len: 1,
+        }
+    }
+
+    /// Append a sequence of octets
+    ///
+    /// Octets that do not fit into [`MAX_MESSAGE_SIZE`] are counted but dropped,
+    /// the length mismatch is reported by [`MessageBuilder::build`].
+    pub fn bytes(mut self, data: &[u8]) -> Self {
+        let end = self.len + data.len();
+        if let Some(out) = self.data.get_mut(self.len..end) {
+            out.copy_from_slice(data);
+        }
+        self.len = end;
+        self
+    }
+
+    /// Append an octet
+    pub fn u8(self, value: u8) -> Self {
+        self.bytes(&[value])
+    }
+
+    /// Append a 16 bit value in big-endian
+    pub fn u16(self, value: u16) -> Self {
+        self.bytes(&value.to_be_bytes())
+    }
+
+    /// Append a node number
+    pub fn node_number(self, node_number: VlcbNodeNumber) -> Self {
+        self.bytes(node_number.as_bytes())
+    }
+
+    /// Append all four octets of an event
+    pub fn event(self, event: &EventId) -> Self {
+        self.bytes(event.as_bytes())
+    }
+
+    /// Append the event number part of an event, as used by short events
+    pub fn event_number(self, event: &EventId) -> Self {
+        self.bytes(&event.as_bytes()[2..])
+    }
+
+    /// Append a DCC locomotive address, see [`LocoAddress::as_bytes_sanitized`]
+    pub fn loco_address(self, address: LocoAddress) -> Self {
+        self.bytes(&address.as_bytes_sanitized())
+    }
+
+    /// Finish the message
+    ///
+    /// Returns [`DecodeError::WrongLength`] when the appended parameters do not match
+    /// the data length of the opcode.
+    pub fn build(self) -> Result<Message, DecodeError> {
+        if self.len != self.opcode.frame_len() {
+            return Err(DecodeError::WrongLength {
+                expected: self.opcode.frame_len(),
+                actual: self.len,
+            });
+        }
+
+        Ok(Message {
+            opcode: self.opcode,
+            data: self.data,
+        })
+    }
+
+    /// Finish a message whose parameters are known to match the opcode
+    fn finish(self) -> Message {
+        debug_assert_eq!(self.len, self.opcode.frame_len(), "{:?}", self.opcode);
+        Message {
+            opcode: self.opcode,
+            data: self.data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::GenericResponseStatus;
+
+    #[test]
+    fn test_named() {
+        let event = EventId::new(false, 0x01, 0x02, 0x00, 0x05);
+        assert_eq!(
+            Message::acon(event).as_bytes(),
+            &[0x90, 0x01, 0x02, 0x00, 0x05]
+        );
+        assert_eq!(Message::hlt().as_bytes(), &[0x02]);
+        assert_eq!(Message::dspd(3, 0x85).as_bytes(), &[0x47, 0x03, 0x85]);
+        assert_eq!(
+            Message::grsp(
+                VlcbNodeNumber::new(0x01, 0x02),
+                OpCode::SetNodeVariable,
+                ServiceType::NodeVariable,
+                GenericResponseStatus::Ok,
+            )
+            .as_bytes(),
+            &[0xAF, 0x01, 0x02, 0x8E, 0x02, 0x00]
+        );
+        assert_eq!(
+            Message::grsp(
+                VlcbNodeNumber::new(0x01, 0x00),
+                OpCode::TeachEvent,
+                ServiceType::EventTeaching,
+                CommandError::TooManyEvents,
+            )
+            .as_bytes(),
+            &[0xAF, 0x01, 0x00, 0xD2, 0x07, 0x04]
+        );
+        assert_eq!(
+            Message::ason(EventId::new(true, 0x01, 0x02, 0x00, 0x07)).as_bytes(),
+            &[0x98, 0x01, 0x02, 0x00, 0x07]
+        );
+        assert_eq!(
+            Message::rloc(LocoAddress::new_long(1234)).as_bytes(),
+            &[0x40, 0xC4, 0xD2]
+        );
+    }
+
+    #[test]
+    fn test_named_lengths() {
+        let nn = VlcbNodeNumber::new(0, 1);
+        let ev = EventId::new(false, 0, 1, 0, 2);
+        let addr = LocoAddress::new(3);
+        let messages = [
+            Message::ack(),
+            Message::nak(),
+            Message::hlt(),
+            Message::bon(),
+            Message::tof(),
+            Message::ton(),
+            Message::estop(),
+            Message::arst(),
+            Message::rtof(),
+            Message::rton(),
+            Message::restp(),
+            Message::rstat(),
+            Message::qnn(),
+            Message::rqnp(),
+            Message::rqmn(),
+            Message::kloc(1),
+            Message::qloc(1),
+            Message::dkeep(1),
+            Message::rloc(addr),
+            Message::gloc(addr, 0),
+            Message::dspd(1, 0),
+            Message::dfun(1, LocoFunctionRange::F0ToF4, 0),
+            Message::ploc(1, addr, 0, [0; 3]),
+            Message::snn(nn),
+            Message::rqnn(nn),
+            Message::nnack(nn),
+            Message::nnrel(nn),
+            Message::nnlrn(nn),
+            Message::nnuln(nn),
+            Message::nnclr(nn),
+            Message::nnevn(nn),
+            Message::nerd(nn),
+            Message::rqevn(nn),
+            Message::wrack(nn),
+            Message::rqnpn(nn, 0),
+            Message::paran(nn, 0, 0),
+            Message::nvrd(nn, 0),
+            Message::nvset(nn, 0, 0),
+            Message::nvans(nn, 0, 0),
+            Message::numev(nn, 0),
+            Message::evnlf(nn, 0),
+            Message::cmderr(nn, CommandError::InvalidEvent),
+            Message::grsp(
+                nn,
+                OpCode::BusHalt,
+                ServiceType::CanBus,
+                GenericResponseStatus::Ok,
+            ),
+            Message::acon(ev),
+            Message::acof(ev),
+            Message::areq(ev),
+            Message::aron(ev),
+            Message::arof(ev),
+            Message::ason(ev),
+            Message::asof(ev),
+            Message::asrq(ev),
+            Message::arson(ev),
+            Message::arsof(ev),
+            Message::evuln(ev),
+            Message::reqev(ev, 0),
+            Message::evlrn(ev, 0, 0),
+            Message::reval(nn, 0, 0),
+        ];
+        for msg in messages {
+            assert_eq!(msg.as_bytes().len(), msg.opcode().frame_len());
+            assert_eq!(OpCode::validate_frame(msg.as_bytes()), Ok(msg.opcode()));
+            assert!(msg.to_message().is_ok(), "{:?}", msg);
+        }
+    }
+
+    #[test]
+    fn test_builder() {
+        assert_eq!(
+            Message::builder(OpCode::BusHalt).u8(1).build(),
+            Err(DecodeError::WrongLength {
+                expected: 1,
+                actual: 2
+            })
+        );
+        assert_eq!(
+            Message::builder(OpCode::DccLocoReport)
+                .bytes(&[0; 9])
+                .build(),
+            Err(DecodeError::WrongLength {
+                expected: 8,
+                actual: 10
+            })
+        );
+        let msg = Message::builder(OpCode::DccQueryConsist)
+            .u16(0x0102)
+            .build()
+            .unwrap();
+        assert_eq!(msg.to_array(), [0x41, 0x01, 0x02, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_write_to() {
+        let msg = Message::snn(VlcbNodeNumber::new(0x01, 0x02));
+        let mut buf = [0u8; 4];
+        assert_eq!(msg.write_to(&mut buf), Ok(3));
+        assert_eq!(&buf[..3], &[0x42, 0x01, 0x02]);
+        assert_eq!(
+            msg.write_to(&mut buf[..2]),
+            Err(DecodeError::WrongLength {
+                expected: 3,
+                actual: 2
+            })
+        );
+    }
+}
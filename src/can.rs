@@ -1,9 +1,29 @@
 use core::fmt;
+use core::ops::RangeInclusive;
+
+use crate::error::{check_len, VlcbError};
+use crate::{OpCode, OpCodePriority};
 
 /// Size of an CBUS CAN ID in octets.
 pub const CANID_SIZE: usize = 1;
 pub const CANID_MASK: u8 = 0x7f;
 
+/// Lowest CAN ID a node may pick or be assigned.
+///
+/// CAN ID 0 is reserved and never used by a node.
+pub const CANID_MIN: u8 = 1;
+
+/// Highest CAN ID a node may pick or be assigned.
+///
+/// The CAN IDs above are reserved, see [`FIXED_CANID_RANGE`].
+pub const CANID_MAX: u8 = 99;
+
+/// CAN IDs reserved for modules with a fixed CAN ID, like command stations and bridges.
+///
+/// Reserved by the CAN ID allocation of the MERG CBUS Developer's Guide. These are
+/// never picked during CAN ID enumeration.
+pub const FIXED_CANID_RANGE: RangeInclusive<u8> = 100..=127;
+
 /// A 7-bit CAN ID for CBUS.
 ///
 /// Used to identify nodes on a CAN network
@@ -12,20 +32,64 @@ pub const CANID_MASK: u8 = 0x7f;
 pub struct VlcbCanId(pub [u8; CANID_SIZE]);
 
 impl VlcbCanId {
+    /// Construct a CAN ID a node may pick or be assigned.
+    ///
+    /// Returns [`VlcbError::ReservedBitsSet`] if `id` does not fit into 7 bits and
+    /// [`VlcbError::ReservedValue`] if `id` is 0 or in [`FIXED_CANID_RANGE`].
+    pub const fn new(id: u8) -> Result<Self, VlcbError> {
+        if id & !CANID_MASK != 0 {
+            return Err(VlcbError::ReservedBitsSet {
+                type_name: "VlcbCanId",
+                value: id,
+            });
+        }
+        if id < CANID_MIN || id > CANID_MAX {
+            return Err(VlcbError::ReservedValue {
+                type_name: "VlcbCanId",
+                value: id,
+            });
+        }
+        Ok(Self([id]))
+    }
+
+    /// Construct a fixed CAN ID of a command station or bridge.
+    ///
+    /// Returns [`VlcbError::ReservedValue`] if `id` is not in [`FIXED_CANID_RANGE`].
+    pub const fn new_fixed(id: u8) -> Result<Self, VlcbError> {
+        if id < *FIXED_CANID_RANGE.start() || id > *FIXED_CANID_RANGE.end() {
+            return Err(VlcbError::ReservedValue {
+                type_name: "VlcbCanId",
+                value: id,
+            });
+        }
+        Ok(Self([id]))
+    }
+
     /// Construct an CAN address from an octet.
     ///
-    /// # Panics
-    /// The function panics if `data` is not one octet long.
-    pub fn from_bytes(data: &[u8]) -> Self {
-        let mut bytes = [0; CANID_SIZE];
-        bytes.copy_from_slice(data);
-        Self(bytes.map(|x| x & CANID_MASK))
+    /// The most significant bit of the octet is stripped, reserved values are
+    /// accepted. Use [`VlcbCanId::new`] to validate a CAN ID.
+    ///
+    /// Returns [`VlcbError::WrongLength`] if `data` is not one octet long.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, VlcbError> {
+        check_len(data, CANID_SIZE)?;
+        Ok(Self([data[0] & CANID_MASK]))
     }
 
     /// Return an CAN address as an octet.
     pub const fn as_bytes(&self) -> &[u8] {
         &self.0
     }
+
+    /// Check whether the CAN ID is reserved for modules with a fixed CAN ID.
+    pub const fn is_fixed(&self) -> bool {
+        self.0[0] >= *FIXED_CANID_RANGE.start() && self.0[0] <= *FIXED_CANID_RANGE.end()
+    }
+
+    /// Check whether the CAN ID is valid for a node, see [`VlcbCanId::new`].
+    pub const fn is_valid(&self) -> bool {
+        self.0[0] >= CANID_MIN && self.0[0] <= CANID_MAX
+    }
 }
 
 impl fmt::Display for VlcbCanId {
@@ -40,6 +104,135 @@ impl From<VlcbCanId> for u8 {
     }
 }
 
+/// Mask of the 11-bit CAN standard identifier.
+pub const STANDARD_ID_MASK: u16 = 0x7FF;
+
+/// Major priority of a CBUS frame
+///
+/// Frames are sent with [`MajorPriority::Normal`] and the priority is raised
+/// when a frame repeatedly loses arbitration. Lower values win the arbitration.
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum MajorPriority {
+    Highest = 0,
+    High = 1,
+    #[default]
+    Normal = 2,
+    Low = 3,
+}
+
+impl MajorPriority {
+    /// Construct the priority from the two least significant bits of `bits`
+    pub const fn from_bits(bits: u8) -> Self {
+        match bits & 0x03 {
+            0 => Self::Highest,
+            1 => Self::High,
+            2 => Self::Normal,
+            _ => Self::Low,
+        }
+    }
+}
+
+/// Minor priority of a CBUS frame
+///
+/// The minor priority is given by the opcode of the message, see [`OpCode::priority`].
+/// Lower values win the arbitration.
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum MinorPriority {
+    High = 0,
+    AboveNormal = 1,
+    #[default]
+    Normal = 2,
+    Low = 3,
+}
+
+impl MinorPriority {
+    /// Construct the priority from the two least significant bits of `bits`
+    pub const fn from_bits(bits: u8) -> Self {
+        match bits & 0x03 {
+            0 => Self::High,
+            1 => Self::AboveNormal,
+            2 => Self::Normal,
+            _ => Self::Low,
+        }
+    }
+}
+
+impl From<OpCodePriority> for MinorPriority {
+    fn from(value: OpCodePriority) -> Self {
+        match value {
+            OpCodePriority::High => Self::High,
+            OpCodePriority::AboveNormal => Self::AboveNormal,
+            OpCodePriority::Normal => Self::Normal,
+            OpCodePriority::Low => Self::Low,
+        }
+    }
+}
+
+/// Header of a CBUS CAN frame
+///
+/// The header is carried in the 11-bit standard identifier of the frame,
+/// from the most significant bit: 2 bits of [`MajorPriority`], 2 bits of
+/// [`MinorPriority`] and 7 bits of [`VlcbCanId`].
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CanHeader {
+    pub major_priority: MajorPriority,
+    pub minor_priority: MinorPriority,
+    pub can_id: VlcbCanId,
+}
+
+impl CanHeader {
+    /// Construct a header from parts.
+    pub const fn new(
+        major_priority: MajorPriority,
+        minor_priority: MinorPriority,
+        can_id: VlcbCanId,
+    ) -> Self {
+        Self {
+            major_priority,
+            minor_priority,
+            can_id,
+        }
+    }
+
+    /// Construct a header for a message with the given opcode
+    ///
+    /// The major priority is [`MajorPriority::Normal`] and the minor priority
+    /// is the priority of the opcode.
+    pub fn for_opcode(can_id: VlcbCanId, opcode: OpCode) -> Self {
+        Self::new(MajorPriority::Normal, opcode.priority().into(), can_id)
+    }
+
+    /// Return the 11-bit CAN standard identifier
+    pub const fn to_standard_id(&self) -> u16 {
+        (self.major_priority as u16) << 9
+            | (self.minor_priority as u16) << 7
+            | (self.can_id.0[0] & CANID_MASK) as u16
+    }
+
+    /// Construct a header from an 11-bit CAN standard identifier
+    ///
+    /// Returns [`VlcbError::ReservedBitsSet`] if `id` does not fit into 11 bits.
+    pub const fn from_standard_id(id: u16) -> Result<Self, VlcbError> {
+        if id & !STANDARD_ID_MASK != 0 {
+            return Err(VlcbError::ReservedBitsSet {
+                type_name: "CanHeader",
+                value: (id >> 8) as u8,
+            });
+        }
+
+        Ok(Self {
+            major_priority: MajorPriority::from_bits((id >> 9) as u8),
+            minor_priority: MinorPriority::from_bits((id >> 7) as u8),
+            can_id: VlcbCanId([id as u8 & CANID_MASK]),
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -47,8 +240,62 @@ mod test {
 
     #[test]
     fn test_address() {
-        let addr = VlcbCanId::from_bytes(&[0xFF]);
+        let addr = VlcbCanId::from_bytes(&[0xFF]).unwrap();
         assert_eq!(addr.as_bytes(), &[0x7F]);
         assert_eq!(addr.to_string(), "7F");
+        assert_eq!(
+            VlcbCanId::from_bytes(&[]),
+            Err(VlcbError::WrongLength {
+                expected: 1,
+                actual: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(VlcbCanId::new(1), Ok(VlcbCanId([1])));
+        assert!(VlcbCanId::new(CANID_MAX).unwrap().is_valid());
+        assert_eq!(
+            VlcbCanId::new(0),
+            Err(VlcbError::ReservedValue {
+                type_name: "VlcbCanId",
+                value: 0
+            })
+        );
+        assert_eq!(
+            VlcbCanId::new(0x80),
+            Err(VlcbError::ReservedBitsSet {
+                type_name: "VlcbCanId",
+                value: 0x80
+            })
+        );
+        assert!(VlcbCanId::new(114).is_err());
+
+        let fixed = VlcbCanId::new_fixed(114).unwrap();
+        assert!(fixed.is_fixed());
+        assert!(!fixed.is_valid());
+        assert!(VlcbCanId::new_fixed(5).is_err());
+        assert!(VlcbCanId::new_fixed(0x80).is_err());
+    }
+
+    #[test]
+    fn test_header() {
+        let header = CanHeader::for_opcode(VlcbCanId([0x7F]), OpCode::LongEventAccessoryOn);
+        assert_eq!(header.major_priority, MajorPriority::Normal);
+        assert_eq!(header.minor_priority, MinorPriority::Low);
+        assert_eq!(header.to_standard_id(), 0x5FF);
+        assert_eq!(CanHeader::from_standard_id(0x5FF), Ok(header));
+
+        let header = CanHeader::for_opcode(VlcbCanId([0x01]), OpCode::BusHalt);
+        assert_eq!(header.to_standard_id(), 0x401);
+
+        assert_eq!(
+            CanHeader::from_standard_id(0x800),
+            Err(VlcbError::ReservedBitsSet {
+                type_name: "CanHeader",
+                value: 0x08
+            })
+        );
     }
 }
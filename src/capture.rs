@@ -0,0 +1,542 @@
+use std::io::{self, BufRead, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::can::CanHeader;
+use crate::error::VlcbError;
+use crate::frame::{CanFrame, FrameId, EXTENDED_ID_MASK, MAX_FRAME_DATA};
+use crate::message_ref::MessageRef;
+use crate::transport::Transport;
+
+/// Interface name written to candump logs by default
+pub const DEFAULT_INTERFACE: &str = "can0";
+
+/// Text format of a CAN capture
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum CaptureFormat {
+    /// Log format of `candump -L`, e.g. `(1436509052.249713) can0 5A0#9000010002`
+    Candump,
+
+    /// Vector ASC format, e.g. `0.249713 1  5A0  Rx   d 5 90 00 01 00 02`
+    Asc,
+}
+
+/// A captured frame
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TimestampedFrame {
+    /// Time of the capture, absolute for candump logs and relative for ASC logs
+    pub timestamp: Duration,
+    pub frame: CanFrame,
+}
+
+impl TimestampedFrame {
+    /// Return the VLCB message carried by the frame, if any
+    ///
+    /// The opcode and the payload of the message are available through
+    /// [`MessageRef::opcode`] and [`MessageRef::payload`].
+    pub fn message_ref(&self) -> Option<MessageRef<'_>> {
+        self.frame.as_frame().ok()?.message_ref()
+    }
+}
+
+/// Reader of a CAN capture
+///
+/// Yields the captured frames in the order of the file. Lines which do not hold
+/// a frame, such as comments, ASC headers and error frames, are skipped. A frame
+/// line that can not be parsed is returned as [`io::ErrorKind::InvalidData`].
+#[derive(Debug)]
+pub struct CaptureReader<R> {
+    reader: R,
+    format: CaptureFormat,
+    line: String,
+    line_number: usize,
+}
+
+impl<R: BufRead> CaptureReader<R> {
+    /// Construct a reader of a capture in `format`
+    pub fn new(reader: R, format: CaptureFormat) -> Self {
+        Self {
+            reader,
+            format,
+            line: String::new(),
+            line_number: 0,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for CaptureReader<R> {
+    type Item = io::Result<TimestampedFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line.clear();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(err) => return Some(Err(err)),
+            }
+            self.line_number += 1;
+
+            let parsed = match self.format {
+                CaptureFormat::Candump => parse_candump(self.line.trim()),
+                CaptureFormat::Asc => parse_asc(self.line.trim()),
+            };
+            match parsed {
+                Ok(Some(frame)) => return Some(Ok(frame)),
+                Ok(None) => {}
+                Err(err) => {
+                    return Some(Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("line {}: {}", self.line_number, err),
+                    )))
+                }
+            }
+        }
+    }
+}
+
+/// Writer of a CAN capture
+///
+/// The ASC header is written with the first frame, call [`CaptureWriter::finish`]
+/// to close the ASC trigger block.
+#[derive(Debug)]
+pub struct CaptureWriter<W: Write> {
+    writer: W,
+    format: CaptureFormat,
+    interface: String,
+    started: bool,
+}
+
+impl<W: Write> CaptureWriter<W> {
+    /// Construct a writer of a capture in `format`
+    pub fn new(writer: W, format: CaptureFormat) -> Self {
+        Self {
+            writer,
+            format,
+            interface: DEFAULT_INTERFACE.into(),
+            started: false,
+        }
+    }
+
+    /// Set the interface name written to candump logs
+    pub fn interface(mut self, name: &str) -> Self {
+        self.interface = name.into();
+        self
+    }
+
+    /// Write a frame
+    pub fn write(&mut self, frame: &TimestampedFrame) -> io::Result<()> {
+        match self.format {
+            CaptureFormat::Candump => self.write_candump(frame),
+            CaptureFormat::Asc => self.write_asc(frame),
+        }
+    }
+
+    /// Complete the capture and return the underlying writer
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.format == CaptureFormat::Asc && self.started {
+            writeln!(self.writer, "End TriggerBlock")?;
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_candump(&mut self, frame: &TimestampedFrame) -> io::Result<()> {
+        write!(
+            self.writer,
+            "({}.{:06}) {} ",
+            frame.timestamp.as_secs(),
+            frame.timestamp.subsec_micros(),
+            self.interface
+        )?;
+        match frame.frame.id() {
+            FrameId::Standard(header) => write!(self.writer, "{:03X}#", header.to_standard_id())?,
+            FrameId::Extended(id) => write!(self.writer, "{:08X}#", id)?,
+        }
+        if frame.frame.is_remote() {
+            write!(self.writer, "R")?;
+        }
+        for byte in frame.frame.data() {
+            write!(self.writer, "{:02X}", byte)?;
+        }
+        writeln!(self.writer)
+    }
+
+    fn write_asc(&mut self, frame: &TimestampedFrame) -> io::Result<()> {
+        if !self.started {
+            writeln!(self.writer, "base hex  timestamps relative")?;
+            writeln!(self.writer, "no internal events logged")?;
+            writeln!(self.writer, "Begin TriggerBlock")?;
+            self.started = true;
+        }
+
+        write!(
+            self.writer,
+            "{:>4}.{:06} 1  ",
+            frame.timestamp.as_secs(),
+            frame.timestamp.subsec_micros()
+        )?;
+        match frame.frame.id() {
+            FrameId::Standard(header) => write!(
+                self.writer,
+                "{:<15} Rx   ",
+                format!("{:X}", header.to_standard_id())
+            )?,
+            FrameId::Extended(id) => write!(self.writer, "{:<15} Rx   ", format!("{:X}x", id))?,
+        }
+        if frame.frame.is_remote() {
+            return writeln!(self.writer, "r");
+        }
+        write!(self.writer, "d {}", frame.frame.data().len())?;
+        for byte in frame.frame.data() {
+            write!(self.writer, " {:02X}", byte)?;
+        }
+        writeln!(self.writer)
+    }
+}
+
+/// Re-emitter of a capture with its original or scaled timing
+///
+/// ```no_run
+/// use std::fs::File;
+/// use std::io::BufReader;
+/// use vlcb_defs::capture::{CaptureFormat, CaptureReader, Replayer};
+/// use vlcb_defs::tcp::GridConnectClient;
+///
+/// let capture = BufReader::new(File::open("layout.log")?);
+/// let client = GridConnectClient::connect(("localhost", vlcb_defs::tcp::DEFAULT_PORT))?;
+/// Replayer::new()
+///     .speed(2.0)
+///     .replay(CaptureReader::new(capture, CaptureFormat::Candump), client)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Replayer {
+    speed: f64,
+}
+
+impl Replayer {
+    /// Construct a replayer keeping the original timing
+    pub const fn new() -> Self {
+        Self { speed: 1.0 }
+    }
+
+    /// Scale the timing, a speed of 2 replays twice as fast
+    ///
+    /// A speed of [`f64::INFINITY`] sends the frames without any delay.
+    /// Speeds which are not positive are ignored.
+    pub fn speed(mut self, speed: f64) -> Self {
+        if speed > 0.0 {
+            self.speed = speed;
+        }
+        self
+    }
+
+    /// Send the frames of a capture to a transport
+    ///
+    /// Each frame is sent once the time elapsed since the first frame matches its
+    /// scaled timestamp. Frames with a timestamp earlier than their predecessor are
+    /// sent immediately. Returns the number of frames sent.
+    pub fn replay<I, T>(&self, frames: I, mut transport: T) -> io::Result<usize>
+    where
+        I: IntoIterator<Item = io::Result<TimestampedFrame>>,
+        T: Transport,
+    {
+        let mut start = None;
+        let mut count = 0;
+        for frame in frames {
+            let frame = frame?;
+            let (started, first) = *start.get_or_insert((Instant::now(), frame.timestamp));
+
+            let offset = frame.timestamp.saturating_sub(first);
+            let scaled = Duration::try_from_secs_f64(offset.as_secs_f64() / self.speed)
+                .unwrap_or(Duration::MAX);
+            if let Some(delay) = scaled.checked_sub(started.elapsed()) {
+                thread::sleep(delay);
+            }
+
+            transport.send(&frame.frame)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+impl Default for Replayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse a line of `candump -L`, e.g. `(1436509052.249713) can0 5A0#9000010002`
+fn parse_candump(line: &str) -> Result<Option<TimestampedFrame>, VlcbError> {
+    if !line.starts_with('(') {
+        return Ok(None);
+    }
+
+    let mut fields = line.split_whitespace();
+    let timestamp = fields
+        .next()
+        .and_then(|field| field.strip_prefix('('))
+        .and_then(|field| field.strip_suffix(')'))
+        .ok_or(VlcbError::MalformedFrame)?;
+    let _interface = fields.next().ok_or(VlcbError::MalformedFrame)?;
+    let frame = fields.next().ok_or(VlcbError::MalformedFrame)?;
+    let (id, data) = frame.split_once('#').ok_or(VlcbError::MalformedFrame)?;
+
+    let id = parse_id(id, id.len() == 8)?;
+    let frame = match data.strip_prefix('R') {
+        // the optional DLC of a remote frame is not kept
+        Some(_) => CanFrame::remote(id)?,
+        None => {
+            if data.len() % 2 != 0 || data.len() > 2 * MAX_FRAME_DATA {
+                return Err(VlcbError::MalformedFrame);
+            }
+            let mut bytes = [0; MAX_FRAME_DATA];
+            for (byte, pair) in bytes.iter_mut().zip(data.as_bytes().chunks_exact(2)) {
+                *byte = parse_byte(pair)?;
+            }
+            CanFrame::new(id, &bytes[..data.len() / 2])?
+        }
+    };
+
+    Ok(Some(TimestampedFrame {
+        timestamp: parse_timestamp(timestamp)?,
+        frame,
+    }))
+}
+
+/// Parse a line of an ASC log, e.g. `0.249713 1  5A0  Rx   d 5 90 00 01 00 02`
+fn parse_asc(line: &str) -> Result<Option<TimestampedFrame>, VlcbError> {
+    let mut fields = line.split_whitespace();
+    let (Some(timestamp), Some(channel), Some(id)) = (fields.next(), fields.next(), fields.next())
+    else {
+        return Ok(None);
+    };
+    let Ok(timestamp) = parse_timestamp(timestamp) else {
+        return Ok(None);
+    };
+    if channel.parse::<u8>().is_err() {
+        return Ok(None);
+    }
+    let (id, extended) = match id.strip_suffix(['x', 'X']) {
+        Some(id) => (id, true),
+        None => (id, false),
+    };
+    // error frames and events
+    let Ok(id) = parse_id(id, extended) else {
+        return Ok(None);
+    };
+
+    let _direction = fields.next().ok_or(VlcbError::MalformedFrame)?;
+    let frame = match fields.next() {
+        Some("r" | "R") => CanFrame::remote(id)?,
+        Some("d" | "D") => {
+            let len: usize = fields
+                .next()
+                .and_then(|len| len.parse().ok())
+                .ok_or(VlcbError::MalformedFrame)?;
+            if len > MAX_FRAME_DATA {
+                return Err(VlcbError::MalformedFrame);
+            }
+            let mut bytes = [0; MAX_FRAME_DATA];
+            for byte in &mut bytes[..len] {
+                let field = fields.next().ok_or(VlcbError::MalformedFrame)?;
+                *byte = parse_byte(field.as_bytes())?;
+            }
+            CanFrame::new(id, &bytes[..len])?
+        }
+        _ => return Err(VlcbError::MalformedFrame),
+    };
+
+    Ok(Some(TimestampedFrame { timestamp, frame }))
+}
+
+fn parse_id(id: &str, extended: bool) -> Result<FrameId, VlcbError> {
+    let raw = u32::from_str_radix(id, 16).map_err(|_| VlcbError::MalformedFrame)?;
+    if extended {
+        if raw & !EXTENDED_ID_MASK != 0 {
+            return Err(VlcbError::MalformedFrame);
+        }
+        return Ok(FrameId::Extended(raw));
+    }
+    let raw = u16::try_from(raw).map_err(|_| VlcbError::MalformedFrame)?;
+    Ok(FrameId::Standard(CanHeader::from_standard_id(raw)?))
+}
+
+fn parse_byte(digits: &[u8]) -> Result<u8, VlcbError> {
+    core::str::from_utf8(digits)
+        .ok()
+        .filter(|digits| digits.len() == 2)
+        .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+        .ok_or(VlcbError::MalformedFrame)
+}
+
+/// Parse seconds with up to 9 decimal places, without losing precision to floats
+fn parse_timestamp(text: &str) -> Result<Duration, VlcbError> {
+    let (secs, fraction) = text.split_once('.').unwrap_or((text, ""));
+    if fraction.len() > 9 || !fraction.bytes().all(|c| c.is_ascii_digit()) {
+        return Err(VlcbError::MalformedFrame);
+    }
+    let secs: u64 = secs.parse().map_err(|_| VlcbError::MalformedFrame)?;
+    let nanos = fraction
+        .bytes()
+        .chain(core::iter::repeat(b'0'))
+        .take(9)
+        .fold(0u32, |acc, c| acc * 10 + u32::from(c - b'0'));
+    Ok(Duration::new(secs, nanos))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::can::VlcbCanId;
+    use crate::frame::BOOTLOADER_CONTROL_ID;
+    use crate::OpCode;
+
+    const CANDUMP: &str = "\
+(1436509052.249713) can0 5A0#9000010002
+(1436509052.250000) can0 00000004#0102
+(1436509052.260000) can0 500#R
+(1436509052.270000) can0 500#
+";
+
+    const ASC: &str = "\
+date Sun Oct 18 07:00:00.000 am 2026
+base hex  timestamps relative
+no internal events logged
+Begin TriggerBlock
+   0.000000 1  5A0             Rx   d 5 90 00 01 00 02  Length = 0 BitCount = 0
+   0.000287 1  4x              Rx   d 2 01 02
+   0.010287 1  ErrorFrame
+   0.010287 1  500             Rx   r
+   0.020287 1  500             Rx   d 0
+End TriggerBlock
+";
+
+    fn read(text: &str, format: CaptureFormat) -> Vec<TimestampedFrame> {
+        CaptureReader::new(text.as_bytes(), format)
+            .collect::<io::Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_candump() {
+        let frames = read(CANDUMP, CaptureFormat::Candump);
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[0].timestamp, Duration::new(1436509052, 249_713_000));
+        let message = frames[0].message_ref().unwrap();
+        assert_eq!(message.opcode(), OpCode::LongEventAccessoryOn);
+        assert_eq!(message.payload(), &[0x00, 0x01, 0x00, 0x02]);
+        let FrameId::Standard(header) = frames[0].frame.id() else {
+            panic!("not a standard frame");
+        };
+        assert_eq!(header.can_id, VlcbCanId([0x20]));
+
+        assert_eq!(
+            frames[1].frame.id(),
+            FrameId::Extended(BOOTLOADER_CONTROL_ID)
+        );
+        assert_eq!(frames[1].message_ref(), None);
+        assert!(frames[2].frame.is_remote());
+        assert!(frames[3].frame.data().is_empty());
+
+        let mut writer = CaptureWriter::new(Vec::new(), CaptureFormat::Candump);
+        for frame in &frames {
+            writer.write(frame).unwrap();
+        }
+        let text = String::from_utf8(writer.finish().unwrap()).unwrap();
+        assert_eq!(text, CANDUMP);
+    }
+
+    #[test]
+    fn test_asc() {
+        let frames = read(ASC, CaptureFormat::Asc);
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[1].timestamp, Duration::from_micros(287));
+        assert_eq!(
+            frames[0].message_ref().unwrap().opcode(),
+            OpCode::LongEventAccessoryOn
+        );
+        assert_eq!(
+            frames[1].frame.id(),
+            FrameId::Extended(BOOTLOADER_CONTROL_ID)
+        );
+        assert!(frames[2].frame.is_remote());
+
+        let mut writer = CaptureWriter::new(Vec::new(), CaptureFormat::Asc);
+        for frame in &frames {
+            writer.write(frame).unwrap();
+        }
+        let text = String::from_utf8(writer.finish().unwrap()).unwrap();
+        assert_eq!(read(&text, CaptureFormat::Asc), frames);
+        assert!(text.starts_with("base hex  timestamps relative\n"));
+        assert!(text.ends_with("End TriggerBlock\n"));
+    }
+
+    #[test]
+    fn test_malformed() {
+        let mut reader = CaptureReader::new(
+            "# comment\n(1.0) can0 5A0#900\n(2.0) can0 5A0#02\n".as_bytes(),
+            CaptureFormat::Candump,
+        );
+        let err = reader.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "line 2: malformed frame");
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().is_none());
+
+        assert!(parse_asc("0.1 1 5A0 Rx d 9").is_err());
+        assert!(parse_asc("0.1 1 5A0 Rx d 2 01").is_err());
+        assert!(parse_candump("(1.0) can0 800#").is_err());
+        assert!(parse_candump("(1.0) can0 5A0##0102").is_err());
+        assert!(parse_timestamp("1.0123456789").is_err());
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        sent: Vec<(Instant, CanFrame)>,
+    }
+
+    impl Transport for Recorder {
+        fn send(&mut self, frame: &CanFrame) -> io::Result<()> {
+            self.sent.push((Instant::now(), *frame));
+            Ok(())
+        }
+
+        fn receive(&mut self, _timeout: Duration) -> io::Result<Option<CanFrame>> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn test_replay() {
+        let frames = read(CANDUMP, CaptureFormat::Candump);
+        let mut recorder = Recorder::default();
+        let start = Instant::now();
+        let count = Replayer::new()
+            .speed(0.0)
+            .speed(2.0)
+            .replay(frames.iter().copied().map(Ok), &mut recorder)
+            .unwrap();
+        assert_eq!(count, 4);
+        // the capture spans 20.287 ms
+        assert!(start.elapsed() >= Duration::from_micros(10_143));
+        let sent: Vec<_> = recorder.sent.iter().map(|(_, frame)| *frame).collect();
+        let captured: Vec<_> = frames.iter().map(|frame| frame.frame).collect();
+        assert_eq!(sent, captured);
+        assert!(recorder.sent[3].0 - recorder.sent[0].0 >= Duration::from_micros(10_143));
+
+        // an hour long capture is sent without waiting
+        let mut frames = frames;
+        frames[3].timestamp += Duration::from_secs(3600);
+        let mut recorder = Recorder::default();
+        let start = Instant::now();
+        Replayer::new()
+            .speed(f64::INFINITY)
+            .replay(frames.iter().copied().map(Ok), &mut recorder)
+            .unwrap();
+        assert_eq!(recorder.sent.len(), 4);
+        assert!(start.elapsed() < Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,43 @@
+/// Monotonic time source with millisecond resolution
+///
+/// Implemented for closures returning the current time, so any hardware timer
+/// can be plugged in, e.g. `|| timer.millis()`.
+pub trait Clock {
+    /// Return the current time in milliseconds since an arbitrary epoch
+    ///
+    /// The time must never go backwards.
+    fn now_ms(&self) -> u64;
+}
+
+impl<F: Fn() -> u64> Clock for F {
+    fn now_ms(&self) -> u64 {
+        self()
+    }
+}
+
+/// Clock backed by [`std::time::Instant`], counting from its construction
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy)]
+pub struct StdClock(std::time::Instant);
+
+#[cfg(feature = "std")]
+impl StdClock {
+    /// Construct a clock starting at 0 ms
+    pub fn new() -> Self {
+        Self(std::time::Instant::now())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for StdClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for StdClock {
+    fn now_ms(&self) -> u64 {
+        self.0.elapsed().as_millis() as u64
+    }
+}
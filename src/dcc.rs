@@ -1,6 +1,10 @@
 use byteorder::{ByteOrder, NetworkEndian};
 use num_enum::{FromPrimitive, IntoPrimitive, TryFromPrimitive};
 
+use crate::error::VlcbError;
+
+/// DCC locomotive address
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct LocoAddress([u8;2], bool);
 
 impl LocoAddress {
@@ -19,6 +23,36 @@ impl LocoAddress {
         s
     }
 
+    /// Constructs DCC locomotive address from two octets in big endian
+    /// as found in CBUS packets
+    ///
+    /// Addresses with most significant octet set to 0 are treated as 7 bit addresses,
+    /// everything else is treated as a 14 bit address with bits 6,7 stripped.
+    pub fn from_bytes_sanitized(bytes: [u8; 2]) -> Self {
+        if bytes[0] == 0 {
+            Self::new(bytes[1])
+        } else {
+            Self([bytes[0] & 0x3F, bytes[1]], true)
+        }
+    }
+
+    /// Constructs DCC locomotive address from two octets in big endian
+    /// as found in CBUS packets
+    ///
+    /// Addresses with most significant octet set to 0 are 7 bit addresses,
+    /// 14 bit addresses need bits 6,7 of the most significant octet set.
+    ///
+    /// Returns [`VlcbError::ReservedBitsSet`] for any other most significant octet.
+    pub fn from_bytes(bytes: [u8; 2]) -> Result<Self, VlcbError> {
+        if bytes[0] != 0 && bytes[0] & 0xC0 != 0xC0 {
+            return Err(VlcbError::ReservedBitsSet {
+                type_name: "LocoAddress",
+                value: bytes[0],
+            });
+        }
+        Ok(Self::from_bytes_sanitized(bytes))
+    }
+
     /// Get the address type
     ///
     /// Returns true when the address is 14 bits long
@@ -0,0 +1,481 @@
+use heapless::Deque;
+
+use crate::builder::Message;
+use crate::can::{CanHeader, VlcbCanId, CANID_MASK, CANID_MAX, CANID_MIN};
+use crate::clock::Clock;
+use crate::frame::VlcbFrame;
+use crate::vlcb::VlcbNodeNumber;
+use crate::{CommandError, GenericResponseStatus, OpCode, ServiceType};
+
+/// Time in milliseconds to collect the responses to the enumeration RTR frame
+pub const ENUMERATION_TIMEOUT_MS: u64 = 100;
+
+/// Number of actions the enumerator buffers between calls to [`CanIdEnumerator::poll`]
+const ACTION_QUEUE_SIZE: usize = 8;
+
+/// Action requested by the [`CanIdEnumerator`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CanIdAction {
+    /// Send an RTR frame to start the enumeration
+    SendRemote(CanHeader),
+
+    /// Send a data frame without data, answering an RTR frame of another node
+    SendEmpty(CanHeader),
+
+    /// Send a message
+    SendMessage(CanHeader, Message),
+
+    /// The node now uses a new CAN ID
+    CanIdChanged(VlcbCanId),
+}
+
+/// Detects another node transmitting with the CAN ID of this node
+///
+/// The specification requires a node to re-enumerate when it detects a conflict.
+/// The monitor fires once per conflict, it is re-armed when the CAN ID is updated
+/// with [`CanIdMonitor::set_can_id`], usually after the enumeration, or with
+/// [`CanIdMonitor::rearm`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CanIdMonitor {
+    can_id: VlcbCanId,
+    triggered: bool,
+    conflicts: u16,
+}
+
+impl CanIdMonitor {
+    /// Construct a monitor for the CAN ID of this node
+    pub const fn new(can_id: VlcbCanId) -> Self {
+        Self {
+            can_id,
+            triggered: false,
+            conflicts: 0,
+        }
+    }
+
+    /// Return the monitored CAN ID
+    pub const fn can_id(&self) -> VlcbCanId {
+        self.can_id
+    }
+
+    /// Update the monitored CAN ID and re-arm the monitor
+    pub fn set_can_id(&mut self, can_id: VlcbCanId) {
+        self.can_id = can_id;
+        self.triggered = false;
+    }
+
+    /// Re-arm the monitor without changing the CAN ID
+    pub fn rearm(&mut self) {
+        self.triggered = false;
+    }
+
+    /// Number of frames received with the CAN ID of this node
+    ///
+    /// Saturates at `u16::MAX`.
+    pub const fn conflicts(&self) -> u16 {
+        self.conflicts
+    }
+
+    /// Check a received frame
+    ///
+    /// Returns `true` if the frame uses the CAN ID of this node and the
+    /// re-enumeration has to be started.
+    pub fn on_frame(&mut self, frame: &VlcbFrame<'_>) -> bool {
+        match frame.header() {
+            Some(header) if header.can_id == self.can_id => {
+                self.conflicts = self.conflicts.saturating_add(1);
+                !core::mem::replace(&mut self.triggered, true)
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum State {
+    Idle,
+    Enumerating {
+        deadline_ms: u64,
+        /// The enumeration was requested by [`OpCode::ForceCanEnumeration`]
+        requested: bool,
+    },
+}
+
+/// CAN ID self-enumeration state machine
+///
+/// The enumerator does not do any I/O. Received frames are passed to
+/// [`CanIdEnumerator::on_frame`] and the frames to send are collected with
+/// [`CanIdEnumerator::poll`], which also finishes the enumeration once
+/// [`ENUMERATION_TIMEOUT_MS`] has passed. `poll` should be called after
+/// each received frame and periodically while enumerating.
+///
+/// An enumeration is started by [`OpCode::ForceCanEnumeration`], by
+/// [`CanIdEnumerator::start`], or when the [`CanIdMonitor`] detects another node
+/// using the same CAN ID.
+/// The node picks the lowest CAN ID in [`CANID_MIN`]..=[`CANID_MAX`] not seen
+/// in any frame during the enumeration. The ENUM description allows any CAN ID in
+/// 1..127, the range is narrower because the CBUS Developer's Guide reserves
+/// [`FIXED_CANID_RANGE`] for modules with a fixed CAN ID, which may not have sent
+/// a frame yet. If no CAN ID is free, the CAN ID is kept and the failure is reported
+/// with [`OpCode::NodeConfigurationError`] (CMDERR) error 7, as the ENUM description
+/// requires.
+///
+/// [`OpCode::SetNodeCanId`] is handled too, the new CAN ID has to be in the same range.
+/// An invalid CAN ID is rejected with [`OpCode::GenericResponse`]
+/// ([`GenericResponseStatus::InvalidCommandParameter`]).
+///
+/// [`FIXED_CANID_RANGE`]: crate::can::FIXED_CANID_RANGE
+#[derive(Debug, Clone)]
+pub struct CanIdEnumerator {
+    node_number: VlcbNodeNumber,
+    monitor: CanIdMonitor,
+    state: State,
+    taken: u128,
+    actions: Deque<CanIdAction, ACTION_QUEUE_SIZE>,
+}
+
+impl CanIdEnumerator {
+    /// Construct an idle enumerator for a node
+    pub fn new(node_number: VlcbNodeNumber, can_id: VlcbCanId) -> Self {
+        Self {
+            node_number,
+            monitor: CanIdMonitor::new(can_id),
+            state: State::Idle,
+            taken: 0,
+            actions: Deque::new(),
+        }
+    }
+
+    /// Return the current CAN ID of the node
+    pub fn can_id(&self) -> VlcbCanId {
+        self.monitor.can_id()
+    }
+
+    /// Return the CAN ID conflict monitor
+    pub fn monitor(&self) -> &CanIdMonitor {
+        &self.monitor
+    }
+
+    /// Update the node number after it has changed
+    pub fn set_node_number(&mut self, node_number: VlcbNodeNumber) {
+        self.node_number = node_number;
+    }
+
+    /// Check whether an enumeration is in progress
+    pub fn is_enumerating(&self) -> bool {
+        matches!(self.state, State::Enumerating { .. })
+    }
+
+    /// Start an enumeration
+    ///
+    /// Does nothing if an enumeration is already in progress.
+    pub fn start(&mut self, clock: &impl Clock) {
+        self.begin(clock, false);
+    }
+
+    /// Process a received frame
+    pub fn on_frame(&mut self, clock: &impl Clock, frame: &VlcbFrame<'_>) {
+        let Some(header) = frame.header() else {
+            return;
+        };
+
+        if self.monitor.on_frame(frame) {
+            self.begin(clock, false);
+        }
+        if self.is_enumerating() {
+            self.mark_taken(header.can_id);
+        }
+
+        match *frame {
+            VlcbFrame::Remote { .. } => {
+                self.push(CanIdAction::SendEmpty(CanHeader::new(
+                    Default::default(),
+                    Default::default(),
+                    self.can_id(),
+                )));
+            }
+            VlcbFrame::Message { message, .. } => {
+                if message.node_number() != Some(self.node_number) {
+                    return;
+                }
+                match message.opcode() {
+                    OpCode::ForceCanEnumeration => self.begin(clock, true),
+                    OpCode::SetNodeCanId => self.set_can_id(message.payload()[2]),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Return the next action to perform
+    ///
+    /// Finishes the enumeration when its time is up.
+    pub fn poll(&mut self, clock: &impl Clock) -> Option<CanIdAction> {
+        if let State::Enumerating {
+            deadline_ms,
+            requested,
+        } = self.state
+        {
+            if clock.now_ms() >= deadline_ms {
+                self.state = State::Idle;
+                self.finish(requested);
+            }
+        }
+
+        self.actions.pop_front()
+    }
+
+    fn begin(&mut self, clock: &impl Clock, requested: bool) {
+        if self.is_enumerating() {
+            return;
+        }
+
+        self.taken = 0;
+        self.state = State::Enumerating {
+            deadline_ms: clock.now_ms().saturating_add(ENUMERATION_TIMEOUT_MS),
+            requested,
+        };
+        self.push(CanIdAction::SendRemote(CanHeader::new(
+            Default::default(),
+            Default::default(),
+            self.can_id(),
+        )));
+    }
+
+    fn finish(&mut self, requested: bool) {
+        let free = (CANID_MIN..=CANID_MAX).find(|&id| self.taken & (1 << id) == 0);
+        match free {
+            Some(id) => {
+                self.change_can_id(VlcbCanId([id]));
+                if requested {
+                    self.respond(OpCode::ForceCanEnumeration, GenericResponseStatus::Ok);
+                }
+            }
+            None => {
+                self.monitor.rearm();
+                // CMDERR(7) is the error required by the ENUM description
+                let msg = Message::cmderr(self.node_number, CommandError::InvalidEvent);
+                self.push(CanIdAction::SendMessage(
+                    CanHeader::for_opcode(self.can_id(), msg.opcode()),
+                    msg,
+                ));
+            }
+        }
+    }
+
+    fn set_can_id(&mut self, id: u8) {
+        match VlcbCanId::new(id) {
+            Ok(can_id) => {
+                self.change_can_id(can_id);
+                self.respond(OpCode::SetNodeCanId, GenericResponseStatus::Ok);
+            }
+            Err(_) => self.respond(
+                OpCode::SetNodeCanId,
+                GenericResponseStatus::InvalidCommandParameter,
+            ),
+        }
+    }
+
+    fn change_can_id(&mut self, can_id: VlcbCanId) {
+        self.monitor.set_can_id(can_id);
+        self.push(CanIdAction::CanIdChanged(can_id));
+    }
+
+    fn respond(&mut self, opcode: OpCode, status: GenericResponseStatus) {
+        let msg = Message::grsp(self.node_number, opcode, ServiceType::CanBus, status);
+        self.push(CanIdAction::SendMessage(
+            CanHeader::for_opcode(self.can_id(), msg.opcode()),
+            msg,
+        ));
+    }
+
+    fn mark_taken(&mut self, can_id: VlcbCanId) {
+        self.taken |= 1 << (can_id.0[0] & CANID_MASK);
+    }
+
+    /// Queue an action, the oldest one is dropped when the queue is full
+    fn push(&mut self, action: CanIdAction) {
+        if self.actions.is_full() {
+            self.actions.pop_front();
+        }
+        let _ = self.actions.push_back(action);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::cell::Cell;
+
+    const NN: VlcbNodeNumber = VlcbNodeNumber::new(0x01, 0x02);
+
+    fn header(can_id: u8) -> CanHeader {
+        CanHeader::new(Default::default(), Default::default(), VlcbCanId([can_id]))
+    }
+
+    fn drain(
+        enumerator: &mut CanIdEnumerator,
+        clock: &impl Clock,
+    ) -> heapless::Vec<CanIdAction, 8> {
+        let mut actions = heapless::Vec::new();
+        while let Some(action) = enumerator.poll(clock) {
+            actions.push(action).unwrap();
+        }
+        actions
+    }
+
+    #[test]
+    fn test_enum_command() {
+        let now = Cell::new(0u64);
+        let clock = || now.get();
+        let mut enumerator = CanIdEnumerator::new(NN, VlcbCanId([5]));
+
+        let enum_msg = Message::builder(OpCode::ForceCanEnumeration)
+            .node_number(NN)
+            .build()
+            .unwrap();
+        let frame = VlcbFrame::message(header(10), enum_msg.as_bytes()).unwrap();
+        enumerator.on_frame(&clock, &frame);
+        assert_eq!(
+            drain(&mut enumerator, &clock),
+            [CanIdAction::SendRemote(header(5))]
+        );
+
+        for id in [1, 2, 4] {
+            enumerator.on_frame(&clock, &VlcbFrame::Empty { header: header(id) });
+        }
+        now.set(99);
+        assert!(drain(&mut enumerator, &clock).is_empty());
+
+        now.set(100);
+        let actions = drain(&mut enumerator, &clock);
+        assert_eq!(actions[0], CanIdAction::CanIdChanged(VlcbCanId([3])));
+        assert_eq!(
+            actions[1],
+            CanIdAction::SendMessage(
+                CanHeader::for_opcode(VlcbCanId([3]), OpCode::GenericResponse),
+                Message::grsp(
+                    NN,
+                    OpCode::ForceCanEnumeration,
+                    ServiceType::CanBus,
+                    GenericResponseStatus::Ok
+                )
+            )
+        );
+        assert_eq!(enumerator.can_id(), VlcbCanId([3]));
+        assert!(!enumerator.is_enumerating());
+    }
+
+    #[test]
+    fn test_conflict_and_rtr() {
+        let now = Cell::new(0u64);
+        let clock = || now.get();
+        let mut enumerator = CanIdEnumerator::new(NN, VlcbCanId([1]));
+
+        enumerator.on_frame(&clock, &VlcbFrame::Remote { header: header(7) });
+        assert_eq!(
+            drain(&mut enumerator, &clock),
+            [CanIdAction::SendEmpty(header(1))]
+        );
+
+        let frame = VlcbFrame::message(header(1), &[0x02]).unwrap();
+        enumerator.on_frame(&clock, &frame);
+        assert_eq!(
+            drain(&mut enumerator, &clock),
+            [CanIdAction::SendRemote(header(1))]
+        );
+
+        now.set(100);
+        assert_eq!(
+            drain(&mut enumerator, &clock),
+            [CanIdAction::CanIdChanged(VlcbCanId([2]))]
+        );
+    }
+
+    #[test]
+    fn test_monitor() {
+        let mut monitor = CanIdMonitor::new(VlcbCanId([3]));
+        assert!(!monitor.on_frame(&VlcbFrame::Empty { header: header(4) }));
+        assert!(monitor.on_frame(&VlcbFrame::Remote { header: header(3) }));
+        assert!(!monitor.on_frame(&VlcbFrame::Empty { header: header(3) }));
+        assert_eq!(monitor.conflicts(), 2);
+
+        monitor.rearm();
+        assert!(monitor.on_frame(&VlcbFrame::Empty { header: header(3) }));
+        monitor.set_can_id(VlcbCanId([4]));
+        assert!(monitor.on_frame(&VlcbFrame::Empty { header: header(4) }));
+        assert!(!monitor.on_frame(&VlcbFrame::Extended { id: 3, data: &[] }));
+    }
+
+    #[test]
+    fn test_enumeration_failure() {
+        let now = Cell::new(0u64);
+        let clock = || now.get();
+        let mut enumerator = CanIdEnumerator::new(NN, VlcbCanId([5]));
+
+        enumerator.start(&clock);
+        for id in CANID_MIN..=CANID_MAX {
+            enumerator.on_frame(&clock, &VlcbFrame::Empty { header: header(id) });
+            enumerator.poll(&clock);
+        }
+        now.set(100);
+        assert_eq!(
+            drain(&mut enumerator, &clock),
+            [CanIdAction::SendMessage(
+                CanHeader::for_opcode(VlcbCanId([5]), OpCode::NodeConfigurationError),
+                Message::cmderr(NN, CommandError::InvalidEvent)
+            )]
+        );
+        assert_eq!(enumerator.can_id(), VlcbCanId([5]));
+    }
+
+    #[test]
+    fn test_set_can_id() {
+        let clock = || 0;
+        let mut enumerator = CanIdEnumerator::new(NN, VlcbCanId([5]));
+
+        let msg = Message::builder(OpCode::SetNodeCanId)
+            .node_number(NN)
+            .u8(42)
+            .build()
+            .unwrap();
+        let frame = VlcbFrame::message(header(10), msg.as_bytes()).unwrap();
+        enumerator.on_frame(&clock, &frame);
+        let actions = drain(&mut enumerator, &clock);
+        assert_eq!(actions[0], CanIdAction::CanIdChanged(VlcbCanId([42])));
+        assert_eq!(enumerator.can_id(), VlcbCanId([42]));
+
+        let msg = Message::builder(OpCode::SetNodeCanId)
+            .node_number(NN)
+            .u8(120)
+            .build()
+            .unwrap();
+        let frame = VlcbFrame::message(header(10), msg.as_bytes()).unwrap();
+        enumerator.on_frame(&clock, &frame);
+        assert_eq!(
+            drain(&mut enumerator, &clock),
+            [CanIdAction::SendMessage(
+                CanHeader::for_opcode(VlcbCanId([42]), OpCode::GenericResponse),
+                Message::grsp(
+                    NN,
+                    OpCode::SetNodeCanId,
+                    ServiceType::CanBus,
+                    GenericResponseStatus::InvalidCommandParameter
+                )
+            )]
+        );
+        assert_eq!(enumerator.can_id(), VlcbCanId([42]));
+
+        // addressed to another node
+        let msg = Message::builder(OpCode::SetNodeCanId)
+            .node_number(VlcbNodeNumber::new(0, 9))
+            .u8(7)
+            .build()
+            .unwrap();
+        let frame = VlcbFrame::message(header(10), msg.as_bytes()).unwrap();
+        enumerator.on_frame(&clock, &frame);
+        assert!(drain(&mut enumerator, &clock).is_empty());
+    }
+}
@@ -0,0 +1,130 @@
+use core::fmt;
+
+use num_enum::{TryFromPrimitive, TryFromPrimitiveError};
+
+use crate::OpCode;
+
+/// Error returned by the fallible operations of the library
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum VlcbError {
+    /// The opcode is not known to the library
+    UnknownOpcode(u8),
+
+    /// The opcode is valid, but not expected by the operation
+    UnexpectedOpcode(OpCode),
+
+    /// The length of the data does not match the expected length
+    ///
+    /// For messages the expected length is the length defined by the opcode,
+    /// for buffers it is the length needed to hold the data.
+    WrongLength { expected: usize, actual: usize },
+
+    /// A value does not match any variant of the enum `type_name`
+    InvalidEnumValue { type_name: &'static str, value: u8 },
+
+    /// A value of `type_name` has bits set which are reserved by the specification
+    ReservedBitsSet { type_name: &'static str, value: u8 },
+
+    /// A value of `type_name` is reserved by the specification
+    ReservedValue { type_name: &'static str, value: u8 },
+
+    /// A fixed capacity container is full
+    CapacityExceeded,
+
+    /// The text representation of a frame is malformed
+    MalformedFrame,
+}
+
+/// Error returned when a VLCB message could not be decoded
+///
+/// This is the same type as [`VlcbError`], the alias only names the intent
+/// in decoding APIs.
+pub type DecodeError = VlcbError;
+
+impl fmt::Display for VlcbError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownOpcode(opcode) => write!(f, "unknown opcode 0x{:02X}", opcode),
+            Self::UnexpectedOpcode(opcode) => write!(f, "unexpected opcode {}", opcode),
+            Self::WrongLength { expected, actual } => write!(
+                f,
+                "wrong length, expected {} octets, got {}",
+                expected, actual
+            ),
+            Self::InvalidEnumValue { type_name, value } => {
+                write!(f, "invalid {} value {}", type_name, value)
+            }
+            Self::ReservedBitsSet { type_name, value } => {
+                write!(
+                    f,
+                    "reserved bits set in {} value 0x{:02X}",
+                    type_name, value
+                )
+            }
+            Self::ReservedValue { type_name, value } => {
+                write!(f, "reserved {} value {}", type_name, value)
+            }
+            Self::CapacityExceeded => f.write_str("capacity exceeded"),
+            Self::MalformedFrame => f.write_str("malformed frame"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VlcbError {}
+
+impl<T: TryFromPrimitive<Primitive = u8>> From<TryFromPrimitiveError<T>> for VlcbError {
+    fn from(err: TryFromPrimitiveError<T>) -> Self {
+        Self::InvalidEnumValue {
+            type_name: T::NAME,
+            value: err.number,
+        }
+    }
+}
+
+/// Check that `data` is exactly `expected` octets long
+pub(crate) fn check_len(data: &[u8], expected: usize) -> Result<(), VlcbError> {
+    if data.len() != expected {
+        return Err(VlcbError::WrongLength {
+            expected,
+            actual: data.len(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ServiceType;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            VlcbError::UnknownOpcode(0x0B).to_string(),
+            "unknown opcode 0x0B"
+        );
+        assert_eq!(
+            VlcbError::WrongLength {
+                expected: 5,
+                actual: 3
+            }
+            .to_string(),
+            "wrong length, expected 5 octets, got 3"
+        );
+    }
+
+    #[test]
+    fn test_from_primitive_error() {
+        let err: VlcbError = ServiceType::try_from(0xF0u8).unwrap_err().into();
+        assert_eq!(
+            err,
+            VlcbError::InvalidEnumValue {
+                type_name: "ServiceType",
+                value: 0xF0
+            }
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use heapless::Vec;
+
+use crate::builder::Message;
+use crate::error::VlcbError;
+use crate::message::{VlcbMessage, MAX_MESSAGE_SIZE};
+use crate::message_ref::MessageRef;
+use crate::vlcb::{EventId, EventType, EVENT_SIZE};
+use crate::OpCode;
+
+/// Maximum number of data octets following the event in an event message
+pub const EVENT_DATA_SIZE: usize = 3;
+
+/// An accessory event message of any of the accessory event opcodes
+///
+/// Covers the long and short variants of ACON, ACOF, ARON, AROF, ASON, ASOF, ARSON and ARSOF
+/// with zero to three data octets, and the AREQ and ASRQ status requests.
+/// The opcode is derived from [`EventId::is_short`], [`EventMessage::kind`] and the length
+/// of [`EventMessage::data`].
+///
+/// Short events are decoded with [`EventId::short_from_array`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EventMessage {
+    pub event: EventId,
+    pub kind: EventType,
+    pub data: Vec<u8, EVENT_DATA_SIZE>,
+}
+
+impl EventMessage {
+    /// Construct an event message without data
+    pub fn new(event: EventId, kind: EventType) -> Self {
+        Self {
+            event,
+            kind,
+            data: Vec::new(),
+        }
+    }
+
+    /// Construct an event message with data
+    ///
+    /// Returns [`VlcbError::WrongLength`] if `data` is longer than [`EVENT_DATA_SIZE`].
+    pub fn with_data(event: EventId, kind: EventType, data: &[u8]) -> Result<Self, VlcbError> {
+        let data = Vec::from_slice(data).map_err(|_| VlcbError::WrongLength {
+            expected: EVENT_DATA_SIZE,
+            actual: data.len(),
+        })?;
+        Ok(Self { event, kind, data })
+    }
+
+    /// Return the opcode of the message
+    ///
+    /// Returns `None` for [`EventType::Unknown`] and for status requests carrying data,
+    /// as there is no opcode for those.
+    pub fn opcode(&self) -> Option<OpCode> {
+        let opcode = match (self.event.is_short(), self.kind, self.data.len()) {
+            (false, EventType::AccessoryOn, 0) => OpCode::LongEventAccessoryOn,
+            (false, EventType::AccessoryOn, 1) => OpCode::LongEventAccessoryOn1,
+            (false, EventType::AccessoryOn, 2) => OpCode::LongEventAccessoryOn2,
+            (false, EventType::AccessoryOn, 3) => OpCode::LongEventAccessoryOn3,
+            (false, EventType::AccessoryOff, 0) => OpCode::LongEventAccessoryOff,
+            (false, EventType::AccessoryOff, 1) => OpCode::LongEventAccessoryOff1,
+            (false, EventType::AccessoryOff, 2) => OpCode::LongEventAccessoryOff2,
+            (false, EventType::AccessoryOff, 3) => OpCode::LongEventAccessoryOff3,
+            (false, EventType::AccessoryStatusOn, 0) => OpCode::LongEventAccessoryStateOn,
+            (false, EventType::AccessoryStatusOn, 1) => OpCode::LongEventAccessoryStateOn1,
+            (false, EventType::AccessoryStatusOn, 2) => OpCode::LongEventAccessoryStateOn2,
+            (false, EventType::AccessoryStatusOn, 3) => OpCode::LongEventAccessoryStateOn3,
+            (false, EventType::AccessoryStatusOff, 0) => OpCode::LongEventAccessoryStateOff,
+            (false, EventType::AccessoryStatusOff, 1) => OpCode::LongEventAccessoryStateOff1,
+            (false, EventType::AccessoryStatusOff, 2) => OpCode::LongEventAccessoryStateOff2,
+            (false, EventType::AccessoryStatusOff, 3) => OpCode::LongEventAccessoryStateOff3,
+            (true, EventType::AccessoryOn, 0) => OpCode::ShortEventAccessoryOn,
+            (true, EventType::AccessoryOn, 1) => OpCode::ShortEventAccessoryOn1,
+            (true, EventType::AccessoryOn, 2) => OpCode::ShortEventAccessoryOn2,
+            (true, EventType::AccessoryOn, 3) => OpCode::ShortEventAccessoryOn3,
+            (true, EventType::AccessoryOff, 0) => OpCode::ShortEventAccessoryOff,
+            (true, EventType::AccessoryOff, 1) => OpCode::ShortEventAccessoryOff1,
+            (true, EventType::AccessoryOff, 2) => OpCode::ShortEventAccessoryOff2,
+            (true, EventType::AccessoryOff, 3) => OpCode::ShortEventAccessoryOff3,
+            (true, EventType::AccessoryStatusOn, 0) => OpCode::ShortEventAccessoryStateOn,
+            (true, EventType::AccessoryStatusOn, 1) => OpCode::ShortEventAccessoryStateOn1,
+            (true, EventType::AccessoryStatusOn, 2) => OpCode::ShortEventAccessoryStateOn2,
+            (true, EventType::AccessoryStatusOn, 3) => OpCode::ShortEventAccessoryStateOn3,
+            (true, EventType::AccessoryStatusOff, 0) => OpCode::ShortEventAccessoryStateOff,
+            (true, EventType::AccessoryStatusOff, 1) => OpCode::ShortEventAccessoryStateOff1,
+            (true, EventType::AccessoryStatusOff, 2) => OpCode::ShortEventAccessoryStateOff2,
+            (true, EventType::AccessoryStatusOff, 3) => OpCode::ShortEventAccessoryStateOff3,
+            (false, EventType::AccessoryStatusRequest, 0) => OpCode::QueryLongEventAccessoryState,
+            (true, EventType::AccessoryStatusRequest, 0) => OpCode::QueryShortEventAccessoryState,
+            _ => return None,
+        };
+        Some(opcode)
+    }
+
+    /// Check whether an opcode is one of the accessory event opcodes
+    pub fn is_event_opcode(opcode: OpCode) -> bool {
+        Self::classify(opcode).is_some()
+    }
+
+    /// Decode an event message from a received message, starting with the opcode
+    ///
+    /// Returns [`VlcbError::UnexpectedOpcode`] if the opcode is not an accessory event opcode.
+    pub fn decode(data: &[u8]) -> Result<Self, VlcbError> {
+        Self::try_from(MessageRef::new(data)?)
+    }
+
+    /// Encode the event message
+    ///
+    /// Returns `None` if there is no opcode for the message, see [`EventMessage::opcode`].
+    pub fn encode(&self) -> Option<Message> {
+        let msg = Message::builder(self.opcode()?)
+            .event(&self.event)
+            .bytes(&self.data)
+            .build();
+        msg.ok()
+    }
+
+    /// Convert the event message into its typed [`VlcbMessage`] form
+    ///
+    /// Returns `None` if there is no opcode for the message, see [`EventMessage::opcode`].
+    pub fn to_message(&self) -> Option<VlcbMessage> {
+        self.encode()?.to_message().ok()
+    }
+
+    fn classify(opcode: OpCode) -> Option<(bool, EventType)> {
+        let kind = match opcode {
+            OpCode::LongEventAccessoryOn => (false, EventType::AccessoryOn),
+            OpCode::LongEventAccessoryOn1 => (false, EventType::AccessoryOn),
+            OpCode::LongEventAccessoryOn2 => (false, EventType::AccessoryOn),
+            OpCode::LongEventAccessoryOn3 => (false, EventType::AccessoryOn),
+            OpCode::LongEventAccessoryOff => (false, EventType::AccessoryOff),
+            OpCode::LongEventAccessoryOff1 => (false, EventType::AccessoryOff),
+            OpCode::LongEventAccessoryOff2 => (false, EventType::AccessoryOff),
+            OpCode::LongEventAccessoryOff3 => (false, EventType::AccessoryOff),
+            OpCode::LongEventAccessoryStateOn => (false, EventType::AccessoryStatusOn),
+            OpCode::LongEventAccessoryStateOn1 => (false, EventType::AccessoryStatusOn),
+            OpCode::LongEventAccessoryStateOn2 => (false, EventType::AccessoryStatusOn),
+            OpCode::LongEventAccessoryStateOn3 => (false, EventType::AccessoryStatusOn),
+            OpCode::LongEventAccessoryStateOff => (false, EventType::AccessoryStatusOff),
+            OpCode::LongEventAccessoryStateOff1 => (false, EventType::AccessoryStatusOff),
+            OpCode::LongEventAccessoryStateOff2 => (false, EventType::AccessoryStatusOff),
+            OpCode::LongEventAccessoryStateOff3 => (false, EventType::AccessoryStatusOff),
+            OpCode::ShortEventAccessoryOn => (true, EventType::AccessoryOn),
+            OpCode::ShortEventAccessoryOn1 => (true, EventType::AccessoryOn),
+            OpCode::ShortEventAccessoryOn2 => (true, EventType::AccessoryOn),
+            OpCode::ShortEventAccessoryOn3 => (true, EventType::AccessoryOn),
+            OpCode::ShortEventAccessoryOff => (true, EventType::AccessoryOff),
+            OpCode::ShortEventAccessoryOff1 => (true, EventType::AccessoryOff),
+            OpCode::ShortEventAccessoryOff2 => (true, EventType::AccessoryOff),
+            OpCode::ShortEventAccessoryOff3 => (true, EventType::AccessoryOff),
+            OpCode::ShortEventAccessoryStateOn => (true, EventType::AccessoryStatusOn),
+            OpCode::ShortEventAccessoryStateOn1 => (true, EventType::AccessoryStatusOn),
+            OpCode::ShortEventAccessoryStateOn2 => (true, EventType::AccessoryStatusOn),
+            OpCode::ShortEventAccessoryStateOn3 => (true, EventType::AccessoryStatusOn),
+            OpCode::ShortEventAccessoryStateOff => (true, EventType::AccessoryStatusOff),
+            OpCode::ShortEventAccessoryStateOff1 => (true, EventType::AccessoryStatusOff),
+            OpCode::ShortEventAccessoryStateOff2 => (true, EventType::AccessoryStatusOff),
+            OpCode::ShortEventAccessoryStateOff3 => (true, EventType::AccessoryStatusOff),
+            OpCode::QueryLongEventAccessoryState => (false, EventType::AccessoryStatusRequest),
+            OpCode::QueryShortEventAccessoryState => (true, EventType::AccessoryStatusRequest),
+            _ => return None,
+        };
+        Some(kind)
+    }
+}
+
+impl TryFrom<MessageRef<'_>> for EventMessage {
+    type Error = VlcbError;
+
+    fn try_from(msg: MessageRef<'_>) -> Result<Self, Self::Error> {
+        let (short, kind) =
+            Self::classify(msg.opcode()).ok_or(VlcbError::UnexpectedOpcode(msg.opcode()))?;
+        let (event, data) = msg.payload().split_at(EVENT_SIZE);
+        let event = if short {
+            EventId::short_from_bytes(event)?
+        } else {
+            EventId::from_bytes(event)?
+        };
+        Self::with_data(event, kind, data)
+    }
+}
+
+impl TryFrom<&VlcbMessage> for EventMessage {
+    type Error = VlcbError;
+
+    fn try_from(msg: &VlcbMessage) -> Result<Self, Self::Error> {
+        if !Self::is_event_opcode(msg.opcode()) {
+            return Err(VlcbError::UnexpectedOpcode(msg.opcode()));
+        }
+        let mut buf = [0; MAX_MESSAGE_SIZE];
+        let len = msg.encode(&mut buf);
+        Self::decode(&buf[..len])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_all_event_opcodes() {
+        let mut count = 0;
+        for op in 0..=u8::MAX {
+            let Ok(opcode) = OpCode::try_from(op) else {
+                continue;
+            };
+            let Some((short, kind)) = EventMessage::classify(opcode) else {
+                continue;
+            };
+            count += 1;
+
+            let mut frame = [0xA5; 8];
+            frame[0] = op;
+            let frame = &frame[..opcode.frame_len()];
+            let msg = EventMessage::decode(frame).unwrap();
+            assert_eq!(msg.kind, kind);
+            assert_eq!(msg.event.is_short(), short);
+            assert_eq!(msg.opcode(), Some(opcode));
+            assert_eq!(msg.encode().unwrap().as_bytes(), frame);
+        }
+        assert_eq!(count, 34);
+    }
+
+    #[test]
+    fn test_opcode_selection() {
+        let long = EventId::new(false, 0, 1, 0, 2);
+        let short = EventId::new(true, 0, 0, 0, 2);
+        let msg = EventMessage::with_data(long, EventType::AccessoryOn, &[1, 2]).unwrap();
+        assert_eq!(msg.opcode(), Some(OpCode::LongEventAccessoryOn2));
+        let msg = EventMessage::new(short, EventType::AccessoryStatusOff);
+        assert_eq!(msg.opcode(), Some(OpCode::ShortEventAccessoryStateOff));
+        let msg = EventMessage::with_data(short, EventType::AccessoryStatusRequest, &[1]).unwrap();
+        assert_eq!(msg.opcode(), None);
+        assert_eq!(EventMessage::new(long, EventType::Unknown).opcode(), None);
+        assert_eq!(
+            EventMessage::with_data(long, EventType::AccessoryOn, &[1, 2, 3, 4]),
+            Err(VlcbError::WrongLength {
+                expected: 3,
+                actual: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_vlcb_message() {
+        let event = EventId::new(false, 0, 1, 0, 2);
+        let vlcb = VlcbMessage::LongEventAccessoryOff1 { event, data: [7] };
+        let msg = EventMessage::try_from(&vlcb).unwrap();
+        assert_eq!(msg.kind, EventType::AccessoryOff);
+        assert_eq!(msg.data.as_slice(), &[7]);
+        assert_eq!(msg.to_message(), Some(vlcb));
+        assert_eq!(
+            EventMessage::try_from(&VlcbMessage::BusHalt),
+            Err(VlcbError::UnexpectedOpcode(OpCode::BusHalt))
+        );
+    }
+}
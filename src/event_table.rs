@@ -0,0 +1,351 @@
+use crate::vlcb::EventId;
+use crate::CommandError;
+
+/// An event taught to a module with its event variables
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LearnedEvent<const EVS: usize> {
+    pub event: EventId,
+    pub evs: [u8; EVS],
+}
+
+/// Fixed-capacity table of the events taught to a consumer module
+///
+/// Stores up to `EVENTS` events with `EVS` event variables each, in slots addressed by
+/// the event index used by NERD, NENRD, REVAL and EVLRNI. A slot keeps its index until
+/// its event is unlearned. EV indices start at 1, EV index 0 reads the number of EVs.
+///
+/// Events are matched by their [`EventKey`](crate::vlcb::EventKey), so a short event
+/// matches whatever the node number of its producer. An event with node number 0 is
+/// taught and matched as a short event, as EVLRN specifies.
+///
+/// Errors are the [`CommandError`] the module reports with CMDERR and GRSP.
+///
+/// ```
+/// use vlcb_defs::event_table::EventTable;
+/// use vlcb_defs::vlcb::EventId;
+/// use vlcb_defs::CommandError;
+///
+/// let mut table = EventTable::<4, 2>::new();
+/// let event = EventId::new(false, 1, 0, 0, 7);
+/// let index = table.teach(event, 1, 42).unwrap();
+/// assert_eq!(table.ev(index, 1), Ok(42));
+/// assert_eq!(table.teach(event, 3, 0), Err(CommandError::InvalidEvIndex));
+/// ```
+#[derive(Debug, Clone)]
+pub struct EventTable<const EVENTS: usize, const EVS: usize> {
+    slots: [Option<LearnedEvent<EVS>>; EVENTS],
+}
+
+impl<const EVENTS: usize, const EVS: usize> EventTable<EVENTS, EVS> {
+    /// Event and EV indices are carried in one octet
+    const INDEX_CHECK: () = assert!(EVENTS <= 256 && EVS < 256);
+
+    /// Construct an empty table
+    pub const fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let _ = Self::INDEX_CHECK;
+        Self {
+            slots: [None; EVENTS],
+        }
+    }
+
+    /// Number of events the table can hold
+    pub const fn capacity(&self) -> usize {
+        EVENTS
+    }
+
+    /// Number of learned events, answered to NNEVN with NUMEV
+    pub fn len(&self) -> usize {
+        self.slots.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    /// Number of free slots, answered to NNEVN with EVNLF
+    pub fn available(&self) -> usize {
+        EVENTS - self.len()
+    }
+
+    /// Check whether no event is learned
+    pub fn is_empty(&self) -> bool {
+        self.slots.iter().all(Option::is_none)
+    }
+
+    /// Check whether no slot is free
+    pub fn is_full(&self) -> bool {
+        self.slots.iter().all(Option::is_some)
+    }
+
+    /// Return the index of a learned event
+    pub fn find(&self, event: &EventId) -> Option<u8> {
+        let key = taught(*event).key();
+        self.slots
+            .iter()
+            .position(|slot| slot.as_ref().is_some_and(|slot| slot.event.key() == key))
+            .map(|index| index as u8)
+    }
+
+    /// Return the event stored at an index, answered to NENRD with ENRSP
+    ///
+    /// Returns [`CommandError::InvalidEventIndex`] if no event is stored at `index`.
+    pub fn get(&self, index: u8) -> Result<&LearnedEvent<EVS>, CommandError> {
+        self.slots
+            .get(index as usize)
+            .and_then(Option::as_ref)
+            .ok_or(CommandError::InvalidEventIndex)
+    }
+
+    /// Iterate over the learned events with their indices, answered to NERD with ENRSP
+    pub fn iter(&self) -> impl Iterator<Item = (u8, &LearnedEvent<EVS>)> + '_ {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| Some((index as u8, slot.as_ref()?)))
+    }
+
+    /// Read an EV of the event stored at an index, answered to REVAL with NEVAL
+    ///
+    /// EV index 0 returns the number of EVs.
+    /// Returns [`CommandError::InvalidEvent`] if no event is stored at `index`, as
+    /// REVAL requires, and [`CommandError::InvalidEvIndex`] if `ev_index` is out of range.
+    pub fn ev(&self, index: u8, ev_index: u8) -> Result<u8, CommandError> {
+        let learned = self
+            .slots
+            .get(index as usize)
+            .and_then(Option::as_ref)
+            .ok_or(CommandError::InvalidEvent)?;
+        read_ev(&learned.evs, ev_index)
+    }
+
+    /// Read an EV of a learned event, answered to REQEV with EVANS
+    ///
+    /// EV index 0 returns the number of EVs.
+    /// Returns [`CommandError::InvalidEvent`] if the event is not learned and
+    /// [`CommandError::InvalidEvIndex`] if `ev_index` is out of range.
+    pub fn event_ev(&self, event: &EventId, ev_index: u8) -> Result<u8, CommandError> {
+        let index = self.find(event).ok_or(CommandError::InvalidEvent)?;
+        self.ev(index, ev_index)
+    }
+
+    /// Teach an event or one of its EVs, requested with EVLRN
+    ///
+    /// An event which is not learned yet takes the first free slot, with its other EVs
+    /// set to 0. Returns the index of the event.
+    /// Returns [`CommandError::InvalidEvIndex`] if `ev_index` is out of range and
+    /// [`CommandError::TooManyEvents`] if the table is full.
+    pub fn teach(&mut self, event: EventId, ev_index: u8, value: u8) -> Result<u8, CommandError> {
+        let event = taught(event);
+        let ev = check_ev_index::<EVS>(ev_index)?;
+        let index = match self.find(&event) {
+            Some(index) => index,
+            None => self
+                .slots
+                .iter()
+                .position(Option::is_none)
+                .ok_or(CommandError::TooManyEvents)? as u8,
+        };
+
+        let learned = self.slots[index as usize].get_or_insert(LearnedEvent {
+            event,
+            evs: [0; EVS],
+        });
+        learned.evs[ev] = value;
+        Ok(index)
+    }
+
+    /// Teach an event or one of its EVs at an index, requested with EVLRNI
+    ///
+    /// An event stored at the index is replaced by `event`, keeping its EVs.
+    /// Returns [`CommandError::InvalidEventIndex`] if `index` is out of range,
+    /// [`CommandError::InvalidEvIndex`] if `ev_index` is out of range and
+    /// [`CommandError::InvalidEvent`] if `event` is learned at another index.
+    pub fn teach_by_index(
+        &mut self,
+        index: u8,
+        event: EventId,
+        ev_index: u8,
+        value: u8,
+    ) -> Result<(), CommandError> {
+        let event = taught(event);
+        let ev = check_ev_index::<EVS>(ev_index)?;
+        if index as usize >= EVENTS {
+            return Err(CommandError::InvalidEventIndex);
+        }
+        if self.find(&event).is_some_and(|other| other != index) {
+            return Err(CommandError::InvalidEvent);
+        }
+
+        let slot = &mut self.slots[index as usize];
+
+        let learned = slot.get_or_insert(LearnedEvent {
+            event,
+            evs: [0; EVS],
+        });
+        learned.event = event;
+        learned.evs[ev] = value;
+        Ok(())
+    }
+
+    /// Forget an event, requested with EVULN
+    ///
+    /// Returns the index the event was stored at, or [`CommandError::InvalidEvent`]
+    /// if the event is not learned.
+    pub fn unlearn(&mut self, event: &EventId) -> Result<u8, CommandError> {
+        let index = self.find(event).ok_or(CommandError::InvalidEvent)?;
+        self.slots[index as usize] = None;
+        Ok(index)
+    }
+
+    /// Forget all events, requested with NNCLR
+    pub fn clear(&mut self) {
+        self.slots = [None; EVENTS];
+    }
+}
+
+impl<const EVENTS: usize, const EVS: usize> Default for EventTable<EVENTS, EVS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Return the event as stored in the table, an event with node number 0 is short
+fn taught(event: EventId) -> EventId {
+    if event.node_num().is_zero() {
+        let bytes = event.as_bytes();
+        EventId::new(true, 0, 0, bytes[2], bytes[3])
+    } else {
+        event
+    }
+}
+
+/// Convert a 1-based EV index into an index of the EV array
+fn check_ev_index<const EVS: usize>(ev_index: u8) -> Result<usize, CommandError> {
+    match ev_index as usize {
+        0 => Err(CommandError::InvalidEvIndex),
+        ev if ev > EVS => Err(CommandError::InvalidEvIndex),
+        ev => Ok(ev - 1),
+    }
+}
+
+fn read_ev<const EVS: usize>(evs: &[u8; EVS], ev_index: u8) -> Result<u8, CommandError> {
+    if ev_index == 0 {
+        return Ok(EVS as u8);
+    }
+    Ok(evs[check_ev_index::<EVS>(ev_index)?])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::message::VlcbMessage;
+    use alloc::vec::Vec;
+
+    fn event(id: u8) -> EventId {
+        EventId::new(false, 1, 0, 0, id)
+    }
+
+    #[test]
+    fn test_teach() {
+        let mut table = EventTable::<2, 3>::new();
+        assert!(table.is_empty());
+        assert_eq!(table.teach(event(1), 1, 10), Ok(0));
+        assert_eq!(table.teach(event(1), 3, 30), Ok(0));
+        assert_eq!(table.teach(event(2), 2, 20), Ok(1));
+        assert!(table.is_full());
+        assert_eq!(table.available(), 0);
+
+        assert_eq!(
+            table.teach(event(3), 1, 0),
+            Err(CommandError::TooManyEvents)
+        );
+        assert_eq!(
+            table.teach(event(1), 0, 0),
+            Err(CommandError::InvalidEvIndex)
+        );
+        assert_eq!(
+            table.teach(event(1), 4, 0),
+            Err(CommandError::InvalidEvIndex)
+        );
+
+        assert_eq!(table.get(0).unwrap().evs, [10, 0, 30]);
+        assert_eq!(table.ev(1, 0), Ok(3));
+        assert_eq!(table.ev(1, 2), Ok(20));
+        assert_eq!(table.ev(1, 4), Err(CommandError::InvalidEvIndex));
+        assert_eq!(table.ev(2, 1), Err(CommandError::InvalidEvent));
+        assert_eq!(table.event_ev(&event(1), 3), Ok(30));
+        assert_eq!(
+            table.event_ev(&event(3), 1),
+            Err(CommandError::InvalidEvent)
+        );
+    }
+
+    #[test]
+    fn test_unlearn_and_index() {
+        let mut table = EventTable::<3, 1>::new();
+        for id in 1..=3 {
+            table.teach(event(id), 1, id).unwrap();
+        }
+        assert_eq!(table.unlearn(&event(2)), Ok(1));
+        assert_eq!(table.unlearn(&event(2)), Err(CommandError::InvalidEvent));
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.get(1), Err(CommandError::InvalidEventIndex));
+        // REVAL of an empty slot
+        assert_eq!(table.ev(1, 1), Err(CommandError::InvalidEvent));
+        assert_eq!(table.ev(1, 0), Err(CommandError::InvalidEvent));
+        let indices: Vec<u8> = table.iter().map(|(index, _)| index).collect();
+        assert_eq!(indices, [0, 2]);
+
+        // the free slot is reused without moving the other events
+        assert_eq!(table.teach(event(4), 1, 4), Ok(1));
+        assert_eq!(table.find(&event(3)), Some(2));
+
+        assert_eq!(
+            table.teach_by_index(2, event(1), 1, 0),
+            Err(CommandError::InvalidEvent)
+        );
+        table.teach_by_index(0, event(1), 1, 10).unwrap();
+        table.teach_by_index(2, event(5), 1, 50).unwrap();
+        assert_eq!(table.find(&event(3)), None);
+        assert_eq!(table.get(2).unwrap().event, event(5));
+        assert_eq!(
+            table.teach_by_index(3, event(5), 1, 0),
+            Err(CommandError::InvalidEventIndex)
+        );
+
+        table.clear();
+        assert!(table.is_empty());
+        assert_eq!(table.available(), 3);
+    }
+
+    #[test]
+    fn test_short_event() {
+        let mut table = EventTable::<2, 1>::new();
+        // EVLRN with node number 0 teaches a short event
+        let VlcbMessage::TeachEvent {
+            event,
+            ev_index,
+            value,
+        } = VlcbMessage::decode(&[0xD2, 0, 0, 0, 9, 1, 5]).unwrap()
+        else {
+            panic!("not EVLRN");
+        };
+        assert_eq!(table.teach(event, ev_index, value), Ok(0));
+        assert!(table.get(0).unwrap().event.is_short());
+
+        // matched by a short event of any producer
+        let VlcbMessage::ShortEventAccessoryOn { event } =
+            VlcbMessage::decode(&[0x98, 0x01, 0x05, 0, 9]).unwrap()
+        else {
+            panic!("not ASON");
+        };
+        assert_eq!(table.find(&event), Some(0));
+        assert_eq!(table.event_ev(&event, 1), Ok(5));
+        assert_eq!(table.find(&EventId::new(false, 1, 5, 0, 9)), None);
+
+        let VlcbMessage::ForgetLearnedEvent { event } =
+            VlcbMessage::decode(&[0x95, 0, 0, 0, 9]).unwrap()
+        else {
+            panic!("not EVULN");
+        };
+        assert_eq!(table.unlearn(&event), Ok(0));
+    }
+}
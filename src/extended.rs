@@ -0,0 +1,270 @@
+use crate::error::VlcbError;
+use crate::opcode::LengthClass;
+use crate::{Manufacturer, OpCode};
+
+/// Maximum number of data octets following the second opcode octet of an extended message
+pub const EXT_DATA_SIZE: usize = 6;
+
+/// An opcode of the extended opcode space
+///
+/// The extended opcodes [`OpCode::ExtOpCode`] - [`OpCode::ExtOpCode6`] are followed
+/// by a second opcode octet, opening a further 256 opcodes for each length class
+/// except [`LengthClass::Data0`].
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ExtendedOpCode {
+    class: LengthClass,
+    code: u8,
+}
+
+impl ExtendedOpCode {
+    /// Construct an extended opcode
+    ///
+    /// Meant for constant definitions, where the panic turns into a compile error.
+    ///
+    /// # Panics
+    /// The function panics if `class` is [`LengthClass::Data0`].
+    pub const fn new(class: LengthClass, code: u8) -> Self {
+        assert!(
+            !matches!(class, LengthClass::Data0),
+            "there is no extended opcode without data"
+        );
+        Self { class, code }
+    }
+
+    /// Construct an extended opcode
+    ///
+    /// Returns [`VlcbError::ReservedValue`] with the rejected class if `class` is
+    /// [`LengthClass::Data0`].
+    pub const fn try_new(class: LengthClass, code: u8) -> Result<Self, VlcbError> {
+        match class {
+            LengthClass::Data0 => Err(VlcbError::ReservedValue {
+                type_name: "LengthClass",
+                value: class as u8,
+            }),
+            _ => Ok(Self { class, code }),
+        }
+    }
+
+    /// Read the extended opcode of a received message, starting with the opcode
+    ///
+    /// Returns [`VlcbError::UnexpectedOpcode`] if the message is not an extended message.
+    pub fn from_frame(frame: &[u8]) -> Result<Self, VlcbError> {
+        let opcode = OpCode::validate_frame(frame)?;
+        if !opcode.is_extended() {
+            return Err(VlcbError::UnexpectedOpcode(opcode));
+        }
+        Ok(Self {
+            class: opcode.length_class(),
+            code: frame[1],
+        })
+    }
+
+    /// Return the length class of the extended opcode
+    pub const fn class(self) -> LengthClass {
+        self.class
+    }
+
+    /// Return the second opcode octet
+    pub const fn code(self) -> u8 {
+        self.code
+    }
+
+    /// Return the opcode carrying the extended opcode
+    pub const fn opcode(self) -> OpCode {
+        match self.class.ext_opcode() {
+            Some(opcode) => opcode,
+            None => OpCode::ExtOpCode,
+        }
+    }
+
+    /// Number of data octets following the second opcode octet
+    pub const fn data_len(self) -> usize {
+        self.class.data_len() - 1
+    }
+
+    /// Number of octets of the whole message, both opcode octets included
+    pub const fn frame_len(self) -> usize {
+        self.class.frame_len()
+    }
+}
+
+/// Definition of an extended opcode by a manufacturer
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ExtendedOpCodeDef {
+    pub opcode: ExtendedOpCode,
+    pub manufacturer: Manufacturer,
+    pub mnemonic: &'static str,
+}
+
+/// Registry of extended opcodes
+///
+/// Implemented for slices and arrays of [`ExtendedOpCodeDef`], so manufacturers can
+/// publish their definitions as a constant table. Registries can be combined
+/// with a tuple, the first one takes precedence.
+///
+/// ```
+/// use vlcb_defs::extended::{ExtendedOpCode, ExtendedOpCodeDef, ExtendedOpCodeRegistry};
+/// use vlcb_defs::opcode::LengthClass;
+/// use vlcb_defs::Manufacturer;
+///
+/// const SET_OUTPUT: ExtendedOpCode = ExtendedOpCode::new(LengthClass::Data3, 0x01);
+/// const REGISTRY: [ExtendedOpCodeDef; 1] = [ExtendedOpCodeDef {
+///     opcode: SET_OUTPUT,
+///     manufacturer: Manufacturer::MERG,
+///     mnemonic: "SETOUT",
+/// }];
+///
+/// assert_eq!(REGISTRY.lookup(SET_OUTPUT).unwrap().mnemonic, "SETOUT");
+/// ```
+pub trait ExtendedOpCodeRegistry {
+    /// Return the definition of an extended opcode, if known
+    fn lookup(&self, opcode: ExtendedOpCode) -> Option<&ExtendedOpCodeDef>;
+}
+
+impl ExtendedOpCodeRegistry for [ExtendedOpCodeDef] {
+    fn lookup(&self, opcode: ExtendedOpCode) -> Option<&ExtendedOpCodeDef> {
+        self.iter().find(|def| def.opcode == opcode)
+    }
+}
+
+impl<const N: usize> ExtendedOpCodeRegistry for [ExtendedOpCodeDef; N] {
+    fn lookup(&self, opcode: ExtendedOpCode) -> Option<&ExtendedOpCodeDef> {
+        self.as_slice().lookup(opcode)
+    }
+}
+
+impl<R: ExtendedOpCodeRegistry + ?Sized> ExtendedOpCodeRegistry for &R {
+    fn lookup(&self, opcode: ExtendedOpCode) -> Option<&ExtendedOpCodeDef> {
+        (**self).lookup(opcode)
+    }
+}
+
+impl<A: ExtendedOpCodeRegistry, B: ExtendedOpCodeRegistry> ExtendedOpCodeRegistry for (A, B) {
+    fn lookup(&self, opcode: ExtendedOpCode) -> Option<&ExtendedOpCodeDef> {
+        self.0.lookup(opcode).or_else(|| self.1.lookup(opcode))
+    }
+}
+
+/// A message of the extended opcode space
+///
+/// The data is carried uninterpreted, its meaning is defined by the manufacturer
+/// of the extended opcode.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ExtendedMessage {
+    ext_opcode: ExtendedOpCode,
+    data: [u8; EXT_DATA_SIZE],
+}
+
+impl ExtendedMessage {
+    /// Construct an extended message
+    ///
+    /// Returns [`VlcbError::WrongLength`] if the length of `data` does not match
+    /// [`ExtendedOpCode::data_len`].
+    pub fn new(ext_opcode: ExtendedOpCode, data: &[u8]) -> Result<Self, VlcbError> {
+        if data.len() != ext_opcode.data_len() {
+            return Err(VlcbError::WrongLength {
+                expected: ext_opcode.data_len(),
+                actual: data.len(),
+            });
+        }
+        let mut bytes = [0; EXT_DATA_SIZE];
+        bytes[..data.len()].copy_from_slice(data);
+        Ok(Self {
+            ext_opcode,
+            data: bytes,
+        })
+    }
+
+    /// Decode an extended message, starting with the opcode
+    pub fn decode(frame: &[u8]) -> Result<Self, VlcbError> {
+        let ext_opcode = ExtendedOpCode::from_frame(frame)?;
+        Self::new(ext_opcode, &frame[2..])
+    }
+
+    /// Return the extended opcode of the message
+    pub fn ext_opcode(&self) -> ExtendedOpCode {
+        self.ext_opcode
+    }
+
+    /// Return the data octets following the second opcode octet
+    pub fn data(&self) -> &[u8] {
+        &self.data[..self.ext_opcode.data_len()]
+    }
+
+    /// Look up the definition of the extended opcode in a registry
+    pub fn resolve<'r, R: ExtendedOpCodeRegistry + ?Sized>(
+        &self,
+        registry: &'r R,
+    ) -> Option<&'r ExtendedOpCodeDef> {
+        registry.lookup(self.ext_opcode)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MIO_A: ExtendedOpCode = ExtendedOpCode::new(LengthClass::Data3, 0x10);
+    const MIO_B: ExtendedOpCode = ExtendedOpCode::new(LengthClass::Data1, 0x10);
+    const ROCRAIL: ExtendedOpCode = ExtendedOpCode::new(LengthClass::Data7, 0x01);
+
+    const MERG: [ExtendedOpCodeDef; 2] = [
+        ExtendedOpCodeDef {
+            opcode: MIO_A,
+            manufacturer: Manufacturer::MERG,
+            mnemonic: "MIOA",
+        },
+        ExtendedOpCodeDef {
+            opcode: MIO_B,
+            manufacturer: Manufacturer::MERG,
+            mnemonic: "MIOB",
+        },
+    ];
+    const ROCRAIL_DEFS: &[ExtendedOpCodeDef] = &[ExtendedOpCodeDef {
+        opcode: ROCRAIL,
+        manufacturer: Manufacturer::ROCRAIL,
+        mnemonic: "RRX",
+    }];
+
+    #[test]
+    fn test_ext_opcode() {
+        assert_eq!(MIO_A.opcode(), OpCode::ExtOpCode2);
+        assert_eq!(MIO_A.data_len(), 2);
+        assert_eq!(ROCRAIL.frame_len(), 8);
+        assert_eq!(
+            ExtendedOpCode::try_new(LengthClass::Data0, 1),
+            Err(VlcbError::ReservedValue {
+                type_name: "LengthClass",
+                value: LengthClass::Data0 as u8
+            })
+        );
+        assert_eq!(ExtendedOpCode::from_frame(&[0x7F, 0x10, 1, 2]), Ok(MIO_A));
+        assert_eq!(
+            ExtendedOpCode::from_frame(&[0x02]),
+            Err(VlcbError::UnexpectedOpcode(OpCode::BusHalt))
+        );
+    }
+
+    #[test]
+    fn test_registry() {
+        let registry = (MERG, ROCRAIL_DEFS);
+        let msg = ExtendedMessage::decode(&[0xFF, 0x01, 1, 2, 3, 4, 5, 6]).unwrap();
+        assert_eq!(msg.data(), &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(msg.resolve(&registry).unwrap().mnemonic, "RRX");
+
+        let msg = ExtendedMessage::decode(&[0x3F, 0x10]).unwrap();
+        assert_eq!(msg.ext_opcode(), MIO_B);
+        assert_eq!(msg.resolve(&registry).unwrap().mnemonic, "MIOB");
+
+        let msg =
+            ExtendedMessage::new(ExtendedOpCode::new(LengthClass::Data2, 0x20), &[7]).unwrap();
+        assert!(msg.resolve(&registry).is_none());
+        assert!(matches!(
+            ExtendedMessage::new(MIO_A, &[1]),
+            Err(VlcbError::WrongLength { .. })
+        ));
+    }
+}
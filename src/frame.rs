@@ -0,0 +1,654 @@
+use crate::can::{CanHeader, VlcbCanId, CANID_MASK};
+use crate::error::VlcbError;
+use crate::message_ref::MessageRef;
+use crate::OpCode;
+
+/// Mask of the 29-bit CAN extended identifier.
+pub const EXTENDED_ID_MASK: u32 = 0x1FFF_FFFF;
+
+/// Extended identifier of the bootloader control frames sent to a module
+pub const BOOTLOADER_CONTROL_ID: u32 = 0x0000_0004;
+
+/// Extended identifier of the bootloader data frames sent to a module
+pub const BOOTLOADER_DATA_ID: u32 = 0x0000_0005;
+
+/// Extended identifier of the bootloader responses sent by a module
+pub const BOOTLOADER_RESPONSE_ID: u32 = 0x1000_0004;
+
+/// Kind of a CAN frame, telling which decoder it belongs to
+///
+/// Only [`FrameKind::Message`] frames carry opcodes, extended frames must never be
+/// passed to the message decoder.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FrameKind {
+    /// Standard data frame carrying a VLCB message
+    Message,
+
+    /// Standard remote (RTR) frame or data frame without data, used by CAN ID enumeration
+    Enumeration,
+
+    /// Extended frame of the FCU / PIC bootloader protocol
+    Bootloader,
+
+    /// Extended frame of an unknown, e.g. manufacturer specific, protocol
+    UnknownExtended,
+}
+
+impl FrameKind {
+    /// Return the kind of an extended frame with the given identifier
+    pub const fn from_extended_id(id: u32) -> Self {
+        match BootloaderFrameKind::from_id(id) {
+            Some(_) => Self::Bootloader,
+            None => Self::UnknownExtended,
+        }
+    }
+}
+
+/// Kind of a bootloader frame
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BootloaderFrameKind {
+    /// Control frame, see [`BOOTLOADER_CONTROL_ID`]
+    Control,
+    /// Data frame, see [`BOOTLOADER_DATA_ID`]
+    Data,
+    /// Response of the module, see [`BOOTLOADER_RESPONSE_ID`]
+    Response,
+}
+
+impl BootloaderFrameKind {
+    /// Return the kind of a bootloader frame from its extended identifier
+    pub const fn from_id(id: u32) -> Option<Self> {
+        match id {
+            BOOTLOADER_CONTROL_ID => Some(Self::Control),
+            BOOTLOADER_DATA_ID => Some(Self::Data),
+            BOOTLOADER_RESPONSE_ID => Some(Self::Response),
+            _ => None,
+        }
+    }
+
+    /// Return the extended identifier of the bootloader frame
+    pub const fn id(self) -> u32 {
+        match self {
+            Self::Control => BOOTLOADER_CONTROL_ID,
+            Self::Data => BOOTLOADER_DATA_ID,
+            Self::Response => BOOTLOADER_RESPONSE_ID,
+        }
+    }
+}
+
+/// A frame of the FCU / PIC bootloader protocol
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BootloaderFrame<'a> {
+    pub kind: BootloaderFrameKind,
+    pub data: &'a [u8],
+}
+
+/// A CBUS CAN frame
+///
+/// Borrows the data of the frame it was read from, see `VlcbFrame::from_can_frame`
+/// with the `embedded-can` feature enabled.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum VlcbFrame<'a> {
+    /// Standard data frame carrying a message
+    Message {
+        header: CanHeader,
+        message: MessageRef<'a>,
+    },
+
+    /// Standard data frame without data
+    ///
+    /// Sent in response to a [`VlcbFrame::Remote`] frame during CAN ID enumeration.
+    Empty { header: CanHeader },
+
+    /// Standard remote (RTR) frame, sent to start CAN ID enumeration
+    Remote { header: CanHeader },
+
+    /// Extended frame, used by the bootloader, see [`VlcbFrame::kind`]
+    Extended { id: u32, data: &'a [u8] },
+}
+
+impl<'a> VlcbFrame<'a> {
+    /// Construct a data frame carrying a message, starting with the opcode
+    ///
+    /// The message is validated with [`MessageRef::new`].
+    pub fn message(header: CanHeader, data: &'a [u8]) -> Result<Self, VlcbError> {
+        Ok(Self::Message {
+            header,
+            message: MessageRef::new(data)?,
+        })
+    }
+
+    /// Return the header of a standard frame
+    pub fn header(&self) -> Option<CanHeader> {
+        match *self {
+            Self::Message { header, .. } | Self::Empty { header } | Self::Remote { header } => {
+                Some(header)
+            }
+            Self::Extended { .. } => None,
+        }
+    }
+
+    /// Return the data of the frame
+    pub fn data(&self) -> &'a [u8] {
+        match *self {
+            Self::Message { message, .. } => message.as_bytes(),
+            Self::Empty { .. } | Self::Remote { .. } => &[],
+            Self::Extended { data, .. } => data,
+        }
+    }
+
+    /// Return the kind of the frame
+    pub fn kind(&self) -> FrameKind {
+        match *self {
+            Self::Message { .. } => FrameKind::Message,
+            Self::Empty { .. } | Self::Remote { .. } => FrameKind::Enumeration,
+            Self::Extended { id, .. } => FrameKind::from_extended_id(id),
+        }
+    }
+
+    /// Return the message of a [`FrameKind::Message`] frame
+    pub fn message_ref(&self) -> Option<MessageRef<'a>> {
+        match *self {
+            Self::Message { message, .. } => Some(message),
+            _ => None,
+        }
+    }
+
+    /// Return the bootloader frame of a [`FrameKind::Bootloader`] frame
+    pub fn bootloader(&self) -> Option<BootloaderFrame<'a>> {
+        match *self {
+            Self::Extended { id, data } => Some(BootloaderFrame {
+                kind: BootloaderFrameKind::from_id(id)?,
+                data,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Maximum number of data octets of a CAN frame
+pub const MAX_FRAME_DATA: usize = 8;
+
+/// Identifier of a [`CanFrame`]
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FrameId {
+    /// 11-bit standard identifier carrying the CBUS header
+    Standard(CanHeader),
+
+    /// 29-bit extended identifier
+    Extended(u32),
+}
+
+/// An owned CAN frame
+///
+/// Used by the text based frame formats, which carry frames that are not
+/// necessarily valid CBUS frames. Use [`CanFrame::as_frame`] to read the frame.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CanFrame {
+    id: FrameId,
+    remote: bool,
+    len: u8,
+    data: [u8; MAX_FRAME_DATA],
+}
+
+impl CanFrame {
+    /// Construct a data frame
+    ///
+    /// Returns [`VlcbError::WrongLength`] if `data` is longer than [`MAX_FRAME_DATA`]
+    /// and [`VlcbError::ReservedBitsSet`] if an extended identifier does not fit into 29 bits.
+    pub fn new(id: FrameId, data: &[u8]) -> Result<Self, VlcbError> {
+        if data.len() > MAX_FRAME_DATA {
+            return Err(VlcbError::WrongLength {
+                expected: MAX_FRAME_DATA,
+                actual: data.len(),
+            });
+        }
+        if let FrameId::Extended(raw) = id {
+            if raw & !EXTENDED_ID_MASK != 0 {
+                return Err(VlcbError::ReservedBitsSet {
+                    type_name: "FrameId",
+                    value: (raw >> 24) as u8,
+                });
+            }
+        }
+
+        let mut bytes = [0; MAX_FRAME_DATA];
+        bytes[..data.len()].copy_from_slice(data);
+        Ok(Self {
+            id,
+            remote: false,
+            len: data.len() as u8,
+            data: bytes,
+        })
+    }
+
+    /// Construct a remote (RTR) frame
+    ///
+    /// Returns [`VlcbError::ReservedBitsSet`] if an extended identifier does not fit into 29 bits.
+    pub fn remote(id: FrameId) -> Result<Self, VlcbError> {
+        Ok(Self {
+            remote: true,
+            ..Self::new(id, &[])?
+        })
+    }
+
+    /// Return the identifier of the frame
+    pub fn id(&self) -> FrameId {
+        self.id
+    }
+
+    /// Check whether the frame is a remote (RTR) frame
+    pub fn is_remote(&self) -> bool {
+        self.remote
+    }
+
+    /// Return the data of the frame
+    pub fn data(&self) -> &[u8] {
+        &self.data[..self.len as usize]
+    }
+
+    /// Return the kind of the frame
+    ///
+    /// A standard data frame is a [`FrameKind::Message`] even when it does not
+    /// carry a valid message, reading it with [`CanFrame::as_frame`] fails then.
+    pub fn kind(&self) -> FrameKind {
+        match self.id {
+            FrameId::Standard(_) if self.remote || self.len == 0 => FrameKind::Enumeration,
+            FrameId::Standard(_) => FrameKind::Message,
+            FrameId::Extended(id) => FrameKind::from_extended_id(id),
+        }
+    }
+
+    /// Read the frame as a CBUS frame
+    ///
+    /// Standard data frames are validated with [`MessageRef::new`]. Extended remote
+    /// frames are returned as [`VlcbFrame::Extended`] without data.
+    pub fn as_frame(&self) -> Result<VlcbFrame<'_>, VlcbError> {
+        match self.id {
+            FrameId::Standard(header) if self.remote => Ok(VlcbFrame::Remote { header }),
+            FrameId::Standard(header) if self.len == 0 => Ok(VlcbFrame::Empty { header }),
+            FrameId::Standard(header) => VlcbFrame::message(header, self.data()),
+            FrameId::Extended(id) => Ok(VlcbFrame::Extended {
+                id,
+                data: self.data(),
+            }),
+        }
+    }
+}
+
+impl From<VlcbFrame<'_>> for CanFrame {
+    fn from(frame: VlcbFrame<'_>) -> Self {
+        let id = match frame {
+            VlcbFrame::Extended { id, .. } => FrameId::Extended(id & EXTENDED_ID_MASK),
+            VlcbFrame::Message { header, .. }
+            | VlcbFrame::Empty { header }
+            | VlcbFrame::Remote { header } => FrameId::Standard(header),
+        };
+        let data = frame.data();
+        let mut bytes = [0; MAX_FRAME_DATA];
+        let len = data.len().min(MAX_FRAME_DATA);
+        bytes[..len].copy_from_slice(&data[..len]);
+        Self {
+            id,
+            remote: matches!(frame, VlcbFrame::Remote { .. }),
+            len: len as u8,
+            data: bytes,
+        }
+    }
+}
+
+/// Filter of received frames by opcode and CAN ID
+///
+/// An empty set of opcodes or CAN IDs matches any frame. Once opcodes are added,
+/// only message frames carrying one of them match, once CAN IDs are added only
+/// standard frames sent with one of them match.
+///
+/// ```
+/// use vlcb_defs::can::{CanHeader, VlcbCanId};
+/// use vlcb_defs::frame::{FrameFilter, VlcbFrame};
+/// use vlcb_defs::OpCode;
+///
+/// const FILTER: FrameFilter = FrameFilter::new()
+///     .opcode(OpCode::BusHalt)
+///     .opcode(OpCode::BusResume);
+///
+/// let header = CanHeader::for_opcode(VlcbCanId([0x05]), OpCode::BusHalt);
+/// assert!(FILTER.matches(&VlcbFrame::message(header, &[0x02]).unwrap()));
+/// assert!(!FILTER.matches(&VlcbFrame::Empty { header }));
+/// ```
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FrameFilter {
+    opcodes: [u128; 2],
+    can_ids: u128,
+}
+
+impl FrameFilter {
+    /// Construct a filter matching any frame
+    pub const fn new() -> Self {
+        Self {
+            opcodes: [0; 2],
+            can_ids: 0,
+        }
+    }
+
+    /// Add an opcode to the set of matched opcodes
+    pub const fn opcode(mut self, opcode: OpCode) -> Self {
+        let op = opcode as u8;
+        self.opcodes[(op >> 7) as usize] |= 1 << (op & 0x7F);
+        self
+    }
+
+    /// Add a CAN ID to the set of matched CAN IDs
+    pub const fn can_id(mut self, can_id: VlcbCanId) -> Self {
+        self.can_ids |= 1 << (can_id.0[0] & CANID_MASK);
+        self
+    }
+
+    /// Check whether the filter matches any frame
+    pub const fn is_empty(&self) -> bool {
+        self.opcodes[0] == 0 && self.opcodes[1] == 0 && self.can_ids == 0
+    }
+
+    /// Check whether a frame passes the filter
+    pub fn matches(&self, frame: &VlcbFrame<'_>) -> bool {
+        if self.can_ids != 0 {
+            match frame.header() {
+                Some(header) if self.can_ids & 1 << (header.can_id.0[0] & CANID_MASK) != 0 => {}
+                _ => return false,
+            }
+        }
+        if self.opcodes != [0; 2] {
+            let VlcbFrame::Message { message, .. } = frame else {
+                return false;
+            };
+            let op = message.opcode() as u8;
+            return self.opcodes[(op >> 7) as usize] & 1 << (op & 0x7F) != 0;
+        }
+        true
+    }
+
+    /// Check whether an owned frame passes the filter
+    ///
+    /// A frame which is not a valid CBUS frame only passes an empty filter.
+    pub fn matches_can_frame(&self, frame: &CanFrame) -> bool {
+        match frame.as_frame() {
+            Ok(frame) => self.matches(&frame),
+            Err(_) => self.is_empty(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_can_frame() {
+        let header = CanHeader::for_opcode(VlcbCanId([0x05]), OpCode::BusHalt);
+        let frame = CanFrame::new(FrameId::Standard(header), &[0x02]).unwrap();
+        let vlcb = frame.as_frame().unwrap();
+        assert_eq!(vlcb, VlcbFrame::message(header, &[0x02]).unwrap());
+        assert_eq!(CanFrame::from(vlcb), frame);
+
+        let rtr = CanFrame::remote(FrameId::Standard(header)).unwrap();
+        assert_eq!(rtr.as_frame(), Ok(VlcbFrame::Remote { header }));
+        assert_eq!(CanFrame::from(VlcbFrame::Remote { header }), rtr);
+
+        let empty = CanFrame::new(FrameId::Standard(header), &[]).unwrap();
+        assert_eq!(empty.as_frame(), Ok(VlcbFrame::Empty { header }));
+
+        let ext = CanFrame::new(FrameId::Extended(0x0008_0004), &[1, 2]).unwrap();
+        assert_eq!(
+            ext.as_frame(),
+            Ok(VlcbFrame::Extended {
+                id: 0x0008_0004,
+                data: &[1, 2]
+            })
+        );
+
+        let bad = CanFrame::new(FrameId::Standard(header), &[0x90]).unwrap();
+        assert!(bad.as_frame().is_err());
+        assert!(CanFrame::new(FrameId::Standard(header), &[0; 9]).is_err());
+        assert!(CanFrame::remote(FrameId::Extended(0x2000_0000)).is_err());
+    }
+
+    #[test]
+    fn test_kind() {
+        let header = CanHeader::for_opcode(VlcbCanId([0x05]), OpCode::BusHalt);
+        let hlt = VlcbFrame::message(header, &[0x02]).unwrap();
+        assert_eq!(hlt.kind(), FrameKind::Message);
+        assert_eq!(hlt.message_ref().unwrap().opcode(), OpCode::BusHalt);
+        assert_eq!(hlt.bootloader(), None);
+        assert_eq!(VlcbFrame::Remote { header }.kind(), FrameKind::Enumeration);
+        assert_eq!(VlcbFrame::Empty { header }.kind(), FrameKind::Enumeration);
+
+        // looks like a HLT, but is a bootloader frame
+        let boot = VlcbFrame::Extended {
+            id: BOOTLOADER_DATA_ID,
+            data: &[0x02],
+        };
+        assert_eq!(boot.kind(), FrameKind::Bootloader);
+        assert_eq!(boot.message_ref(), None);
+        assert_eq!(
+            boot.bootloader(),
+            Some(BootloaderFrame {
+                kind: BootloaderFrameKind::Data,
+                data: &[0x02]
+            })
+        );
+
+        let unknown = VlcbFrame::Extended {
+            id: 0x0123_4567,
+            data: &[],
+        };
+        assert_eq!(unknown.kind(), FrameKind::UnknownExtended);
+        assert_eq!(unknown.bootloader(), None);
+
+        let frame = CanFrame::new(FrameId::Standard(header), &[0x90]).unwrap();
+        assert_eq!(frame.kind(), FrameKind::Message);
+        let frame = CanFrame::remote(FrameId::Extended(BOOTLOADER_RESPONSE_ID)).unwrap();
+        assert_eq!(frame.kind(), FrameKind::Bootloader);
+        assert_eq!(BootloaderFrameKind::Response.id(), BOOTLOADER_RESPONSE_ID);
+    }
+    #[test]
+    fn test_filter() {
+        let header = CanHeader::for_opcode(VlcbCanId([0x05]), OpCode::LongEventAccessoryOn);
+        let acon = VlcbFrame::message(header, &[0x90, 0, 1, 0, 2]).unwrap();
+        let empty = VlcbFrame::Empty { header };
+        let extended = VlcbFrame::Extended { id: 5, data: &[] };
+
+        let any = FrameFilter::new();
+        assert!(any.is_empty());
+        assert!(any.matches(&acon) && any.matches(&empty) && any.matches(&extended));
+
+        let by_id = FrameFilter::new().can_id(VlcbCanId([0x05]));
+        assert!(!by_id.is_empty());
+        assert!(by_id.matches(&acon) && by_id.matches(&empty));
+        assert!(!by_id.matches(&extended));
+        assert!(!FrameFilter::new().can_id(VlcbCanId([0x06])).matches(&acon));
+
+        let by_opcode = FrameFilter::new().opcode(OpCode::LongEventAccessoryOn);
+        assert!(by_opcode.matches(&acon));
+        assert!(!by_opcode.matches(&empty));
+        assert!(!FrameFilter::new().opcode(OpCode::BusHalt).matches(&acon));
+        assert!(!by_opcode.can_id(VlcbCanId([0x06])).matches(&acon));
+
+        let invalid = CanFrame::new(FrameId::Standard(header), &[0x90]).unwrap();
+        assert!(any.matches_can_frame(&invalid));
+        assert!(!by_id.matches_can_frame(&invalid));
+        assert!(by_opcode.matches_can_frame(&CanFrame::from(acon)));
+    }
+}
+
+#[cfg(feature = "embedded-can")]
+mod embedded {
+    use embedded_can::{ExtendedId, Frame, Id, StandardId};
+
+    use super::*;
+
+    impl From<CanHeader> for StandardId {
+        fn from(header: CanHeader) -> Self {
+            // the header always fits into 11 bits
+            StandardId::new(header.to_standard_id()).unwrap_or(StandardId::MAX)
+        }
+    }
+
+    impl From<CanHeader> for Id {
+        fn from(header: CanHeader) -> Self {
+            Id::Standard(header.into())
+        }
+    }
+
+    impl From<StandardId> for CanHeader {
+        fn from(id: StandardId) -> Self {
+            // a standard id always fits into 11 bits
+            CanHeader::from_standard_id(id.as_raw()).unwrap_or_default()
+        }
+    }
+
+    impl<'a> VlcbFrame<'a> {
+        /// Read a CBUS frame from an `embedded-can` frame
+        ///
+        /// Returns an error if a standard data frame does not carry a valid message.
+        pub fn from_can_frame<F: Frame>(frame: &'a F) -> Result<Self, VlcbError> {
+            let header = match frame.id() {
+                Id::Standard(id) => CanHeader::from(id),
+                Id::Extended(id) => {
+                    return Ok(Self::Extended {
+                        id: id.as_raw(),
+                        data: frame.data(),
+                    })
+                }
+            };
+
+            if frame.is_remote_frame() {
+                Ok(Self::Remote { header })
+            } else if frame.data().is_empty() {
+                Ok(Self::Empty { header })
+            } else {
+                Self::message(header, frame.data())
+            }
+        }
+
+        /// Construct an `embedded-can` frame
+        ///
+        /// Returns `None` if the frame implementation rejects the frame.
+        pub fn to_can_frame<F: Frame>(&self) -> Option<F> {
+            match *self {
+                Self::Message { header, message } => F::new(header, message.as_bytes()),
+                Self::Empty { header } => F::new(header, &[]),
+                Self::Remote { header } => F::new_remote(header, 0),
+                Self::Extended { id, data } => F::new(ExtendedId::new(id)?, data),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+        use crate::can::VlcbCanId;
+        use crate::OpCode;
+
+        #[derive(Debug, PartialEq)]
+        struct TestFrame {
+            id: Id,
+            remote: bool,
+            data: heapless::Vec<u8, 8>,
+        }
+
+        impl Frame for TestFrame {
+            fn new(id: impl Into<Id>, data: &[u8]) -> Option<Self> {
+                Some(Self {
+                    id: id.into(),
+                    remote: false,
+                    data: heapless::Vec::from_slice(data).ok()?,
+                })
+            }
+
+            fn new_remote(id: impl Into<Id>, _dlc: usize) -> Option<Self> {
+                Some(Self {
+                    id: id.into(),
+                    remote: true,
+                    data: heapless::Vec::new(),
+                })
+            }
+
+            fn is_extended(&self) -> bool {
+                matches!(self.id, Id::Extended(_))
+            }
+
+            fn is_remote_frame(&self) -> bool {
+                self.remote
+            }
+
+            fn id(&self) -> Id {
+                self.id
+            }
+
+            fn dlc(&self) -> usize {
+                self.data.len()
+            }
+
+            fn data(&self) -> &[u8] {
+                &self.data
+            }
+        }
+
+        #[test]
+        fn test_message_frame() {
+            let header = CanHeader::for_opcode(VlcbCanId([0x05]), OpCode::BusHalt);
+            let frame = VlcbFrame::message(header, &[0x02]).unwrap();
+            let can: TestFrame = frame.to_can_frame().unwrap();
+            assert_eq!(can.id, Id::Standard(StandardId::new(0x405).unwrap()));
+            assert_eq!(can.data(), &[0x02]);
+            assert_eq!(VlcbFrame::from_can_frame(&can), Ok(frame));
+
+            let can = TestFrame::new(header, &[0x90, 0x00]).unwrap();
+            assert!(matches!(
+                VlcbFrame::from_can_frame(&can),
+                Err(VlcbError::WrongLength { .. })
+            ));
+        }
+
+        #[test]
+        fn test_enumeration_frames() {
+            let header = CanHeader::default();
+            let can: TestFrame = VlcbFrame::Remote { header }.to_can_frame().unwrap();
+            assert!(can.is_remote_frame());
+            assert_eq!(
+                VlcbFrame::from_can_frame(&can),
+                Ok(VlcbFrame::Remote { header })
+            );
+
+            let can: TestFrame = VlcbFrame::Empty { header }.to_can_frame().unwrap();
+            assert!(!can.is_remote_frame());
+            assert_eq!(
+                VlcbFrame::from_can_frame(&can),
+                Ok(VlcbFrame::Empty { header })
+            );
+        }
+
+        #[test]
+        fn test_extended_frame() {
+            let frame = VlcbFrame::Extended {
+                id: 0x0008_0004,
+                data: &[1, 2, 3],
+            };
+            let can: TestFrame = frame.to_can_frame().unwrap();
+            assert!(can.is_extended());
+            assert_eq!(VlcbFrame::from_can_frame(&can), Ok(frame));
+
+            let frame = VlcbFrame::Extended {
+                id: 0x2000_0000,
+                data: &[],
+            };
+            assert_eq!(frame.to_can_frame::<TestFrame>(), None);
+        }
+    }
+}
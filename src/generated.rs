@@ -3,6 +3,7 @@ use num_enum::TryFromPrimitive;
 use num_enum::UnsafeFromPrimitive;
 use bitflags::bitflags;
 use num_enum::FromPrimitive;
+use crate::opcode::OpCodeParameter;
 /// VLCB opcodes
 #[derive(
     Debug,
@@ -753,6 +754,3427 @@ pub enum OpCode {
     #[doc = include_str!("../docs/opcode/extc6.md")]
     ExtOpCode6 = 255,
 }
+impl OpCode {
+    /// Return the mnemonic used by the opcode specification, e.g. `ACON`
+    pub const fn mnemonic(self) -> &'static str {
+        match self {
+            Self::GeneralAck => "ACK",
+            Self::GeneralNack => "NAK",
+            Self::BusHalt => "HLT",
+            Self::BusResume => "BON",
+            Self::DccTrackPoweredOff => "TOF",
+            Self::DccTrackPoweredOn => "TON",
+            Self::DccEmergencyStopEngaged => "ESTOP",
+            Self::RestartAllNodes => "ARST",
+            Self::DccTrackPowerOff => "RTOF",
+            Self::DccTrackPowerOn => "RTON",
+            Self::DccEmergencyStop => "RESTP",
+            Self::DccQueryCommandStationStatus => "RSTAT",
+            Self::QueryNodeInfo => "QNN",
+            Self::QueryNodeParameters => "RQNP",
+            Self::QueryModuleName => "RQMN",
+            Self::DccReleaseSession => "KLOC",
+            Self::DccQueryLocoStatus => "QLOC",
+            Self::DccSessionKeepAlive => "DKEEP",
+            Self::DebugMsg1 => "DBG1",
+            Self::ExtOpCode => "EXTC",
+            Self::DccRequestNewSession => "RLOC",
+            Self::DccQueryConsist => "QCON",
+            Self::SetNodeNumber => "SNN",
+            Self::DccAllocateLocoToActivity => "ALOC",
+            Self::DccSetThrottleMode => "STMOD",
+            Self::DccConsistAddLoco => "PCON",
+            Self::DccConsistRemoveLoco => "KCON",
+            Self::DccSetLocoThrottle => "DSPD",
+            Self::DccSetLocoFlags => "DFLG",
+            Self::DccLocoFunctionOn => "DFNON",
+            Self::DccLocoFunctionOff => "DFNOF",
+            Self::DccServiceModeStatus => "SSTAT",
+            Self::ResetModuleToFactory => "NNRSM",
+            Self::RequestNewNodeNumber => "RQNN",
+            Self::NodeNumberReleased => "NNREL",
+            Self::NodeNumberAck => "NNACK",
+            Self::PutNodeIntoLearnMode => "NNLRN",
+            Self::ReleaseNodeFromLearnMode => "NNULN",
+            Self::ForgetAllLearnedEvents => "NNCLR",
+            Self::QueryAvailableEventSlots => "NNEVN",
+            Self::QueryAllLearnedEvents => "NERD",
+            Self::QueryLearnedEventCount => "RQEVN",
+            Self::WriteAck => "WRACK",
+            Self::QueryNodeData => "RQDAT",
+            Self::RequestDeviceDataShortMode => "RQDDS",
+            Self::RebootIntoBootloader => "BOOTM",
+            Self::ForceCanEnumeration => "ENUM",
+            Self::RestartNode => "NNRST",
+            Self::ExtOpCode1 => "EXTC1",
+            Self::DccSetLocoFunctions => "DFUN",
+            Self::DccQueryLocoSession => "GLOC",
+            Self::DccCommandStationError => "ERR",
+            Self::NodeConfigurationError => "CMDERR",
+            Self::AvailableEventSlots => "EVNLF",
+            Self::QueryNodeVariable => "NVRD",
+            Self::QueryLearnedEventByIndex => "NENRD",
+            Self::QueryNodeParameterByIndex => "RQNPN",
+            Self::LearnedEventCount => "NUMEV",
+            Self::SetNodeCanId => "CANID",
+            Self::PutNodeIntoMode => "MODE",
+            Self::ServiceDiscoveryQuery => "RQSD",
+            Self::ExtOpCode2 => "EXTC2",
+            Self::DccSendRawPacket3 => "RDCC3",
+            Self::DccWriteCvByteInOpsMode => "WCVO",
+            Self::DcWriteCvBitInOpsMode => "WCVB",
+            Self::DccReadCv => "QCVS",
+            Self::DccCvValue => "PCVS",
+            Self::QueryDiagnosticData => "RDGN",
+            Self::SetNodeVariable => "NVSETRD",
+            Self::LongEventAccessoryOn => "ACON",
+            Self::LongEventAccessoryOff => "ACOF",
+            Self::QueryLongEventAccessoryState => "AREQ",
+            Self::LongEventAccessoryStateOn => "ARON",
+            Self::LongEventAccessoryStateOff => "AROF",
+            Self::ForgetLearnedEvent => "EVULN",
+            Self::LegacySetNodeVariable => "NVSET",
+            Self::NodeVariableValue => "NVANS",
+            Self::ShortEventAccessoryOn => "ASON",
+            Self::ShortEventAccessoryOff => "ASOF",
+            Self::QueryShortEventAccessoryState => "ASRQ",
+            Self::NodeParameterValue => "PARAN",
+            Self::QueryEventVariable => "REVAL",
+            Self::ShortEventAccessoryStateOn => "ARSON",
+            Self::ShortEventAccessoryStateOff => "ARSOF",
+            Self::ExtOpCode3 => "EXTC3",
+            Self::DccSendRawPacket4 => "RDCC4",
+            Self::DccWriteCvInServiceMode => "WCVS",
+            Self::Heartbeat => "HEARTB",
+            Self::ServiceDiscoveryResponse => "SD",
+            Self::GenericResponse => "GRSP",
+            Self::LongEventAccessoryOn1 => "ACON1",
+            Self::LongEventAccessoryOff1 => "ACOF1",
+            Self::QueryEventVariableInLearnMode => "REQEV",
+            Self::LongEventAccessoryStateOn1 => "ARON1",
+            Self::LongEventAccessoryStateOff1 => "AROF1",
+            Self::EventVariableValue => "NEVAL",
+            Self::NodeInfo => "PNN",
+            Self::ShortEventAccessoryOn1 => "ASON1",
+            Self::ShortEventAccessoryOff1 => "ASOF1",
+            Self::ShortEventAccessoryStateOn1 => "ARSON1",
+            Self::ShortEventAccessoryStateOff1 => "ARSOF1",
+            Self::ExtOpCode4 => "EXTC4",
+            Self::DccSendRawPacket5 => "RDCC5",
+            Self::DccWriteCvByteInOpsModeByAddress => "WCVOA",
+            Self::DccSendDataToCab => "CABDAT",
+            Self::DiagnosticData => "DGN",
+            Self::FastClock => "FCLK",
+            Self::LongEventAccessoryOn2 => "ACON2",
+            Self::LongEventAccessoryOff2 => "ACOF2",
+            Self::TeachEvent => "EVLRN",
+            Self::EventVariableValueInLearnMode => "EVANS",
+            Self::LongEventAccessoryStateOn2 => "ARON2",
+            Self::LongEventAccessoryStateOff2 => "AROF2",
+            Self::ShortEventAccessoryOn2 => "ASON2",
+            Self::ShortEventAccessoryOff2 => "ASOF2",
+            Self::ShortEventAccessoryStateOn2 => "ARSON2",
+            Self::ShortEventAccessoryStateOff2 => "ARSOF2",
+            Self::ExtOpCode5 => "EXTC5",
+            Self::DccSendRawPacket6 => "RDCC6",
+            Self::DccLocoReport => "PLOC",
+            Self::ModuleName => "NAME",
+            Self::DccCommandStationStatus => "STAT",
+            Self::EventAck => "ENACK",
+            Self::ExtendedServiceDiscoveryResponse => "ESD",
+            Self::StreamPacket => "DTXC",
+            Self::NodeParametersReport => "PARAMS",
+            Self::LongEventAccessoryOn3 => "ACON3",
+            Self::LongEventAccessoryOff3 => "ACOF3",
+            Self::LearnedEventResponse => "ENRSP",
+            Self::LongEventAccessoryStateOn3 => "ARON3",
+            Self::LongEventAccessoryStateOff3 => "AROF3",
+            Self::TeachEventByIndex => "EVLRNI",
+            Self::DataEventAccessory => "ACDAT",
+            Self::NodeDataEventResponse => "ARDAT",
+            Self::ShortEventAccessoryOn3 => "ASON3",
+            Self::ShortEventAccessoryOff3 => "ASOF3",
+            Self::DeviceDataEventShortMode => "DDES",
+            Self::DeviceDataResponseShortMode => "DDRS",
+            Self::WriteData => "DDWS",
+            Self::ShortEventAccessoryStateOn3 => "ARSON3",
+            Self::ShortEventAccessoryStateOff3 => "ARSOF3",
+            Self::ExtOpCode6 => "EXTC6",
+        }
+    }
+
+    /// Look up an opcode by its mnemonic or its Rust name
+    ///
+    /// Mnemonics are matched in upper case only, e.g. `ACON` or `LongEventAccessoryOn`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "ACK" | "GeneralAck" => Some(Self::GeneralAck),
+            "NAK" | "GeneralNack" => Some(Self::GeneralNack),
+            "HLT" | "BusHalt" => Some(Self::BusHalt),
+            "BON" | "BusResume" => Some(Self::BusResume),
+            "TOF" | "DccTrackPoweredOff" => Some(Self::DccTrackPoweredOff),
+            "TON" | "DccTrackPoweredOn" => Some(Self::DccTrackPoweredOn),
+            "ESTOP" | "DccEmergencyStopEngaged" => Some(Self::DccEmergencyStopEngaged),
+            "ARST" | "RestartAllNodes" => Some(Self::RestartAllNodes),
+            "RTOF" | "DccTrackPowerOff" => Some(Self::DccTrackPowerOff),
+            "RTON" | "DccTrackPowerOn" => Some(Self::DccTrackPowerOn),
+            "RESTP" | "DccEmergencyStop" => Some(Self::DccEmergencyStop),
+            "RSTAT" | "DccQueryCommandStationStatus" => Some(Self::DccQueryCommandStationStatus),
+            "QNN" | "QueryNodeInfo" => Some(Self::QueryNodeInfo),
+            "RQNP" | "QueryNodeParameters" => Some(Self::QueryNodeParameters),
+            "RQMN" | "QueryModuleName" => Some(Self::QueryModuleName),
+            "KLOC" | "DccReleaseSession" => Some(Self::DccReleaseSession),
+            "QLOC" | "DccQueryLocoStatus" => Some(Self::DccQueryLocoStatus),
+            "DKEEP" | "DccSessionKeepAlive" => Some(Self::DccSessionKeepAlive),
+            "DBG1" | "DebugMsg1" => Some(Self::DebugMsg1),
+            "EXTC" | "ExtOpCode" => Some(Self::ExtOpCode),
+            "RLOC" | "DccRequestNewSession" => Some(Self::DccRequestNewSession),
+            "QCON" | "DccQueryConsist" => Some(Self::DccQueryConsist),
+            "SNN" | "SetNodeNumber" => Some(Self::SetNodeNumber),
+            "ALOC" | "DccAllocateLocoToActivity" => Some(Self::DccAllocateLocoToActivity),
+            "STMOD" | "DccSetThrottleMode" => Some(Self::DccSetThrottleMode),
+            "PCON" | "DccConsistAddLoco" => Some(Self::DccConsistAddLoco),
+            "KCON" | "DccConsistRemoveLoco" => Some(Self::DccConsistRemoveLoco),
+            "DSPD" | "DccSetLocoThrottle" => Some(Self::DccSetLocoThrottle),
+            "DFLG" | "DccSetLocoFlags" => Some(Self::DccSetLocoFlags),
+            "DFNON" | "DccLocoFunctionOn" => Some(Self::DccLocoFunctionOn),
+            "DFNOF" | "DccLocoFunctionOff" => Some(Self::DccLocoFunctionOff),
+            "SSTAT" | "DccServiceModeStatus" => Some(Self::DccServiceModeStatus),
+            "NNRSM" | "ResetModuleToFactory" => Some(Self::ResetModuleToFactory),
+            "RQNN" | "RequestNewNodeNumber" => Some(Self::RequestNewNodeNumber),
+            "NNREL" | "NodeNumberReleased" => Some(Self::NodeNumberReleased),
+            "NNACK" | "NodeNumberAck" => Some(Self::NodeNumberAck),
+            "NNLRN" | "PutNodeIntoLearnMode" => Some(Self::PutNodeIntoLearnMode),
+            "NNULN" | "ReleaseNodeFromLearnMode" => Some(Self::ReleaseNodeFromLearnMode),
+            "NNCLR" | "ForgetAllLearnedEvents" => Some(Self::ForgetAllLearnedEvents),
+            "NNEVN" | "QueryAvailableEventSlots" => Some(Self::QueryAvailableEventSlots),
+            "NERD" | "QueryAllLearnedEvents" => Some(Self::QueryAllLearnedEvents),
+            "RQEVN" | "QueryLearnedEventCount" => Some(Self::QueryLearnedEventCount),
+            "WRACK" | "WriteAck" => Some(Self::WriteAck),
+            "RQDAT" | "QueryNodeData" => Some(Self::QueryNodeData),
+            "RQDDS" | "RequestDeviceDataShortMode" => Some(Self::RequestDeviceDataShortMode),
+            "BOOTM" | "RebootIntoBootloader" => Some(Self::RebootIntoBootloader),
+            "ENUM" | "ForceCanEnumeration" => Some(Self::ForceCanEnumeration),
+            "NNRST" | "RestartNode" => Some(Self::RestartNode),
+            "EXTC1" | "ExtOpCode1" => Some(Self::ExtOpCode1),
+            "DFUN" | "DccSetLocoFunctions" => Some(Self::DccSetLocoFunctions),
+            "GLOC" | "DccQueryLocoSession" => Some(Self::DccQueryLocoSession),
+            "ERR" | "DccCommandStationError" => Some(Self::DccCommandStationError),
+            "CMDERR" | "NodeConfigurationError" => Some(Self::NodeConfigurationError),
+            "EVNLF" | "AvailableEventSlots" => Some(Self::AvailableEventSlots),
+            "NVRD" | "QueryNodeVariable" => Some(Self::QueryNodeVariable),
+            "NENRD" | "QueryLearnedEventByIndex" => Some(Self::QueryLearnedEventByIndex),
+            "RQNPN" | "QueryNodeParameterByIndex" => Some(Self::QueryNodeParameterByIndex),
+            "NUMEV" | "LearnedEventCount" => Some(Self::LearnedEventCount),
+            "CANID" | "SetNodeCanId" => Some(Self::SetNodeCanId),
+            "MODE" | "PutNodeIntoMode" => Some(Self::PutNodeIntoMode),
+            "RQSD" | "ServiceDiscoveryQuery" => Some(Self::ServiceDiscoveryQuery),
+            "EXTC2" | "ExtOpCode2" => Some(Self::ExtOpCode2),
+            "RDCC3" | "DccSendRawPacket3" => Some(Self::DccSendRawPacket3),
+            "WCVO" | "DccWriteCvByteInOpsMode" => Some(Self::DccWriteCvByteInOpsMode),
+            "WCVB" | "DcWriteCvBitInOpsMode" => Some(Self::DcWriteCvBitInOpsMode),
+            "QCVS" | "DccReadCv" => Some(Self::DccReadCv),
+            "PCVS" | "DccCvValue" => Some(Self::DccCvValue),
+            "RDGN" | "QueryDiagnosticData" => Some(Self::QueryDiagnosticData),
+            "NVSETRD" | "SetNodeVariable" => Some(Self::SetNodeVariable),
+            "ACON" | "LongEventAccessoryOn" => Some(Self::LongEventAccessoryOn),
+            "ACOF" | "LongEventAccessoryOff" => Some(Self::LongEventAccessoryOff),
+            "AREQ" | "QueryLongEventAccessoryState" => Some(Self::QueryLongEventAccessoryState),
+            "ARON" | "LongEventAccessoryStateOn" => Some(Self::LongEventAccessoryStateOn),
+            "AROF" | "LongEventAccessoryStateOff" => Some(Self::LongEventAccessoryStateOff),
+            "EVULN" | "ForgetLearnedEvent" => Some(Self::ForgetLearnedEvent),
+            "NVSET" | "LegacySetNodeVariable" => Some(Self::LegacySetNodeVariable),
+            "NVANS" | "NodeVariableValue" => Some(Self::NodeVariableValue),
+            "ASON" | "ShortEventAccessoryOn" => Some(Self::ShortEventAccessoryOn),
+            "ASOF" | "ShortEventAccessoryOff" => Some(Self::ShortEventAccessoryOff),
+            "ASRQ" | "QueryShortEventAccessoryState" => Some(Self::QueryShortEventAccessoryState),
+            "PARAN" | "NodeParameterValue" => Some(Self::NodeParameterValue),
+            "REVAL" | "QueryEventVariable" => Some(Self::QueryEventVariable),
+            "ARSON" | "ShortEventAccessoryStateOn" => Some(Self::ShortEventAccessoryStateOn),
+            "ARSOF" | "ShortEventAccessoryStateOff" => Some(Self::ShortEventAccessoryStateOff),
+            "EXTC3" | "ExtOpCode3" => Some(Self::ExtOpCode3),
+            "RDCC4" | "DccSendRawPacket4" => Some(Self::DccSendRawPacket4),
+            "WCVS" | "DccWriteCvInServiceMode" => Some(Self::DccWriteCvInServiceMode),
+            "HEARTB" | "Heartbeat" => Some(Self::Heartbeat),
+            "SD" | "ServiceDiscoveryResponse" => Some(Self::ServiceDiscoveryResponse),
+            "GRSP" | "GenericResponse" => Some(Self::GenericResponse),
+            "ACON1" | "LongEventAccessoryOn1" => Some(Self::LongEventAccessoryOn1),
+            "ACOF1" | "LongEventAccessoryOff1" => Some(Self::LongEventAccessoryOff1),
+            "REQEV" | "QueryEventVariableInLearnMode" => Some(Self::QueryEventVariableInLearnMode),
+            "ARON1" | "LongEventAccessoryStateOn1" => Some(Self::LongEventAccessoryStateOn1),
+            "AROF1" | "LongEventAccessoryStateOff1" => Some(Self::LongEventAccessoryStateOff1),
+            "NEVAL" | "EventVariableValue" => Some(Self::EventVariableValue),
+            "PNN" | "NodeInfo" => Some(Self::NodeInfo),
+            "ASON1" | "ShortEventAccessoryOn1" => Some(Self::ShortEventAccessoryOn1),
+            "ASOF1" | "ShortEventAccessoryOff1" => Some(Self::ShortEventAccessoryOff1),
+            "ARSON1" | "ShortEventAccessoryStateOn1" => Some(Self::ShortEventAccessoryStateOn1),
+            "ARSOF1" | "ShortEventAccessoryStateOff1" => Some(Self::ShortEventAccessoryStateOff1),
+            "EXTC4" | "ExtOpCode4" => Some(Self::ExtOpCode4),
+            "RDCC5" | "DccSendRawPacket5" => Some(Self::DccSendRawPacket5),
+            "WCVOA" | "DccWriteCvByteInOpsModeByAddress" => {
+                Some(Self::DccWriteCvByteInOpsModeByAddress)
+            }
+            "CABDAT" | "DccSendDataToCab" => Some(Self::DccSendDataToCab),
+            "DGN" | "DiagnosticData" => Some(Self::DiagnosticData),
+            "FCLK" | "FastClock" => Some(Self::FastClock),
+            "ACON2" | "LongEventAccessoryOn2" => Some(Self::LongEventAccessoryOn2),
+            "ACOF2" | "LongEventAccessoryOff2" => Some(Self::LongEventAccessoryOff2),
+            "EVLRN" | "TeachEvent" => Some(Self::TeachEvent),
+            "EVANS" | "EventVariableValueInLearnMode" => Some(Self::EventVariableValueInLearnMode),
+            "ARON2" | "LongEventAccessoryStateOn2" => Some(Self::LongEventAccessoryStateOn2),
+            "AROF2" | "LongEventAccessoryStateOff2" => Some(Self::LongEventAccessoryStateOff2),
+            "ASON2" | "ShortEventAccessoryOn2" => Some(Self::ShortEventAccessoryOn2),
+            "ASOF2" | "ShortEventAccessoryOff2" => Some(Self::ShortEventAccessoryOff2),
+            "ARSON2" | "ShortEventAccessoryStateOn2" => Some(Self::ShortEventAccessoryStateOn2),
+            "ARSOF2" | "ShortEventAccessoryStateOff2" => Some(Self::ShortEventAccessoryStateOff2),
+            "EXTC5" | "ExtOpCode5" => Some(Self::ExtOpCode5),
+            "RDCC6" | "DccSendRawPacket6" => Some(Self::DccSendRawPacket6),
+            "PLOC" | "DccLocoReport" => Some(Self::DccLocoReport),
+            "NAME" | "ModuleName" => Some(Self::ModuleName),
+            "STAT" | "DccCommandStationStatus" => Some(Self::DccCommandStationStatus),
+            "ENACK" | "EventAck" => Some(Self::EventAck),
+            "ESD" | "ExtendedServiceDiscoveryResponse" => {
+                Some(Self::ExtendedServiceDiscoveryResponse)
+            }
+            "DTXC" | "StreamPacket" => Some(Self::StreamPacket),
+            "PARAMS" | "NodeParametersReport" => Some(Self::NodeParametersReport),
+            "ACON3" | "LongEventAccessoryOn3" => Some(Self::LongEventAccessoryOn3),
+            "ACOF3" | "LongEventAccessoryOff3" => Some(Self::LongEventAccessoryOff3),
+            "ENRSP" | "LearnedEventResponse" => Some(Self::LearnedEventResponse),
+            "ARON3" | "LongEventAccessoryStateOn3" => Some(Self::LongEventAccessoryStateOn3),
+            "AROF3" | "LongEventAccessoryStateOff3" => Some(Self::LongEventAccessoryStateOff3),
+            "EVLRNI" | "TeachEventByIndex" => Some(Self::TeachEventByIndex),
+            "ACDAT" | "DataEventAccessory" => Some(Self::DataEventAccessory),
+            "ARDAT" | "NodeDataEventResponse" => Some(Self::NodeDataEventResponse),
+            "ASON3" | "ShortEventAccessoryOn3" => Some(Self::ShortEventAccessoryOn3),
+            "ASOF3" | "ShortEventAccessoryOff3" => Some(Self::ShortEventAccessoryOff3),
+            "DDES" | "DeviceDataEventShortMode" => Some(Self::DeviceDataEventShortMode),
+            "DDRS" | "DeviceDataResponseShortMode" => Some(Self::DeviceDataResponseShortMode),
+            "DDWS" | "WriteData" => Some(Self::WriteData),
+            "ARSON3" | "ShortEventAccessoryStateOn3" => Some(Self::ShortEventAccessoryStateOn3),
+            "ARSOF3" | "ShortEventAccessoryStateOff3" => Some(Self::ShortEventAccessoryStateOff3),
+            "EXTC6" | "ExtOpCode6" => Some(Self::ExtOpCode6),
+            _ => None,
+        }
+    }
+
+    /// Return the priority as defined by the opcode specification
+    pub const fn priority(self) -> OpCodePriority {
+        match self {
+            Self::GeneralAck => OpCodePriority::Normal,
+            Self::GeneralNack => OpCodePriority::Normal,
+            Self::BusHalt => OpCodePriority::High,
+            Self::BusResume => OpCodePriority::AboveNormal,
+            Self::DccTrackPoweredOff => OpCodePriority::AboveNormal,
+            Self::DccTrackPoweredOn => OpCodePriority::AboveNormal,
+            Self::DccEmergencyStopEngaged => OpCodePriority::AboveNormal,
+            Self::RestartAllNodes => OpCodePriority::High,
+            Self::DccTrackPowerOff => OpCodePriority::AboveNormal,
+            Self::DccTrackPowerOn => OpCodePriority::AboveNormal,
+            Self::DccEmergencyStop => OpCodePriority::High,
+            Self::DccQueryCommandStationStatus => OpCodePriority::Normal,
+            Self::QueryNodeInfo => OpCodePriority::Low,
+            Self::QueryNodeParameters => OpCodePriority::Low,
+            Self::QueryModuleName => OpCodePriority::Normal,
+            Self::DccReleaseSession => OpCodePriority::Normal,
+            Self::DccQueryLocoStatus => OpCodePriority::Normal,
+            Self::DccSessionKeepAlive => OpCodePriority::Normal,
+            Self::DebugMsg1 => OpCodePriority::Normal,
+            Self::ExtOpCode => OpCodePriority::Low,
+            Self::DccRequestNewSession => OpCodePriority::Normal,
+            Self::DccQueryConsist => OpCodePriority::Normal,
+            Self::SetNodeNumber => OpCodePriority::Low,
+            Self::DccAllocateLocoToActivity => OpCodePriority::Normal,
+            Self::DccSetThrottleMode => OpCodePriority::Normal,
+            Self::DccConsistAddLoco => OpCodePriority::Normal,
+            Self::DccConsistRemoveLoco => OpCodePriority::Normal,
+            Self::DccSetLocoThrottle => OpCodePriority::Normal,
+            Self::DccSetLocoFlags => OpCodePriority::Normal,
+            Self::DccLocoFunctionOn => OpCodePriority::Normal,
+            Self::DccLocoFunctionOff => OpCodePriority::Normal,
+            Self::DccServiceModeStatus => OpCodePriority::Low,
+            Self::ResetModuleToFactory => OpCodePriority::Low,
+            Self::RequestNewNodeNumber => OpCodePriority::Low,
+            Self::NodeNumberReleased => OpCodePriority::Low,
+            Self::NodeNumberAck => OpCodePriority::Low,
+            Self::PutNodeIntoLearnMode => OpCodePriority::Low,
+            Self::ReleaseNodeFromLearnMode => OpCodePriority::Low,
+            Self::ForgetAllLearnedEvents => OpCodePriority::Low,
+            Self::QueryAvailableEventSlots => OpCodePriority::Low,
+            Self::QueryAllLearnedEvents => OpCodePriority::Low,
+            Self::QueryLearnedEventCount => OpCodePriority::Low,
+            Self::WriteAck => OpCodePriority::Low,
+            Self::QueryNodeData => OpCodePriority::Low,
+            Self::RequestDeviceDataShortMode => OpCodePriority::Low,
+            Self::RebootIntoBootloader => OpCodePriority::Low,
+            Self::ForceCanEnumeration => OpCodePriority::Low,
+            Self::RestartNode => OpCodePriority::Low,
+            Self::ExtOpCode1 => OpCodePriority::Low,
+            Self::DccSetLocoFunctions => OpCodePriority::Normal,
+            Self::DccQueryLocoSession => OpCodePriority::Normal,
+            Self::DccCommandStationError => OpCodePriority::Normal,
+            Self::NodeConfigurationError => OpCodePriority::Low,
+            Self::AvailableEventSlots => OpCodePriority::Low,
+            Self::QueryNodeVariable => OpCodePriority::Low,
+            Self::QueryLearnedEventByIndex => OpCodePriority::Low,
+            Self::QueryNodeParameterByIndex => OpCodePriority::Low,
+            Self::LearnedEventCount => OpCodePriority::Low,
+            Self::SetNodeCanId => OpCodePriority::Low,
+            Self::PutNodeIntoMode => OpCodePriority::Low,
+            Self::ServiceDiscoveryQuery => OpCodePriority::Low,
+            Self::ExtOpCode2 => OpCodePriority::Low,
+            Self::DccSendRawPacket3 => OpCodePriority::Normal,
+            Self::DccWriteCvByteInOpsMode => OpCodePriority::Normal,
+            Self::DcWriteCvBitInOpsMode => OpCodePriority::Normal,
+            Self::DccReadCv => OpCodePriority::Normal,
+            Self::DccCvValue => OpCodePriority::Normal,
+            Self::QueryDiagnosticData => OpCodePriority::Low,
+            Self::SetNodeVariable => OpCodePriority::Low,
+            Self::LongEventAccessoryOn => OpCodePriority::Low,
+            Self::LongEventAccessoryOff => OpCodePriority::Low,
+            Self::QueryLongEventAccessoryState => OpCodePriority::Low,
+            Self::LongEventAccessoryStateOn => OpCodePriority::Low,
+            Self::LongEventAccessoryStateOff => OpCodePriority::Low,
+            Self::ForgetLearnedEvent => OpCodePriority::Low,
+            Self::LegacySetNodeVariable => OpCodePriority::Low,
+            Self::NodeVariableValue => OpCodePriority::Low,
+            Self::ShortEventAccessoryOn => OpCodePriority::Low,
+            Self::ShortEventAccessoryOff => OpCodePriority::Low,
+            Self::QueryShortEventAccessoryState => OpCodePriority::Low,
+            Self::NodeParameterValue => OpCodePriority::Low,
+            Self::QueryEventVariable => OpCodePriority::Low,
+            Self::ShortEventAccessoryStateOn => OpCodePriority::Low,
+            Self::ShortEventAccessoryStateOff => OpCodePriority::Low,
+            Self::ExtOpCode3 => OpCodePriority::Low,
+            Self::DccSendRawPacket4 => OpCodePriority::Normal,
+            Self::DccWriteCvInServiceMode => OpCodePriority::Normal,
+            Self::Heartbeat => OpCodePriority::Low,
+            Self::ServiceDiscoveryResponse => OpCodePriority::Low,
+            Self::GenericResponse => OpCodePriority::Low,
+            Self::LongEventAccessoryOn1 => OpCodePriority::Low,
+            Self::LongEventAccessoryOff1 => OpCodePriority::Low,
+            Self::QueryEventVariableInLearnMode => OpCodePriority::Low,
+            Self::LongEventAccessoryStateOn1 => OpCodePriority::Low,
+            Self::LongEventAccessoryStateOff1 => OpCodePriority::Low,
+            Self::EventVariableValue => OpCodePriority::Low,
+            Self::NodeInfo => OpCodePriority::Low,
+            Self::ShortEventAccessoryOn1 => OpCodePriority::Low,
+            Self::ShortEventAccessoryOff1 => OpCodePriority::Low,
+            Self::ShortEventAccessoryStateOn1 => OpCodePriority::Low,
+            Self::ShortEventAccessoryStateOff1 => OpCodePriority::Low,
+            Self::ExtOpCode4 => OpCodePriority::Low,
+            Self::DccSendRawPacket5 => OpCodePriority::Normal,
+            Self::DccWriteCvByteInOpsModeByAddress => OpCodePriority::Normal,
+            Self::DccSendDataToCab => OpCodePriority::Normal,
+            Self::DiagnosticData => OpCodePriority::Low,
+            Self::FastClock => OpCodePriority::Normal,
+            Self::LongEventAccessoryOn2 => OpCodePriority::Low,
+            Self::LongEventAccessoryOff2 => OpCodePriority::Low,
+            Self::TeachEvent => OpCodePriority::Low,
+            Self::EventVariableValueInLearnMode => OpCodePriority::Low,
+            Self::LongEventAccessoryStateOn2 => OpCodePriority::Low,
+            Self::LongEventAccessoryStateOff2 => OpCodePriority::Low,
+            Self::ShortEventAccessoryOn2 => OpCodePriority::Low,
+            Self::ShortEventAccessoryOff2 => OpCodePriority::Low,
+            Self::ShortEventAccessoryStateOn2 => OpCodePriority::Low,
+            Self::ShortEventAccessoryStateOff2 => OpCodePriority::Low,
+            Self::ExtOpCode5 => OpCodePriority::Low,
+            Self::DccSendRawPacket6 => OpCodePriority::Normal,
+            Self::DccLocoReport => OpCodePriority::Normal,
+            Self::ModuleName => OpCodePriority::Low,
+            Self::DccCommandStationStatus => OpCodePriority::Normal,
+            Self::EventAck => OpCodePriority::Low,
+            Self::ExtendedServiceDiscoveryResponse => OpCodePriority::Low,
+            Self::StreamPacket => OpCodePriority::Low,
+            Self::NodeParametersReport => OpCodePriority::Low,
+            Self::LongEventAccessoryOn3 => OpCodePriority::Low,
+            Self::LongEventAccessoryOff3 => OpCodePriority::Low,
+            Self::LearnedEventResponse => OpCodePriority::Low,
+            Self::LongEventAccessoryStateOn3 => OpCodePriority::Low,
+            Self::LongEventAccessoryStateOff3 => OpCodePriority::Low,
+            Self::TeachEventByIndex => OpCodePriority::Low,
+            Self::DataEventAccessory => OpCodePriority::Low,
+            Self::NodeDataEventResponse => OpCodePriority::Low,
+            Self::ShortEventAccessoryOn3 => OpCodePriority::Low,
+            Self::ShortEventAccessoryOff3 => OpCodePriority::Low,
+            Self::DeviceDataEventShortMode => OpCodePriority::Low,
+            Self::DeviceDataResponseShortMode => OpCodePriority::Low,
+            Self::WriteData => OpCodePriority::Low,
+            Self::ShortEventAccessoryStateOn3 => OpCodePriority::Low,
+            Self::ShortEventAccessoryStateOff3 => OpCodePriority::Low,
+            Self::ExtOpCode6 => OpCodePriority::Low,
+        }
+    }
+
+    /// Return the services the opcode belongs to as defined by the opcode specification
+    ///
+    /// Opcodes that are not bound to a VLCB service, such as DCC opcodes, return an empty slice.
+    pub const fn services(self) -> &'static [ServiceType] {
+        match self {
+            Self::GeneralAck => &[],
+            Self::GeneralNack => &[],
+            Self::BusHalt => &[],
+            Self::BusResume => &[],
+            Self::DccTrackPoweredOff => &[],
+            Self::DccTrackPoweredOn => &[],
+            Self::DccEmergencyStopEngaged => &[],
+            Self::RestartAllNodes => &[],
+            Self::DccTrackPowerOff => &[],
+            Self::DccTrackPowerOn => &[],
+            Self::DccEmergencyStop => &[],
+            Self::DccQueryCommandStationStatus => &[],
+            Self::QueryNodeInfo => &[ServiceType::MinimumNodeService],
+            Self::QueryNodeParameters => &[ServiceType::MinimumNodeService],
+            Self::QueryModuleName => &[ServiceType::MinimumNodeService],
+            Self::DccReleaseSession => &[],
+            Self::DccQueryLocoStatus => &[],
+            Self::DccSessionKeepAlive => &[],
+            Self::DebugMsg1 => &[],
+            Self::ExtOpCode => &[],
+            Self::DccRequestNewSession => &[],
+            Self::DccQueryConsist => &[],
+            Self::SetNodeNumber => &[ServiceType::MinimumNodeService],
+            Self::DccAllocateLocoToActivity => &[],
+            Self::DccSetThrottleMode => &[],
+            Self::DccConsistAddLoco => &[],
+            Self::DccConsistRemoveLoco => &[],
+            Self::DccSetLocoThrottle => &[],
+            Self::DccSetLocoFlags => &[],
+            Self::DccLocoFunctionOn => &[],
+            Self::DccLocoFunctionOff => &[],
+            Self::DccServiceModeStatus => &[],
+            Self::ResetModuleToFactory => &[ServiceType::MinimumNodeService],
+            Self::RequestNewNodeNumber => &[ServiceType::MinimumNodeService],
+            Self::NodeNumberReleased => &[ServiceType::MinimumNodeService],
+            Self::NodeNumberAck => &[ServiceType::MinimumNodeService],
+            Self::PutNodeIntoLearnMode => &[ServiceType::EventTeaching],
+            Self::ReleaseNodeFromLearnMode => &[ServiceType::EventTeaching],
+            Self::ForgetAllLearnedEvents => &[ServiceType::EventTeaching],
+            Self::QueryAvailableEventSlots => &[ServiceType::EventTeaching],
+            Self::QueryAllLearnedEvents => &[ServiceType::EventTeaching],
+            Self::QueryLearnedEventCount => &[ServiceType::EventTeaching],
+            Self::WriteAck => &[ServiceType::EventTeaching, ServiceType::NodeVariable],
+            Self::QueryNodeData => &[],
+            Self::RequestDeviceDataShortMode => &[],
+            Self::RebootIntoBootloader => &[ServiceType::Bootloader],
+            Self::ForceCanEnumeration => &[ServiceType::CanBus],
+            Self::RestartNode => &[],
+            Self::ExtOpCode1 => &[],
+            Self::DccSetLocoFunctions => &[],
+            Self::DccQueryLocoSession => &[],
+            Self::DccCommandStationError => &[],
+            Self::NodeConfigurationError => &[
+                ServiceType::MinimumNodeService,
+                ServiceType::EventTeaching,
+                ServiceType::NodeVariable,
+            ],
+            Self::AvailableEventSlots => &[ServiceType::EventTeaching],
+            Self::QueryNodeVariable => &[ServiceType::NodeVariable],
+            Self::QueryLearnedEventByIndex => &[ServiceType::EventTeaching],
+            Self::QueryNodeParameterByIndex => &[ServiceType::MinimumNodeService],
+            Self::LearnedEventCount => &[ServiceType::EventTeaching],
+            Self::SetNodeCanId => &[ServiceType::CanBus],
+            Self::PutNodeIntoMode => &[ServiceType::MinimumNodeService],
+            Self::ServiceDiscoveryQuery => &[ServiceType::MinimumNodeService],
+            Self::ExtOpCode2 => &[],
+            Self::DccSendRawPacket3 => &[],
+            Self::DccWriteCvByteInOpsMode => &[],
+            Self::DcWriteCvBitInOpsMode => &[],
+            Self::DccReadCv => &[],
+            Self::DccCvValue => &[],
+            Self::QueryDiagnosticData => &[ServiceType::MinimumNodeService],
+            Self::SetNodeVariable => &[ServiceType::NodeVariable],
+            Self::LongEventAccessoryOn => {
+                &[ServiceType::EventProducer, ServiceType::EventConsumer]
+            }
+            Self::LongEventAccessoryOff => {
+                &[ServiceType::EventProducer, ServiceType::EventConsumer]
+            }
+            Self::QueryLongEventAccessoryState => &[ServiceType::EventProducer],
+            Self::LongEventAccessoryStateOn => &[ServiceType::EventProducer],
+            Self::LongEventAccessoryStateOff => &[ServiceType::EventProducer],
+            Self::ForgetLearnedEvent => &[ServiceType::EventTeaching],
+            Self::LegacySetNodeVariable => &[ServiceType::NodeVariable],
+            Self::NodeVariableValue => &[ServiceType::NodeVariable],
+            Self::ShortEventAccessoryOn => {
+                &[ServiceType::EventProducer, ServiceType::EventConsumer]
+            }
+            Self::ShortEventAccessoryOff => {
+                &[ServiceType::EventProducer, ServiceType::EventConsumer]
+            }
+            Self::QueryShortEventAccessoryState => &[ServiceType::EventProducer],
+            Self::NodeParameterValue => &[ServiceType::MinimumNodeService],
+            Self::QueryEventVariable => &[ServiceType::EventTeaching],
+            Self::ShortEventAccessoryStateOn => &[ServiceType::EventProducer],
+            Self::ShortEventAccessoryStateOff => &[ServiceType::EventProducer],
+            Self::ExtOpCode3 => &[],
+            Self::DccSendRawPacket4 => &[],
+            Self::DccWriteCvInServiceMode => &[],
+            Self::Heartbeat => &[ServiceType::MinimumNodeService],
+            Self::ServiceDiscoveryResponse => &[ServiceType::MinimumNodeService],
+            Self::GenericResponse => &[ServiceType::MinimumNodeService],
+            Self::LongEventAccessoryOn1 => {
+                &[ServiceType::EventProducer, ServiceType::EventConsumer]
+            }
+            Self::LongEventAccessoryOff1 => {
+                &[ServiceType::EventProducer, ServiceType::EventConsumer]
+            }
+            Self::QueryEventVariableInLearnMode => &[ServiceType::EventTeaching],
+            Self::LongEventAccessoryStateOn1 => &[ServiceType::EventProducer],
+            Self::LongEventAccessoryStateOff1 => &[ServiceType::EventProducer],
+            Self::EventVariableValue => &[ServiceType::EventTeaching],
+            Self::NodeInfo => &[ServiceType::MinimumNodeService],
+            Self::ShortEventAccessoryOn1 => {
+                &[ServiceType::EventProducer, ServiceType::EventConsumer]
+            }
+            Self::ShortEventAccessoryOff1 => {
+                &[ServiceType::EventProducer, ServiceType::EventConsumer]
+            }
+            Self::ShortEventAccessoryStateOn1 => &[ServiceType::EventProducer],
+            Self::ShortEventAccessoryStateOff1 => &[ServiceType::EventProducer],
+            Self::ExtOpCode4 => &[],
+            Self::DccSendRawPacket5 => &[],
+            Self::DccWriteCvByteInOpsModeByAddress => &[],
+            Self::DccSendDataToCab => &[],
+            Self::DiagnosticData => &[ServiceType::MinimumNodeService],
+            Self::FastClock => &[],
+            Self::LongEventAccessoryOn2 => {
+                &[ServiceType::EventProducer, ServiceType::EventConsumer]
+            }
+            Self::LongEventAccessoryOff2 => {
+                &[ServiceType::EventProducer, ServiceType::EventConsumer]
+            }
+            Self::TeachEvent => &[ServiceType::EventTeaching],
+            Self::EventVariableValueInLearnMode => &[ServiceType::EventTeaching],
+            Self::LongEventAccessoryStateOn2 => &[ServiceType::EventProducer],
+            Self::LongEventAccessoryStateOff2 => &[ServiceType::EventProducer],
+            Self::ShortEventAccessoryOn2 => {
+                &[ServiceType::EventProducer, ServiceType::EventConsumer]
+            }
+            Self::ShortEventAccessoryOff2 => {
+                &[ServiceType::EventProducer, ServiceType::EventConsumer]
+            }
+            Self::ShortEventAccessoryStateOn2 => &[ServiceType::EventProducer],
+            Self::ShortEventAccessoryStateOff2 => &[ServiceType::EventProducer],
+            Self::ExtOpCode5 => &[],
+            Self::DccSendRawPacket6 => &[],
+            Self::DccLocoReport => &[],
+            Self::ModuleName => &[ServiceType::MinimumNodeService],
+            Self::DccCommandStationStatus => &[],
+            Self::EventAck => {
+                &[ServiceType::EventAcknowledge, ServiceType::EventConsumer]
+            }
+            Self::ExtendedServiceDiscoveryResponse => &[ServiceType::MinimumNodeService],
+            Self::StreamPacket => &[],
+            Self::NodeParametersReport => &[ServiceType::MinimumNodeService],
+            Self::LongEventAccessoryOn3 => {
+                &[ServiceType::EventProducer, ServiceType::EventConsumer]
+            }
+            Self::LongEventAccessoryOff3 => {
+                &[ServiceType::EventProducer, ServiceType::EventConsumer]
+            }
+            Self::LearnedEventResponse => &[ServiceType::EventTeaching],
+            Self::LongEventAccessoryStateOn3 => &[ServiceType::EventProducer],
+            Self::LongEventAccessoryStateOff3 => &[ServiceType::EventProducer],
+            Self::TeachEventByIndex => &[ServiceType::EventTeaching],
+            Self::DataEventAccessory => &[],
+            Self::NodeDataEventResponse => &[],
+            Self::ShortEventAccessoryOn3 => {
+                &[ServiceType::EventProducer, ServiceType::EventConsumer]
+            }
+            Self::ShortEventAccessoryOff3 => {
+                &[ServiceType::EventProducer, ServiceType::EventConsumer]
+            }
+            Self::DeviceDataEventShortMode => &[],
+            Self::DeviceDataResponseShortMode => &[],
+            Self::WriteData => &[],
+            Self::ShortEventAccessoryStateOn3 => &[ServiceType::EventProducer],
+            Self::ShortEventAccessoryStateOff3 => &[ServiceType::EventProducer],
+            Self::ExtOpCode6 => &[],
+        }
+    }
+
+    /// Return the direction of the message as defined by the opcode specification
+    pub const fn direction(self) -> MessageDirection {
+        match self {
+            Self::GeneralAck => MessageDirection::Unspecified,
+            Self::GeneralNack => MessageDirection::Unspecified,
+            Self::BusHalt => MessageDirection::Both,
+            Self::BusResume => MessageDirection::Both,
+            Self::DccTrackPoweredOff => MessageDirection::FromCommandStation,
+            Self::DccTrackPoweredOn => MessageDirection::FromCommandStation,
+            Self::DccEmergencyStopEngaged => MessageDirection::FromCommandStation,
+            Self::RestartAllNodes => MessageDirection::Unspecified,
+            Self::DccTrackPowerOff => MessageDirection::ToCommandStation,
+            Self::DccTrackPowerOn => MessageDirection::ToCommandStation,
+            Self::DccEmergencyStop => MessageDirection::ToCommandStation,
+            Self::DccQueryCommandStationStatus => MessageDirection::ToCommandStation,
+            Self::QueryNodeInfo => MessageDirection::ToModule,
+            Self::QueryNodeParameters => MessageDirection::ToModule,
+            Self::QueryModuleName => MessageDirection::ToModule,
+            Self::DccReleaseSession => MessageDirection::ToCommandStation,
+            Self::DccQueryLocoStatus => MessageDirection::ToCommandStation,
+            Self::DccSessionKeepAlive => MessageDirection::ToCommandStation,
+            Self::DebugMsg1 => MessageDirection::Unspecified,
+            Self::ExtOpCode => MessageDirection::Unspecified,
+            Self::DccRequestNewSession => MessageDirection::ToCommandStation,
+            Self::DccQueryConsist => MessageDirection::ToCommandStation,
+            Self::SetNodeNumber => MessageDirection::ToModule,
+            Self::DccAllocateLocoToActivity => MessageDirection::ToCommandStation,
+            Self::DccSetThrottleMode => MessageDirection::ToCommandStation,
+            Self::DccConsistAddLoco => MessageDirection::ToCommandStation,
+            Self::DccConsistRemoveLoco => MessageDirection::ToModule,
+            Self::DccSetLocoThrottle => MessageDirection::ToCommandStation,
+            Self::DccSetLocoFlags => MessageDirection::ToCommandStation,
+            Self::DccLocoFunctionOn => MessageDirection::ToModule,
+            Self::DccLocoFunctionOff => MessageDirection::ToModule,
+            Self::DccServiceModeStatus => MessageDirection::FromCommandStation,
+            Self::ResetModuleToFactory => MessageDirection::ToModule,
+            Self::RequestNewNodeNumber => MessageDirection::FromModule,
+            Self::NodeNumberReleased => MessageDirection::FromModule,
+            Self::NodeNumberAck => MessageDirection::FromModule,
+            Self::PutNodeIntoLearnMode => MessageDirection::ToModule,
+            Self::ReleaseNodeFromLearnMode => MessageDirection::ToModule,
+            Self::ForgetAllLearnedEvents => MessageDirection::ToModule,
+            Self::QueryAvailableEventSlots => MessageDirection::ToModule,
+            Self::QueryAllLearnedEvents => MessageDirection::ToModule,
+            Self::QueryLearnedEventCount => MessageDirection::ToModule,
+            Self::WriteAck => MessageDirection::FromModule,
+            Self::QueryNodeData => MessageDirection::Both,
+            Self::RequestDeviceDataShortMode => MessageDirection::ToModule,
+            Self::RebootIntoBootloader => MessageDirection::ToModule,
+            Self::ForceCanEnumeration => MessageDirection::ToModule,
+            Self::RestartNode => MessageDirection::ToModule,
+            Self::ExtOpCode1 => MessageDirection::Unspecified,
+            Self::DccSetLocoFunctions => MessageDirection::ToCommandStation,
+            Self::DccQueryLocoSession => MessageDirection::ToCommandStation,
+            Self::DccCommandStationError => MessageDirection::FromCommandStation,
+            Self::NodeConfigurationError => MessageDirection::FromModule,
+            Self::AvailableEventSlots => MessageDirection::FromModule,
+            Self::QueryNodeVariable => MessageDirection::ToModule,
+            Self::QueryLearnedEventByIndex => MessageDirection::ToModule,
+            Self::QueryNodeParameterByIndex => MessageDirection::ToModule,
+            Self::LearnedEventCount => MessageDirection::FromModule,
+            Self::SetNodeCanId => MessageDirection::ToModule,
+            Self::PutNodeIntoMode => MessageDirection::ToModule,
+            Self::ServiceDiscoveryQuery => MessageDirection::ToModule,
+            Self::ExtOpCode2 => MessageDirection::Unspecified,
+            Self::DccSendRawPacket3 => MessageDirection::ToCommandStation,
+            Self::DccWriteCvByteInOpsMode => MessageDirection::ToCommandStation,
+            Self::DcWriteCvBitInOpsMode => MessageDirection::ToCommandStation,
+            Self::DccReadCv => MessageDirection::ToCommandStation,
+            Self::DccCvValue => MessageDirection::FromCommandStation,
+            Self::QueryDiagnosticData => MessageDirection::ToModule,
+            Self::SetNodeVariable => MessageDirection::ToModule,
+            Self::LongEventAccessoryOn => MessageDirection::Unspecified,
+            Self::LongEventAccessoryOff => MessageDirection::Unspecified,
+            Self::QueryLongEventAccessoryState => MessageDirection::ToModule,
+            Self::LongEventAccessoryStateOn => MessageDirection::FromModule,
+            Self::LongEventAccessoryStateOff => MessageDirection::FromModule,
+            Self::ForgetLearnedEvent => MessageDirection::ToModule,
+            Self::LegacySetNodeVariable => MessageDirection::ToModule,
+            Self::NodeVariableValue => MessageDirection::FromModule,
+            Self::ShortEventAccessoryOn => MessageDirection::Unspecified,
+            Self::ShortEventAccessoryOff => MessageDirection::Unspecified,
+            Self::QueryShortEventAccessoryState => MessageDirection::ToModule,
+            Self::NodeParameterValue => MessageDirection::FromModule,
+            Self::QueryEventVariable => MessageDirection::ToModule,
+            Self::ShortEventAccessoryStateOn => MessageDirection::FromModule,
+            Self::ShortEventAccessoryStateOff => MessageDirection::FromModule,
+            Self::ExtOpCode3 => MessageDirection::Unspecified,
+            Self::DccSendRawPacket4 => MessageDirection::ToCommandStation,
+            Self::DccWriteCvInServiceMode => MessageDirection::ToModule,
+            Self::Heartbeat => MessageDirection::FromModule,
+            Self::ServiceDiscoveryResponse => MessageDirection::FromModule,
+            Self::GenericResponse => MessageDirection::FromModule,
+            Self::LongEventAccessoryOn1 => MessageDirection::Unspecified,
+            Self::LongEventAccessoryOff1 => MessageDirection::Unspecified,
+            Self::QueryEventVariableInLearnMode => MessageDirection::ToModule,
+            Self::LongEventAccessoryStateOn1 => MessageDirection::FromModule,
+            Self::LongEventAccessoryStateOff1 => MessageDirection::FromModule,
+            Self::EventVariableValue => MessageDirection::FromModule,
+            Self::NodeInfo => MessageDirection::FromModule,
+            Self::ShortEventAccessoryOn1 => MessageDirection::Unspecified,
+            Self::ShortEventAccessoryOff1 => MessageDirection::Unspecified,
+            Self::ShortEventAccessoryStateOn1 => MessageDirection::FromModule,
+            Self::ShortEventAccessoryStateOff1 => MessageDirection::FromModule,
+            Self::ExtOpCode4 => MessageDirection::Unspecified,
+            Self::DccSendRawPacket5 => MessageDirection::ToModule,
+            Self::DccWriteCvByteInOpsModeByAddress => MessageDirection::ToCommandStation,
+            Self::DccSendDataToCab => MessageDirection::Unspecified,
+            Self::DiagnosticData => MessageDirection::FromModule,
+            Self::FastClock => MessageDirection::Unspecified,
+            Self::LongEventAccessoryOn2 => MessageDirection::Unspecified,
+            Self::LongEventAccessoryOff2 => MessageDirection::Both,
+            Self::TeachEvent => MessageDirection::ToModule,
+            Self::EventVariableValueInLearnMode => MessageDirection::FromModule,
+            Self::LongEventAccessoryStateOn2 => MessageDirection::FromModule,
+            Self::LongEventAccessoryStateOff2 => MessageDirection::FromModule,
+            Self::ShortEventAccessoryOn2 => MessageDirection::Unspecified,
+            Self::ShortEventAccessoryOff2 => MessageDirection::Unspecified,
+            Self::ShortEventAccessoryStateOn2 => MessageDirection::FromModule,
+            Self::ShortEventAccessoryStateOff2 => MessageDirection::FromModule,
+            Self::ExtOpCode5 => MessageDirection::Unspecified,
+            Self::DccSendRawPacket6 => MessageDirection::ToModule,
+            Self::DccLocoReport => MessageDirection::FromCommandStation,
+            Self::ModuleName => MessageDirection::FromModule,
+            Self::DccCommandStationStatus => MessageDirection::FromCommandStation,
+            Self::EventAck => MessageDirection::FromModule,
+            Self::ExtendedServiceDiscoveryResponse => MessageDirection::FromModule,
+            Self::StreamPacket => MessageDirection::Unspecified,
+            Self::NodeParametersReport => MessageDirection::FromModule,
+            Self::LongEventAccessoryOn3 => MessageDirection::Unspecified,
+            Self::LongEventAccessoryOff3 => MessageDirection::Unspecified,
+            Self::LearnedEventResponse => MessageDirection::FromModule,
+            Self::LongEventAccessoryStateOn3 => MessageDirection::FromModule,
+            Self::LongEventAccessoryStateOff3 => MessageDirection::FromModule,
+            Self::TeachEventByIndex => MessageDirection::ToModule,
+            Self::DataEventAccessory => MessageDirection::FromModule,
+            Self::NodeDataEventResponse => MessageDirection::FromModule,
+            Self::ShortEventAccessoryOn3 => MessageDirection::Unspecified,
+            Self::ShortEventAccessoryOff3 => MessageDirection::Unspecified,
+            Self::DeviceDataEventShortMode => MessageDirection::FromModule,
+            Self::DeviceDataResponseShortMode => MessageDirection::FromModule,
+            Self::WriteData => MessageDirection::ToModule,
+            Self::ShortEventAccessoryStateOn3 => MessageDirection::FromModule,
+            Self::ShortEventAccessoryStateOff3 => MessageDirection::FromModule,
+            Self::ExtOpCode6 => MessageDirection::Unspecified,
+        }
+    }
+
+    /// Return the states and modes of the module in which the opcode is used,
+    /// as named by the opcode specification
+    ///
+    /// Returns an empty slice when the specification does not restrict the opcode.
+    pub const fn modes(self) -> &'static [&'static str] {
+        match self {
+            Self::GeneralAck => &["Normal", "Learn", "NOHEARTB"],
+            Self::GeneralNack => &[],
+            Self::BusHalt => &[],
+            Self::BusResume => &[],
+            Self::DccTrackPoweredOff => &[],
+            Self::DccTrackPoweredOn => &[],
+            Self::DccEmergencyStopEngaged => &[],
+            Self::RestartAllNodes => &[],
+            Self::DccTrackPowerOff => &[],
+            Self::DccTrackPowerOn => &[],
+            Self::DccEmergencyStop => &[],
+            Self::DccQueryCommandStationStatus => &[],
+            Self::QueryNodeInfo => &[],
+            Self::QueryNodeParameters => &[],
+            Self::QueryModuleName => &[],
+            Self::DccReleaseSession => &[],
+            Self::DccQueryLocoStatus => &[],
+            Self::DccSessionKeepAlive => &[],
+            Self::DebugMsg1 => &[],
+            Self::ExtOpCode => &[],
+            Self::DccRequestNewSession => &[],
+            Self::DccQueryConsist => &[],
+            Self::SetNodeNumber => &[],
+            Self::DccAllocateLocoToActivity => &[],
+            Self::DccSetThrottleMode => &[],
+            Self::DccConsistAddLoco => &[],
+            Self::DccConsistRemoveLoco => &[],
+            Self::DccSetLocoThrottle => &[],
+            Self::DccSetLocoFlags => &[],
+            Self::DccLocoFunctionOn => &[],
+            Self::DccLocoFunctionOff => &[],
+            Self::DccServiceModeStatus => &[],
+            Self::ResetModuleToFactory => &[],
+            Self::RequestNewNodeNumber => &[],
+            Self::NodeNumberReleased => &[],
+            Self::NodeNumberAck => &[],
+            Self::PutNodeIntoLearnMode => &[],
+            Self::ReleaseNodeFromLearnMode => &[],
+            Self::ForgetAllLearnedEvents => &[],
+            Self::QueryAvailableEventSlots => &[],
+            Self::QueryAllLearnedEvents => &[],
+            Self::QueryLearnedEventCount => &[],
+            Self::WriteAck => &[],
+            Self::QueryNodeData => &[],
+            Self::RequestDeviceDataShortMode => &[],
+            Self::RebootIntoBootloader => &[],
+            Self::ForceCanEnumeration => &[],
+            Self::RestartNode => &[],
+            Self::ExtOpCode1 => &[],
+            Self::DccSetLocoFunctions => &[],
+            Self::DccQueryLocoSession => &[],
+            Self::DccCommandStationError => &[],
+            Self::NodeConfigurationError => &[],
+            Self::AvailableEventSlots => &[],
+            Self::QueryNodeVariable => &[],
+            Self::QueryLearnedEventByIndex => &[],
+            Self::QueryNodeParameterByIndex => &[],
+            Self::LearnedEventCount => &[],
+            Self::SetNodeCanId => &[],
+            Self::PutNodeIntoMode => &[],
+            Self::ServiceDiscoveryQuery => &[],
+            Self::ExtOpCode2 => &[],
+            Self::DccSendRawPacket3 => &[],
+            Self::DccWriteCvByteInOpsMode => &[],
+            Self::DcWriteCvBitInOpsMode => &[],
+            Self::DccReadCv => &[],
+            Self::DccCvValue => &[],
+            Self::QueryDiagnosticData => &[],
+            Self::SetNodeVariable => &[],
+            Self::LongEventAccessoryOn => &[],
+            Self::LongEventAccessoryOff => &[],
+            Self::QueryLongEventAccessoryState => &[],
+            Self::LongEventAccessoryStateOn => &[],
+            Self::LongEventAccessoryStateOff => &[],
+            Self::ForgetLearnedEvent => &[],
+            Self::LegacySetNodeVariable => &[],
+            Self::NodeVariableValue => &[],
+            Self::ShortEventAccessoryOn => &[],
+            Self::ShortEventAccessoryOff => &[],
+            Self::QueryShortEventAccessoryState => &[],
+            Self::NodeParameterValue => &[],
+            Self::QueryEventVariable => &[],
+            Self::ShortEventAccessoryStateOn => &[],
+            Self::ShortEventAccessoryStateOff => &[],
+            Self::ExtOpCode3 => &[],
+            Self::DccSendRawPacket4 => &[],
+            Self::DccWriteCvInServiceMode => &[],
+            Self::Heartbeat => &[],
+            Self::ServiceDiscoveryResponse => &[],
+            Self::GenericResponse => &[],
+            Self::LongEventAccessoryOn1 => &[],
+            Self::LongEventAccessoryOff1 => &[],
+            Self::QueryEventVariableInLearnMode => &[],
+            Self::LongEventAccessoryStateOn1 => &[],
+            Self::LongEventAccessoryStateOff1 => &[],
+            Self::EventVariableValue => &[],
+            Self::NodeInfo => &[],
+            Self::ShortEventAccessoryOn1 => &[],
+            Self::ShortEventAccessoryOff1 => &[],
+            Self::ShortEventAccessoryStateOn1 => &[],
+            Self::ShortEventAccessoryStateOff1 => &[],
+            Self::ExtOpCode4 => &[],
+            Self::DccSendRawPacket5 => &[],
+            Self::DccWriteCvByteInOpsModeByAddress => &[],
+            Self::DccSendDataToCab => &[],
+            Self::DiagnosticData => &[],
+            Self::FastClock => &[],
+            Self::LongEventAccessoryOn2 => &[],
+            Self::LongEventAccessoryOff2 => &[],
+            Self::TeachEvent => &[],
+            Self::EventVariableValueInLearnMode => &[],
+            Self::LongEventAccessoryStateOn2 => &[],
+            Self::LongEventAccessoryStateOff2 => &[],
+            Self::ShortEventAccessoryOn2 => &[],
+            Self::ShortEventAccessoryOff2 => &[],
+            Self::ShortEventAccessoryStateOn2 => &[],
+            Self::ShortEventAccessoryStateOff2 => &[],
+            Self::ExtOpCode5 => &[],
+            Self::DccSendRawPacket6 => &[],
+            Self::DccLocoReport => &[],
+            Self::ModuleName => &[],
+            Self::DccCommandStationStatus => &[],
+            Self::EventAck => &[],
+            Self::ExtendedServiceDiscoveryResponse => &[],
+            Self::StreamPacket => &[],
+            Self::NodeParametersReport => &[],
+            Self::LongEventAccessoryOn3 => &[],
+            Self::LongEventAccessoryOff3 => &[],
+            Self::LearnedEventResponse => &[],
+            Self::LongEventAccessoryStateOn3 => &[],
+            Self::LongEventAccessoryStateOff3 => &[],
+            Self::TeachEventByIndex => &[],
+            Self::DataEventAccessory => &[],
+            Self::NodeDataEventResponse => &[],
+            Self::ShortEventAccessoryOn3 => &[],
+            Self::ShortEventAccessoryOff3 => &[],
+            Self::DeviceDataEventShortMode => &[],
+            Self::DeviceDataResponseShortMode => &[],
+            Self::WriteData => &[],
+            Self::ShortEventAccessoryStateOn3 => &[],
+            Self::ShortEventAccessoryStateOff3 => &[],
+            Self::ExtOpCode6 => &[],
+        }
+    }
+
+    /// Return the parameters following the opcode as defined by the opcode specification
+    pub const fn parameters(self) -> &'static [OpCodeParameter] {
+        match self {
+            Self::GeneralAck => &[],
+            Self::GeneralNack => &[],
+            Self::BusHalt => &[],
+            Self::BusResume => &[],
+            Self::DccTrackPoweredOff => &[],
+            Self::DccTrackPoweredOn => &[],
+            Self::DccEmergencyStopEngaged => &[],
+            Self::RestartAllNodes => &[],
+            Self::DccTrackPowerOff => &[],
+            Self::DccTrackPowerOn => &[],
+            Self::DccEmergencyStop => &[],
+            Self::DccQueryCommandStationStatus => &[],
+            Self::QueryNodeInfo => &[],
+            Self::QueryNodeParameters => &[],
+            Self::QueryModuleName => &[],
+            Self::DccReleaseSession => &[
+                OpCodeParameter {
+                    name: "Session",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccQueryLocoStatus => &[
+                OpCodeParameter {
+                    name: "Session",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccSessionKeepAlive => &[
+                OpCodeParameter {
+                    name: "Session",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DebugMsg1 => &[
+                OpCodeParameter {
+                    name: "Status",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::ExtOpCode => &[
+                OpCodeParameter {
+                    name: "Ext_OPC",
+                    size: 1,
+                    description: "Extended opcode",
+                },
+            ],
+            Self::DccRequestNewSession => &[
+                OpCodeParameter {
+                    name: "Dat1",
+                    size: 1,
+                    description: "AddrH of the decoder",
+                },
+                OpCodeParameter {
+                    name: "Dat2",
+                    size: 1,
+                    description: "AddrL of the decoder",
+                },
+            ],
+            Self::DccQueryConsist => &[
+                OpCodeParameter {
+                    name: "ConID",
+                    size: 1,
+                    description: "Consist ID",
+                },
+                OpCodeParameter {
+                    name: "Index",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::SetNodeNumber => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "the new node number",
+                },
+            ],
+            Self::DccAllocateLocoToActivity => &[
+                OpCodeParameter {
+                    name: "Session ID",
+                    size: 1,
+                    description: "the Session ID.",
+                },
+                OpCodeParameter {
+                    name: "Allocation code",
+                    size: 1,
+                    description: "application specific allocation code.",
+                },
+            ],
+            Self::DccSetThrottleMode => &[
+                OpCodeParameter {
+                    name: "Session",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "mode",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccConsistAddLoco => &[
+                OpCodeParameter {
+                    name: "Session",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Consist#",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccConsistRemoveLoco => &[
+                OpCodeParameter {
+                    name: "Session",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Consist#",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccSetLocoThrottle => &[
+                OpCodeParameter {
+                    name: "Session",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Speed/Dir",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccSetLocoFlags => &[
+                OpCodeParameter {
+                    name: "Session",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "SpeedMode",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccLocoFunctionOn => &[
+                OpCodeParameter {
+                    name: "Session",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Fnum",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccLocoFunctionOff => &[
+                OpCodeParameter {
+                    name: "Session",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Fnum",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccServiceModeStatus => &[
+                OpCodeParameter {
+                    name: "Session",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Status",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::ResetModuleToFactory => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+            ],
+            Self::RequestNewNodeNumber => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Existing Node number",
+                },
+            ],
+            Self::NodeNumberReleased => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+            ],
+            Self::NodeNumberAck => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "the new node number to be used.",
+                },
+            ],
+            Self::PutNodeIntoLearnMode => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+            ],
+            Self::ReleaseNodeFromLearnMode => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+            ],
+            Self::ForgetAllLearnedEvents => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+            ],
+            Self::QueryAvailableEventSlots => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+            ],
+            Self::QueryAllLearnedEvents => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+            ],
+            Self::QueryLearnedEventCount => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+            ],
+            Self::WriteAck => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+            ],
+            Self::QueryNodeData => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+            ],
+            Self::RequestDeviceDataShortMode => &[
+                OpCodeParameter {
+                    name: "DN",
+                    size: 2,
+                    description: "Device number",
+                },
+            ],
+            Self::RebootIntoBootloader => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+            ],
+            Self::ForceCanEnumeration => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+            ],
+            Self::RestartNode => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+            ],
+            Self::ExtOpCode1 => &[
+                OpCodeParameter {
+                    name: "Ext_OPC",
+                    size: 1,
+                    description: "Extended opcode",
+                },
+                OpCodeParameter {
+                    name: "Data1",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccSetLocoFunctions => &[
+                OpCodeParameter {
+                    name: "Session",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Fn1",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Fn2",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccQueryLocoSession => &[
+                OpCodeParameter {
+                    name: "Addr",
+                    size: 2,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Flags",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccCommandStationError => &[
+                OpCodeParameter {
+                    name: "AddrH",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "AddrL",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "ErrorCode",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::NodeConfigurationError => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "Error",
+                    size: 1,
+                    description: "Error number, see CMDERR list in Appendix C",
+                },
+            ],
+            Self::AvailableEventSlots => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "Spaces",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::QueryNodeVariable => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "NV#",
+                    size: 1,
+                    description: "Node variable index",
+                },
+            ],
+            Self::QueryLearnedEventByIndex => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN#",
+                    size: 1,
+                    description: "Event index",
+                },
+            ],
+            Self::QueryNodeParameterByIndex => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "Para#",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::LearnedEventCount => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "No. of events",
+                    size: 1,
+                    description: "Number of events",
+                },
+            ],
+            Self::SetNodeCanId => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "CAN_ID",
+                    size: 1,
+                    description: "CAN identifier",
+                },
+            ],
+            Self::PutNodeIntoMode => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "Mode",
+                    size: 1,
+                    description: "Mode Command. ● Mode command = 0 is a request to transition to Setup Mode ● Mode command = 1 is a request to transition to Normal Mode. Please refer to service specific documentation for other Mode Command definitions.",
+                },
+            ],
+            Self::ServiceDiscoveryQuery => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "ServiceIndex",
+                    size: 1,
+                    description: "Index into the list of services.",
+                },
+            ],
+            Self::ExtOpCode2 => &[
+                OpCodeParameter {
+                    name: "Ext_OPC",
+                    size: 1,
+                    description: "Extended opcode",
+                },
+                OpCodeParameter {
+                    name: "Data1",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Data2",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccSendRawPacket3 => &[
+                OpCodeParameter {
+                    name: "Rep",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Byte1",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Byte2",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Byte3",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccWriteCvByteInOpsMode => &[
+                OpCodeParameter {
+                    name: "Session",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "CV",
+                    size: 2,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Value",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DcWriteCvBitInOpsMode => &[
+                OpCodeParameter {
+                    name: "Session",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "CV",
+                    size: 2,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Value",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccReadCv => &[
+                OpCodeParameter {
+                    name: "Session",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "CV",
+                    size: 2,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Mode",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccCvValue => &[
+                OpCodeParameter {
+                    name: "Session",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "CV",
+                    size: 2,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Value",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::QueryDiagnosticData => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "ServiceIndex",
+                    size: 1,
+                    description: "Index into the list of services",
+                },
+                OpCodeParameter {
+                    name: "DiagnosticCode",
+                    size: 1,
+                    description: "Diagnostic data code",
+                },
+            ],
+            Self::SetNodeVariable => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "NV#",
+                    size: 1,
+                    description: "Node variable index",
+                },
+                OpCodeParameter {
+                    name: "NVvalue",
+                    size: 1,
+                    description: "Node variable value",
+                },
+            ],
+            Self::LongEventAccessoryOn => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+            ],
+            Self::LongEventAccessoryOff => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+            ],
+            Self::QueryLongEventAccessoryState => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+            ],
+            Self::LongEventAccessoryStateOn => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+            ],
+            Self::LongEventAccessoryStateOff => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+            ],
+            Self::ForgetLearnedEvent => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+            ],
+            Self::LegacySetNodeVariable => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "NV#",
+                    size: 1,
+                    description: "Node variable index",
+                },
+                OpCodeParameter {
+                    name: "NV val",
+                    size: 1,
+                    description: "Node variable value",
+                },
+            ],
+            Self::NodeVariableValue => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "NV#",
+                    size: 1,
+                    description: "Node variable index",
+                },
+                OpCodeParameter {
+                    name: "NV val",
+                    size: 1,
+                    description: "Node variable value",
+                },
+            ],
+            Self::ShortEventAccessoryOn => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+            ],
+            Self::ShortEventAccessoryOff => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+            ],
+            Self::QueryShortEventAccessoryState => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+            ],
+            Self::NodeParameterValue => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "Para#",
+                    size: 1,
+                    description: "Parameter index",
+                },
+                OpCodeParameter {
+                    name: "Para val",
+                    size: 1,
+                    description: "Parameter value",
+                },
+            ],
+            Self::QueryEventVariable => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN#",
+                    size: 1,
+                    description: "Event index",
+                },
+                OpCodeParameter {
+                    name: "EV#",
+                    size: 1,
+                    description: "Event variable index",
+                },
+            ],
+            Self::ShortEventAccessoryStateOn => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+            ],
+            Self::ShortEventAccessoryStateOff => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+            ],
+            Self::ExtOpCode3 => &[
+                OpCodeParameter {
+                    name: "Ext_OPC",
+                    size: 1,
+                    description: "Extended opcode",
+                },
+                OpCodeParameter {
+                    name: "Data1",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Data2",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Data3",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccSendRawPacket4 => &[
+                OpCodeParameter {
+                    name: "REP",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Byte0",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Byte1",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Byte2",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Byte3",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccWriteCvInServiceMode => &[
+                OpCodeParameter {
+                    name: "Session",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "CV",
+                    size: 2,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Mode",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Value",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::Heartbeat => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Source Node number",
+                },
+                OpCodeParameter {
+                    name: "Sequence",
+                    size: 1,
+                    description: "The message sequence number",
+                },
+                OpCodeParameter {
+                    name: "Status",
+                    size: 1,
+                    description: "Diagnostic status",
+                },
+                OpCodeParameter {
+                    name: "StatusBits",
+                    size: 1,
+                    description: "Reserved",
+                },
+            ],
+            Self::ServiceDiscoveryResponse => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "ServiceIndex",
+                    size: 1,
+                    description: "Index into the list of services. Note that ServiceIndex values may not be contiguous and therefore the ServiceIndex may be greater than the number of services reported within the first response to RQSD.",
+                },
+                OpCodeParameter {
+                    name: "ServiceType",
+                    size: 1,
+                    description: "Service Type",
+                },
+                OpCodeParameter {
+                    name: "Version",
+                    size: 1,
+                    description: "Version of the service definition, not the version of its implementation.",
+                },
+            ],
+            Self::GenericResponse => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "opcode",
+                    size: 1,
+                    description: "Request opcode",
+                },
+                OpCodeParameter {
+                    name: "ServiceType",
+                    size: 1,
+                    description: "Service Type",
+                },
+                OpCodeParameter {
+                    name: "Result",
+                    size: 1,
+                    description: "Result",
+                },
+            ],
+            Self::LongEventAccessoryOn1 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data",
+                    size: 1,
+                    description: "Event data 1",
+                },
+            ],
+            Self::LongEventAccessoryOff1 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data",
+                    size: 1,
+                    description: "Event data 1",
+                },
+            ],
+            Self::QueryEventVariableInLearnMode => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number of event",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "EV#",
+                    size: 1,
+                    description: "Event variable index",
+                },
+            ],
+            Self::LongEventAccessoryStateOn1 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data",
+                    size: 1,
+                    description: "Event data 1",
+                },
+            ],
+            Self::LongEventAccessoryStateOff1 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data",
+                    size: 1,
+                    description: "Event data 1",
+                },
+            ],
+            Self::EventVariableValue => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN#",
+                    size: 1,
+                    description: "Event index",
+                },
+                OpCodeParameter {
+                    name: "EV#",
+                    size: 1,
+                    description: "Event variable index",
+                },
+                OpCodeParameter {
+                    name: "EVval",
+                    size: 1,
+                    description: "Event variable value",
+                },
+            ],
+            Self::NodeInfo => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "Manufacturer Id",
+                    size: 1,
+                    description: "Manufacturer identifier",
+                },
+                OpCodeParameter {
+                    name: "Module Id",
+                    size: 1,
+                    description: "Module identifier",
+                },
+                OpCodeParameter {
+                    name: "Flags",
+                    size: 1,
+                    description: "Module flags",
+                },
+            ],
+            Self::ShortEventAccessoryOn1 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data",
+                    size: 1,
+                    description: "Event data 1",
+                },
+            ],
+            Self::ShortEventAccessoryOff1 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data",
+                    size: 1,
+                    description: "Event data 1",
+                },
+            ],
+            Self::ShortEventAccessoryStateOn1 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data",
+                    size: 1,
+                    description: "Event data 1",
+                },
+            ],
+            Self::ShortEventAccessoryStateOff1 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data",
+                    size: 1,
+                    description: "Event data 1",
+                },
+            ],
+            Self::ExtOpCode4 => &[
+                OpCodeParameter {
+                    name: "Ext_OPC",
+                    size: 1,
+                    description: "Extended opcode",
+                },
+                OpCodeParameter {
+                    name: "Data1",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Data2",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Data3",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Data4",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccSendRawPacket5 => &[
+                OpCodeParameter {
+                    name: "REP",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Byte 0",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Byte1",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Byte2",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Byte3",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Byte4",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccWriteCvByteInOpsModeByAddress => &[
+                OpCodeParameter {
+                    name: "Addr",
+                    size: 2,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "CV",
+                    size: 2,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Mode",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Value",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccSendDataToCab => &[],
+            Self::DiagnosticData => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "ServiceIndex",
+                    size: 1,
+                    description: "Index into the list of services",
+                },
+                OpCodeParameter {
+                    name: "DiagnosticCode",
+                    size: 1,
+                    description: "Diagnostic data code",
+                },
+                OpCodeParameter {
+                    name: "DiagnosticVal",
+                    size: 2,
+                    description: "Diagnostic data value",
+                },
+            ],
+            Self::FastClock => &[
+                OpCodeParameter {
+                    name: "mins",
+                    size: 1,
+                    description: "Minutes 0-59",
+                },
+                OpCodeParameter {
+                    name: "hrs",
+                    size: 1,
+                    description: "Hours 0-23",
+                },
+                OpCodeParameter {
+                    name: "wdmon",
+                    size: 1,
+                    description: "Bits 0-3 define day of week (1=Sun ..7=Sat). Bits 4-7 define month (1=Jan .. 12=Dec)",
+                },
+                OpCodeParameter {
+                    name: "div",
+                    size: 1,
+                    description: "Divider. 0=freeze",
+                },
+                OpCodeParameter {
+                    name: "mday",
+                    size: 1,
+                    description: "Day of month 1-31",
+                },
+                OpCodeParameter {
+                    name: "temp",
+                    size: 1,
+                    description: "Temperature. Two’s complement -127 to +127",
+                },
+            ],
+            Self::LongEventAccessoryOn2 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data1",
+                    size: 1,
+                    description: "Event data 1",
+                },
+                OpCodeParameter {
+                    name: "data2",
+                    size: 1,
+                    description: "Event data 2",
+                },
+            ],
+            Self::LongEventAccessoryOff2 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data1",
+                    size: 1,
+                    description: "Event data 1",
+                },
+                OpCodeParameter {
+                    name: "data2",
+                    size: 1,
+                    description: "Event data 2",
+                },
+            ],
+            Self::TeachEvent => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Event Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "EV#",
+                    size: 1,
+                    description: "Event variable index",
+                },
+                OpCodeParameter {
+                    name: "(1-n) EV val",
+                    size: 1,
+                    description: "Event variable value",
+                },
+            ],
+            Self::EventVariableValueInLearnMode => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "event’s Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "EV#",
+                    size: 1,
+                    description: "Event variable index",
+                },
+                OpCodeParameter {
+                    name: "EV val",
+                    size: 1,
+                    description: "Event variable value",
+                },
+            ],
+            Self::LongEventAccessoryStateOn2 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data1",
+                    size: 1,
+                    description: "Event data 1",
+                },
+                OpCodeParameter {
+                    name: "data2",
+                    size: 1,
+                    description: "Event data 2",
+                },
+            ],
+            Self::LongEventAccessoryStateOff2 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data1",
+                    size: 1,
+                    description: "Event data 1",
+                },
+                OpCodeParameter {
+                    name: "data2",
+                    size: 1,
+                    description: "Event data 2",
+                },
+            ],
+            Self::ShortEventAccessoryOn2 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data1",
+                    size: 1,
+                    description: "Event data 1",
+                },
+                OpCodeParameter {
+                    name: "data2",
+                    size: 1,
+                    description: "Event data 2",
+                },
+            ],
+            Self::ShortEventAccessoryOff2 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data1",
+                    size: 1,
+                    description: "Event data 1",
+                },
+                OpCodeParameter {
+                    name: "data2",
+                    size: 1,
+                    description: "Event data 2",
+                },
+            ],
+            Self::ShortEventAccessoryStateOn2 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data1",
+                    size: 1,
+                    description: "Event data 1",
+                },
+                OpCodeParameter {
+                    name: "data2",
+                    size: 1,
+                    description: "Event data 2",
+                },
+            ],
+            Self::ShortEventAccessoryStateOff2 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data1",
+                    size: 1,
+                    description: "Event data 1",
+                },
+                OpCodeParameter {
+                    name: "data2",
+                    size: 1,
+                    description: "Event data 2",
+                },
+            ],
+            Self::ExtOpCode5 => &[
+                OpCodeParameter {
+                    name: "Ext_OPC",
+                    size: 1,
+                    description: "Extended opcode",
+                },
+                OpCodeParameter {
+                    name: "Data1",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Data2",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Data3",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Data4",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Data5",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccSendRawPacket6 => &[
+                OpCodeParameter {
+                    name: "REP",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Byte0",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Byte1",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Byte2",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Byte3",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Byte4",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Byte5",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DccLocoReport => &[
+                OpCodeParameter {
+                    name: "Session",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Addr",
+                    size: 2,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Speed/Dir",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Fn1",
+                    size: 1,
+                    description: "Function byte F0-F4",
+                },
+                OpCodeParameter {
+                    name: "Fn2",
+                    size: 1,
+                    description: "Function byte F5-F8",
+                },
+                OpCodeParameter {
+                    name: "Fn3",
+                    size: 1,
+                    description: "Function byte F9-F12",
+                },
+            ],
+            Self::ModuleName => &[
+                OpCodeParameter {
+                    name: "NAME",
+                    size: 7,
+                    description: "Module type name in ASCII and padded on right with spaces (0x20).",
+                },
+            ],
+            Self::DccCommandStationStatus => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "CSnum",
+                    size: 1,
+                    description: "For future expansion -set to zero at present",
+                },
+                OpCodeParameter {
+                    name: "Flags",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Major rev",
+                    size: 1,
+                    description: "Major revision number",
+                },
+                OpCodeParameter {
+                    name: "Minor rev",
+                    size: 1,
+                    description: "Minor revision letter",
+                },
+                OpCodeParameter {
+                    name: "Build no",
+                    size: 1,
+                    description: "Build number.",
+                },
+            ],
+            Self::EventAck => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Module’s Node Number",
+                },
+                OpCodeParameter {
+                    name: "opcode",
+                    size: 1,
+                    description: "The opcode of the event being acknowledged",
+                },
+                OpCodeParameter {
+                    name: "EventNNh",
+                    size: 1,
+                    description: "The high byte of the event’s NN",
+                },
+                OpCodeParameter {
+                    name: "EventNNl",
+                    size: 1,
+                    description: "The low byte of the event’s NN",
+                },
+                OpCodeParameter {
+                    name: "EventENh",
+                    size: 1,
+                    description: "The high byte of the event’s EN",
+                },
+                OpCodeParameter {
+                    name: "EventENl",
+                    size: 1,
+                    description: "The low byte of the event’s EN",
+                },
+            ],
+            Self::ExtendedServiceDiscoveryResponse => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "ServiceIndex",
+                    size: 1,
+                    description: "Index into the list of services",
+                },
+                OpCodeParameter {
+                    name: "ServiceType",
+                    size: 1,
+                    description: "Service Type",
+                },
+                OpCodeParameter {
+                    name: "Data1",
+                    size: 1,
+                    description: "Service specific data",
+                },
+                OpCodeParameter {
+                    name: "Data2",
+                    size: 1,
+                    description: "Service specific data",
+                },
+                OpCodeParameter {
+                    name: "Data3",
+                    size: 1,
+                    description: "Service specific data",
+                },
+            ],
+            Self::StreamPacket => &[],
+            Self::NodeParametersReport => &[
+                OpCodeParameter {
+                    name: "PARA 1",
+                    size: 1,
+                    description: "Parameter 1",
+                },
+                OpCodeParameter {
+                    name: "PARA 2",
+                    size: 1,
+                    description: "Parameter 2",
+                },
+                OpCodeParameter {
+                    name: "PARA 3",
+                    size: 1,
+                    description: "Parameter 3",
+                },
+                OpCodeParameter {
+                    name: "PARA 4",
+                    size: 1,
+                    description: "Parameter 4",
+                },
+                OpCodeParameter {
+                    name: "PARA 5",
+                    size: 1,
+                    description: "Parameter 5",
+                },
+                OpCodeParameter {
+                    name: "PARA 6",
+                    size: 1,
+                    description: "Parameter 6",
+                },
+                OpCodeParameter {
+                    name: "PARA 7",
+                    size: 1,
+                    description: "Parameter 7",
+                },
+            ],
+            Self::LongEventAccessoryOn3 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data1",
+                    size: 1,
+                    description: "Event data 1",
+                },
+                OpCodeParameter {
+                    name: "data2",
+                    size: 1,
+                    description: "Event data 2",
+                },
+                OpCodeParameter {
+                    name: "data3",
+                    size: 1,
+                    description: "Event data 3",
+                },
+            ],
+            Self::LongEventAccessoryOff3 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data1",
+                    size: 1,
+                    description: "Event data 1",
+                },
+                OpCodeParameter {
+                    name: "data2",
+                    size: 1,
+                    description: "Event data 2",
+                },
+                OpCodeParameter {
+                    name: "data3",
+                    size: 1,
+                    description: "Event data 3",
+                },
+            ],
+            Self::LearnedEventResponse => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN3",
+                    size: 1,
+                    description: "Event Node number hi",
+                },
+                OpCodeParameter {
+                    name: "EN2",
+                    size: 1,
+                    description: "Event Node number lo",
+                },
+                OpCodeParameter {
+                    name: "EN1",
+                    size: 1,
+                    description: "Event number hi",
+                },
+                OpCodeParameter {
+                    name: "EN0",
+                    size: 1,
+                    description: "Event number lo",
+                },
+                OpCodeParameter {
+                    name: "EN#",
+                    size: 1,
+                    description: "Event index.",
+                },
+            ],
+            Self::LongEventAccessoryStateOn3 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data1",
+                    size: 1,
+                    description: "Event data 1",
+                },
+                OpCodeParameter {
+                    name: "data2",
+                    size: 1,
+                    description: "Event data 2",
+                },
+                OpCodeParameter {
+                    name: "data3",
+                    size: 1,
+                    description: "Event data 3",
+                },
+            ],
+            Self::LongEventAccessoryStateOff3 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data1",
+                    size: 1,
+                    description: "Event data 1",
+                },
+                OpCodeParameter {
+                    name: "data2",
+                    size: 1,
+                    description: "Event data 2",
+                },
+                OpCodeParameter {
+                    name: "data3",
+                    size: 1,
+                    description: "Event data 3",
+                },
+            ],
+            Self::TeachEventByIndex => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Event Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "EN#",
+                    size: 1,
+                    description: "Event index",
+                },
+                OpCodeParameter {
+                    name: "EV#",
+                    size: 1,
+                    description: "Event variable index",
+                },
+                OpCodeParameter {
+                    name: "EV val",
+                    size: 1,
+                    description: "Event variable value",
+                },
+            ],
+            Self::DataEventAccessory => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "data 1",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "data 2",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "data 3",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "data 4",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "data 5",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::NodeDataEventResponse => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "data 1",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "data 2",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "data 3",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "data 4",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "data 5",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::ShortEventAccessoryOn3 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data1",
+                    size: 1,
+                    description: "Event data 1",
+                },
+                OpCodeParameter {
+                    name: "data2",
+                    size: 1,
+                    description: "Event data 2",
+                },
+                OpCodeParameter {
+                    name: "data3",
+                    size: 1,
+                    description: "Event data 3",
+                },
+            ],
+            Self::ShortEventAccessoryOff3 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data1",
+                    size: 1,
+                    description: "Event data 1",
+                },
+                OpCodeParameter {
+                    name: "data2",
+                    size: 1,
+                    description: "Event data 2",
+                },
+                OpCodeParameter {
+                    name: "data3",
+                    size: 1,
+                    description: "Event data 3",
+                },
+            ],
+            Self::DeviceDataEventShortMode => &[
+                OpCodeParameter {
+                    name: "DN",
+                    size: 2,
+                    description: "Device number",
+                },
+                OpCodeParameter {
+                    name: "data 1",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "data 2",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "data 3",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "data 4",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "data 5",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::DeviceDataResponseShortMode => &[
+                OpCodeParameter {
+                    name: "DN",
+                    size: 2,
+                    description: "Device number",
+                },
+                OpCodeParameter {
+                    name: "data 1",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "data 2",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "data 3",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "data 4",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "data 5",
+                    size: 1,
+                    description: "",
+                },
+            ],
+            Self::WriteData => &[
+                OpCodeParameter {
+                    name: "DN",
+                    size: 2,
+                    description: "Device number",
+                },
+                OpCodeParameter {
+                    name: "data 1",
+                    size: 1,
+                    description: "data to be written to device",
+                },
+                OpCodeParameter {
+                    name: "data 2",
+                    size: 1,
+                    description: "data to be written to device",
+                },
+                OpCodeParameter {
+                    name: "data 3",
+                    size: 1,
+                    description: "data to be written to device",
+                },
+                OpCodeParameter {
+                    name: "data 4",
+                    size: 1,
+                    description: "data to be written to device",
+                },
+                OpCodeParameter {
+                    name: "data 5",
+                    size: 1,
+                    description: "data to be written to device",
+                },
+            ],
+            Self::ShortEventAccessoryStateOn3 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data1",
+                    size: 1,
+                    description: "Event data 1",
+                },
+                OpCodeParameter {
+                    name: "data2",
+                    size: 1,
+                    description: "Event data 2",
+                },
+                OpCodeParameter {
+                    name: "data3",
+                    size: 1,
+                    description: "Event data 3",
+                },
+            ],
+            Self::ShortEventAccessoryStateOff3 => &[
+                OpCodeParameter {
+                    name: "NN",
+                    size: 2,
+                    description: "Node number",
+                },
+                OpCodeParameter {
+                    name: "EN",
+                    size: 2,
+                    description: "Event number",
+                },
+                OpCodeParameter {
+                    name: "data1",
+                    size: 1,
+                    description: "Event data 1",
+                },
+                OpCodeParameter {
+                    name: "data2",
+                    size: 1,
+                    description: "Event data 2",
+                },
+                OpCodeParameter {
+                    name: "data3",
+                    size: 1,
+                    description: "Event data 3",
+                },
+            ],
+            Self::ExtOpCode6 => &[
+                OpCodeParameter {
+                    name: "Ext_OPC",
+                    size: 1,
+                    description: "Extended opcode",
+                },
+                OpCodeParameter {
+                    name: "Data1",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Data2",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Data3",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Data4",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Data5",
+                    size: 1,
+                    description: "",
+                },
+                OpCodeParameter {
+                    name: "Data6",
+                    size: 1,
+                    description: "",
+                },
+            ],
+        }
+    }
+
+    /// Return the opcodes expected in response to the opcode
+    ///
+    /// Returns an empty slice for opcodes that are not requests.
+    pub const fn expected_responses(self) -> &'static [OpCode] {
+        match self {
+            Self::GeneralAck => &[],
+            Self::GeneralNack => &[],
+            Self::BusHalt => &[],
+            Self::BusResume => &[],
+            Self::DccTrackPoweredOff => &[],
+            Self::DccTrackPoweredOn => &[],
+            Self::DccEmergencyStopEngaged => &[],
+            Self::RestartAllNodes => &[],
+            Self::DccTrackPowerOff => &[],
+            Self::DccTrackPowerOn => &[],
+            Self::DccEmergencyStop => &[],
+            Self::DccQueryCommandStationStatus => &[Self::DccCommandStationStatus],
+            Self::QueryNodeInfo => &[Self::NodeInfo],
+            Self::QueryNodeParameters => &[Self::NodeParametersReport],
+            Self::QueryModuleName => &[Self::ModuleName],
+            Self::DccReleaseSession => &[],
+            Self::DccQueryLocoStatus => &[Self::DccLocoReport, Self::DccCommandStationError],
+            Self::DccSessionKeepAlive => &[],
+            Self::DebugMsg1 => &[],
+            Self::ExtOpCode => &[],
+            Self::DccRequestNewSession => &[Self::DccLocoReport, Self::DccCommandStationError],
+            Self::DccQueryConsist => &[],
+            Self::SetNodeNumber => &[Self::NodeNumberAck],
+            Self::DccAllocateLocoToActivity => &[],
+            Self::DccSetThrottleMode => &[],
+            Self::DccConsistAddLoco => &[],
+            Self::DccConsistRemoveLoco => &[],
+            Self::DccSetLocoThrottle => &[],
+            Self::DccSetLocoFlags => &[],
+            Self::DccLocoFunctionOn => &[],
+            Self::DccLocoFunctionOff => &[],
+            Self::DccServiceModeStatus => &[],
+            Self::ResetModuleToFactory => &[],
+            Self::RequestNewNodeNumber => &[],
+            Self::NodeNumberReleased => &[],
+            Self::NodeNumberAck => &[],
+            Self::PutNodeIntoLearnMode => &[],
+            Self::ReleaseNodeFromLearnMode => &[],
+            Self::ForgetAllLearnedEvents => &[
+                Self::WriteAck,
+                Self::NodeConfigurationError,
+                Self::GenericResponse,
+            ],
+            Self::QueryAvailableEventSlots => &[Self::AvailableEventSlots],
+            Self::QueryAllLearnedEvents => &[Self::LearnedEventResponse],
+            Self::QueryLearnedEventCount => &[Self::LearnedEventCount],
+            Self::WriteAck => &[],
+            Self::QueryNodeData => &[Self::NodeDataEventResponse],
+            Self::RequestDeviceDataShortMode => &[Self::DeviceDataResponseShortMode],
+            Self::RebootIntoBootloader => &[],
+            Self::ForceCanEnumeration => &[],
+            Self::RestartNode => &[],
+            Self::ExtOpCode1 => &[],
+            Self::DccSetLocoFunctions => &[],
+            Self::DccQueryLocoSession => &[Self::DccLocoReport, Self::DccCommandStationError],
+            Self::DccCommandStationError => &[],
+            Self::NodeConfigurationError => &[],
+            Self::AvailableEventSlots => &[],
+            Self::QueryNodeVariable => &[
+                Self::NodeVariableValue,
+                Self::NodeConfigurationError,
+                Self::GenericResponse,
+            ],
+            Self::QueryLearnedEventByIndex => &[
+                Self::LearnedEventResponse,
+                Self::NodeConfigurationError,
+                Self::GenericResponse,
+            ],
+            Self::QueryNodeParameterByIndex => &[
+                Self::NodeParameterValue,
+                Self::NodeConfigurationError,
+                Self::GenericResponse,
+            ],
+            Self::LearnedEventCount => &[],
+            Self::SetNodeCanId => &[
+                Self::WriteAck,
+                Self::NodeConfigurationError,
+                Self::GenericResponse,
+            ],
+            Self::PutNodeIntoMode => &[Self::GenericResponse],
+            Self::ServiceDiscoveryQuery => &[
+                Self::ServiceDiscoveryResponse,
+                Self::ExtendedServiceDiscoveryResponse,
+                Self::GenericResponse,
+            ],
+            Self::ExtOpCode2 => &[],
+            Self::DccSendRawPacket3 => &[],
+            Self::DccWriteCvByteInOpsMode => &[],
+            Self::DcWriteCvBitInOpsMode => &[],
+            Self::DccReadCv => &[
+                Self::DccCvValue,
+                Self::DccServiceModeStatus,
+                Self::DccCommandStationError,
+            ],
+            Self::DccCvValue => &[],
+            Self::QueryDiagnosticData => &[Self::DiagnosticData, Self::GenericResponse],
+            Self::SetNodeVariable => &[
+                Self::NodeVariableValue,
+                Self::NodeConfigurationError,
+                Self::GenericResponse,
+            ],
+            Self::LongEventAccessoryOn => &[],
+            Self::LongEventAccessoryOff => &[],
+            Self::QueryLongEventAccessoryState => &[
+                Self::LongEventAccessoryStateOn,
+                Self::LongEventAccessoryStateOff,
+            ],
+            Self::LongEventAccessoryStateOn => &[],
+            Self::LongEventAccessoryStateOff => &[],
+            Self::ForgetLearnedEvent => &[
+                Self::WriteAck,
+                Self::NodeConfigurationError,
+                Self::GenericResponse,
+            ],
+            Self::LegacySetNodeVariable => &[
+                Self::WriteAck,
+                Self::NodeConfigurationError,
+                Self::GenericResponse,
+            ],
+            Self::NodeVariableValue => &[],
+            Self::ShortEventAccessoryOn => &[],
+            Self::ShortEventAccessoryOff => &[],
+            Self::QueryShortEventAccessoryState => &[
+                Self::ShortEventAccessoryStateOn,
+                Self::ShortEventAccessoryStateOff,
+            ],
+            Self::NodeParameterValue => &[],
+            Self::QueryEventVariable => &[
+                Self::EventVariableValue,
+                Self::NodeConfigurationError,
+                Self::GenericResponse,
+            ],
+            Self::ShortEventAccessoryStateOn => &[],
+            Self::ShortEventAccessoryStateOff => &[],
+            Self::ExtOpCode3 => &[],
+            Self::DccSendRawPacket4 => &[],
+            Self::DccWriteCvInServiceMode => &[],
+            Self::Heartbeat => &[],
+            Self::ServiceDiscoveryResponse => &[],
+            Self::GenericResponse => &[],
+            Self::LongEventAccessoryOn1 => &[],
+            Self::LongEventAccessoryOff1 => &[],
+            Self::QueryEventVariableInLearnMode => &[
+                Self::EventVariableValueInLearnMode,
+                Self::NodeConfigurationError,
+                Self::GenericResponse,
+            ],
+            Self::LongEventAccessoryStateOn1 => &[],
+            Self::LongEventAccessoryStateOff1 => &[],
+            Self::EventVariableValue => &[],
+            Self::NodeInfo => &[],
+            Self::ShortEventAccessoryOn1 => &[],
+            Self::ShortEventAccessoryOff1 => &[],
+            Self::ShortEventAccessoryStateOn1 => &[],
+            Self::ShortEventAccessoryStateOff1 => &[],
+            Self::ExtOpCode4 => &[],
+            Self::DccSendRawPacket5 => &[],
+            Self::DccWriteCvByteInOpsModeByAddress => &[],
+            Self::DccSendDataToCab => &[],
+            Self::DiagnosticData => &[],
+            Self::FastClock => &[],
+            Self::LongEventAccessoryOn2 => &[],
+            Self::LongEventAccessoryOff2 => &[],
+            Self::TeachEvent => &[
+                Self::WriteAck,
+                Self::NodeConfigurationError,
+                Self::GenericResponse,
+            ],
+            Self::EventVariableValueInLearnMode => &[],
+            Self::LongEventAccessoryStateOn2 => &[],
+            Self::LongEventAccessoryStateOff2 => &[],
+            Self::ShortEventAccessoryOn2 => &[],
+            Self::ShortEventAccessoryOff2 => &[],
+            Self::ShortEventAccessoryStateOn2 => &[],
+            Self::ShortEventAccessoryStateOff2 => &[],
+            Self::ExtOpCode5 => &[],
+            Self::DccSendRawPacket6 => &[],
+            Self::DccLocoReport => &[],
+            Self::ModuleName => &[],
+            Self::DccCommandStationStatus => &[],
+            Self::EventAck => &[],
+            Self::ExtendedServiceDiscoveryResponse => &[],
+            Self::StreamPacket => &[],
+            Self::NodeParametersReport => &[],
+            Self::LongEventAccessoryOn3 => &[],
+            Self::LongEventAccessoryOff3 => &[],
+            Self::LearnedEventResponse => &[],
+            Self::LongEventAccessoryStateOn3 => &[],
+            Self::LongEventAccessoryStateOff3 => &[],
+            Self::TeachEventByIndex => &[
+                Self::WriteAck,
+                Self::NodeConfigurationError,
+                Self::GenericResponse,
+            ],
+            Self::DataEventAccessory => &[],
+            Self::NodeDataEventResponse => &[],
+            Self::ShortEventAccessoryOn3 => &[],
+            Self::ShortEventAccessoryOff3 => &[],
+            Self::DeviceDataEventShortMode => &[],
+            Self::DeviceDataResponseShortMode => &[],
+            Self::WriteData => &[],
+            Self::ShortEventAccessoryStateOn3 => &[],
+            Self::ShortEventAccessoryStateOff3 => &[],
+            Self::ExtOpCode6 => &[],
+        }
+    }
+}
+/// Direction in which a message is sent as defined by the opcode specification
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    UnsafeFromPrimitive,
+    IntoPrimitive,
+    Eq,
+    PartialEq,
+    TryFromPrimitive,
+)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum MessageDirection {
+    /// Direction is not specified
+    Unspecified = 0,
+    /// Sent to a module, usually by a configuration tool
+    ToModule = 1,
+    /// Sent by a module
+    FromModule = 2,
+    /// Sent to and by a module
+    Both = 3,
+    /// Sent to a command station, usually by a cab
+    ToCommandStation = 4,
+    /// Sent by a command station
+    FromCommandStation = 5,
+}
+/// Opcode priority as defined by the opcode specification
+///
+/// Values match the CAN header minor priority bits, lower value wins arbitration.
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    UnsafeFromPrimitive,
+    IntoPrimitive,
+    Eq,
+    PartialEq,
+    TryFromPrimitive,
+)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum OpCodePriority {
+    /// High priority
+    High = 0,
+    /// Above normal priority
+    AboveNormal = 1,
+    /// Normal priority
+    Normal = 2,
+    /// Low priority
+    Low = 3,
+}
 /// VLCB Service Types
 #[derive(
     Debug,
@@ -1059,7 +4481,7 @@ pub enum ModuleParam {
     BetaVersion = 20,
 }
 bitflags! {
-    #[doc = " Flags in node parameter FLAGS"] #[derive(Debug, Copy, Clone)] pub struct
+    #[doc = " Flags in node parameter FLAGS"] #[derive(Debug, Copy, Clone, Eq, PartialEq)] pub struct
     ModuleFlags : u8 { #[doc = " Module doesn't support events"] const EventsUnsupported
     = 0b00000000; #[doc = " Module is a consumer of events"] const EventConsumer =
     0b00000001; #[doc = " Module is a producer of events"] const EventProducer =
@@ -1086,6 +4508,7 @@ bitflags! {
 )]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(u8)]
+#[allow(non_camel_case_types)]
 pub enum MergModuleType {
     /// default for SLiM nodes
     SLIM = 0,
@@ -1283,6 +4706,7 @@ pub enum MergModuleType {
 )]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(u8)]
+#[allow(non_camel_case_types)]
 pub enum Manufacturer {
     /// Develoment mode manufacturer
     ///
@@ -0,0 +1,284 @@
+use core::fmt::Write;
+
+use heapless::{String, Vec};
+
+use crate::can::CanHeader;
+use crate::error::VlcbError;
+use crate::frame::{CanFrame, FrameId, EXTENDED_ID_MASK, MAX_FRAME_DATA};
+
+/// Maximum length of a GridConnect frame, e.g. `:X1FFFFFFFN0011223344556677;`
+pub const MAX_GRIDCONNECT_LEN: usize = 28;
+
+/// Number of characters between the start and the end marker of the longest frame
+const MAX_BODY_LEN: usize = MAX_GRIDCONNECT_LEN - 2;
+
+/// Extended identifier flag (EXIDE) in the register layout of an extended identifier
+const EXTENDED_FLAG: u32 = 0x0008_0000;
+
+/// Streaming codec of the GridConnect ASCII frame format
+///
+/// A frame starts with `:`, followed by `S` for a standard or `X` for an extended
+/// identifier, the identifier in hex, `N` for a data frame or `R` for a remote
+/// frame, the data in hex and a closing `;`, e.g. `:SB020N9101000005;`.
+///
+/// Identifiers are written the way the CBUS tools do, laid out like in the
+/// registers of the MCP2515 CAN controller. Standard identifiers take 4 hex digits,
+/// shifted left by 5 bits. Extended identifiers take 8 hex digits, with the 11 most
+/// significant bits in the top 11 bits, the extended identifier flag in bit 19 and
+/// the 18 least significant bits below, e.g. `:X00080004N...;` for the bootloader
+/// control frame `0x0000_0004`.
+///
+/// Characters outside of frames, such as line breaks, are skipped. A malformed or
+/// truncated frame is reported as [`VlcbError::MalformedFrame`] and decoding resumes
+/// with the next `:`.
+///
+/// ```
+/// use vlcb_defs::gridconnect::GridConnectCodec;
+/// use vlcb_defs::frame::VlcbFrame;
+/// use vlcb_defs::OpCode;
+///
+/// let mut codec = GridConnectCodec::new();
+/// let frame = codec.decode(b":SB020N9101000005;\r\n").next().unwrap().unwrap();
+/// let VlcbFrame::Message { header, message } = frame.as_frame().unwrap() else {
+///     panic!("not a message");
+/// };
+/// assert_eq!(u8::from(header.can_id), 1);
+/// assert_eq!(message.opcode(), OpCode::LongEventAccessoryOff);
+/// assert_eq!(GridConnectCodec::encode(&frame), ":SB020N9101000005;");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GridConnectCodec {
+    body: Vec<u8, MAX_BODY_LEN>,
+    in_frame: bool,
+}
+
+impl GridConnectCodec {
+    /// Construct a codec waiting for the start of a frame
+    pub const fn new() -> Self {
+        Self {
+            body: Vec::new(),
+            in_frame: false,
+        }
+    }
+
+    /// Drop a partially received frame
+    pub fn reset(&mut self) {
+        self.body.clear();
+        self.in_frame = false;
+    }
+
+    /// Process one received character
+    ///
+    /// Returns a result once a frame has been completed or found malformed.
+    pub fn push(&mut self, byte: u8) -> Option<Result<CanFrame, VlcbError>> {
+        match byte {
+            b':' => {
+                let truncated = self.in_frame;
+                self.body.clear();
+                self.in_frame = true;
+                truncated.then_some(Err(VlcbError::MalformedFrame))
+            }
+            _ if !self.in_frame => None,
+            b';' => {
+                let result = parse(&self.body);
+                self.reset();
+                Some(result)
+            }
+            _ => {
+                if self.body.push(byte).is_err() {
+                    self.reset();
+                    return Some(Err(VlcbError::MalformedFrame));
+                }
+                None
+            }
+        }
+    }
+
+    /// Process a chunk of received characters
+    ///
+    /// Returns an iterator over the frames completed by the chunk. A frame left
+    /// incomplete at the end of the chunk is completed by a later call.
+    pub fn decode<'c, 'b>(&'c mut self, bytes: &'b [u8]) -> Decode<'c, 'b> {
+        Decode { codec: self, bytes }
+    }
+
+    /// Encode a frame into its GridConnect text
+    pub fn encode(frame: &CanFrame) -> String<MAX_GRIDCONNECT_LEN> {
+        let mut text = String::new();
+        // the capacity is sized for the longest frame, writing can not fail
+        let _ = match frame.id() {
+            FrameId::Standard(header) => write!(text, ":S{:04X}", header.to_standard_id() << 5),
+            FrameId::Extended(id) => write!(text, ":X{:08X}", to_registers(id)),
+        };
+        let _ = text.push(if frame.is_remote() { 'R' } else { 'N' });
+        for byte in frame.data() {
+            let _ = write!(text, "{:02X}", byte);
+        }
+        let _ = text.push(';');
+        text
+    }
+}
+
+/// Iterator over the frames of a chunk, see [`GridConnectCodec::decode`]
+#[derive(Debug)]
+pub struct Decode<'c, 'b> {
+    codec: &'c mut GridConnectCodec,
+    bytes: &'b [u8],
+}
+
+impl Iterator for Decode<'_, '_> {
+    type Item = Result<CanFrame, VlcbError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((&byte, rest)) = self.bytes.split_first() {
+            self.bytes = rest;
+            if let Some(result) = self.codec.push(byte) {
+                return Some(result);
+            }
+        }
+        None
+    }
+}
+
+/// Parse the text between `:` and `;`
+fn parse(body: &[u8]) -> Result<CanFrame, VlcbError> {
+    let (&kind, rest) = body.split_first().ok_or(VlcbError::MalformedFrame)?;
+    let split = rest
+        .iter()
+        .position(|c| matches!(c, b'N' | b'n' | b'R' | b'r'))
+        .ok_or(VlcbError::MalformedFrame)?;
+    let (id, rest) = rest.split_at(split);
+    let remote = matches!(rest[0], b'R' | b'r');
+    let data = &rest[1..];
+
+    let id = match kind {
+        b'S' | b's' if (1..=4).contains(&id.len()) => {
+            let raw = parse_hex(id)?;
+            FrameId::Standard(CanHeader::from_standard_id((raw >> 5) as u16)?)
+        }
+        b'X' | b'x' if (1..=8).contains(&id.len()) => {
+            FrameId::Extended(from_registers(parse_hex(id)?))
+        }
+        _ => return Err(VlcbError::MalformedFrame),
+    };
+
+    if remote {
+        if !data.is_empty() {
+            return Err(VlcbError::MalformedFrame);
+        }
+        return CanFrame::remote(id);
+    }
+
+    if data.len() % 2 != 0 || data.len() > 2 * MAX_FRAME_DATA {
+        return Err(VlcbError::MalformedFrame);
+    }
+    let mut bytes = [0; MAX_FRAME_DATA];
+    for (byte, pair) in bytes.iter_mut().zip(data.chunks_exact(2)) {
+        *byte = parse_hex(pair)? as u8;
+    }
+    CanFrame::new(id, &bytes[..data.len() / 2])
+}
+
+/// Lay out an extended identifier like the SIDH, SIDL, EIDH and EIDL registers
+fn to_registers(id: u32) -> u32 {
+    let id = id & EXTENDED_ID_MASK;
+    (id >> 18) << 21 | EXTENDED_FLAG | id & 0x3_FFFF
+}
+
+/// Read an extended identifier laid out like the registers, see [`to_registers`]
+///
+/// The flag and the unused bits of SIDL are ignored.
+fn from_registers(registers: u32) -> u32 {
+    (registers >> 21) << 18 | registers & 0x3_FFFF
+}
+
+fn parse_hex(digits: &[u8]) -> Result<u32, VlcbError> {
+    digits.iter().try_fold(0u32, |acc, &c| {
+        let digit = (c as char).to_digit(16).ok_or(VlcbError::MalformedFrame)?;
+        Ok(acc << 4 | digit)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::can::{MajorPriority, MinorPriority, VlcbCanId};
+    use crate::frame::{FrameKind, VlcbFrame, BOOTLOADER_CONTROL_ID, BOOTLOADER_RESPONSE_ID};
+    use crate::OpCode;
+    use alloc::vec::Vec;
+
+    fn decode_all(codec: &mut GridConnectCodec, text: &[u8]) -> Vec<Result<CanFrame, VlcbError>> {
+        codec.decode(text).collect()
+    }
+
+    #[test]
+    fn test_standard() {
+        let mut codec = GridConnectCodec::new();
+        let frames = decode_all(&mut codec, b":S0FA0N9000010002;");
+        let frame = frames[0].unwrap();
+        let FrameId::Standard(header) = frame.id() else {
+            panic!("not a standard frame");
+        };
+        assert_eq!(header.major_priority, MajorPriority::Highest);
+        assert_eq!(header.minor_priority, MinorPriority::High);
+        assert_eq!(header.can_id, VlcbCanId([0x7D]));
+        assert_eq!(frame.data(), &[0x90, 0x00, 0x01, 0x00, 0x02]);
+        assert_eq!(GridConnectCodec::encode(&frame), ":S0FA0N9000010002;");
+
+        let header = CanHeader::for_opcode(VlcbCanId([0x05]), OpCode::BusHalt);
+        let frame = CanFrame::from(VlcbFrame::message(header, &[0x02]).unwrap());
+        let text = GridConnectCodec::encode(&frame);
+        assert_eq!(text, ":S80A0N02;");
+        assert_eq!(decode_all(&mut codec, text.as_bytes()), [Ok(frame)]);
+    }
+
+    #[test]
+    fn test_remote_and_extended() {
+        let mut codec = GridConnectCodec::new();
+        let rtr = CanFrame::remote(FrameId::Standard(CanHeader::default())).unwrap();
+        assert_eq!(GridConnectCodec::encode(&rtr), ":SA000R;");
+        assert_eq!(decode_all(&mut codec, b":sa000r;"), [Ok(rtr)]);
+
+        let ext = CanFrame::new(FrameId::Extended(BOOTLOADER_CONTROL_ID), &[1, 2, 3]).unwrap();
+        assert_eq!(GridConnectCodec::encode(&ext), ":X00080004N010203;");
+        assert_eq!(decode_all(&mut codec, b":X80004N010203;"), [Ok(ext)]);
+        assert_eq!(ext.kind(), FrameKind::Bootloader);
+
+        let response = CanFrame::new(FrameId::Extended(BOOTLOADER_RESPONSE_ID), &[1]).unwrap();
+        assert_eq!(decode_all(&mut codec, b":X80180004N01;"), [Ok(response)]);
+        assert_eq!(GridConnectCodec::encode(&response), ":X80080004N01;");
+
+        let long = CanFrame::new(FrameId::Extended(EXTENDED_ID_MASK), &[0xFF; 8]).unwrap();
+        let text = GridConnectCodec::encode(&long);
+        assert_eq!(text.len(), MAX_GRIDCONNECT_LEN);
+        assert_eq!(decode_all(&mut codec, text.as_bytes()), [Ok(long)]);
+    }
+
+    #[test]
+    fn test_stream() {
+        let mut codec = GridConnectCodec::new();
+        assert!(decode_all(&mut codec, b"junk\r\n:SB0").is_empty());
+        assert_eq!(decode_all(&mut codec, b"20N9101000005;\n").len(), 1);
+
+        let results = decode_all(
+            &mut codec,
+            b":SB020N91;:S:SB020N9101000005;:X123456789N;:SB020N910;:SB020R00;:Q;:SB020;",
+        );
+        assert!(results[0].is_ok());
+        assert_eq!(results[1], Err(VlcbError::MalformedFrame));
+        assert!(results[2].is_ok());
+        assert!(results[3..]
+            .iter()
+            .all(|r| *r == Err(VlcbError::MalformedFrame)));
+        assert_eq!(results.len(), 8);
+
+        let overflow = [b'0'; MAX_GRIDCONNECT_LEN];
+        let mut text = Vec::from(&b":SB020N"[..]);
+        text.extend_from_slice(&overflow);
+        text.extend_from_slice(b";:SB020N02;");
+        let results = decode_all(&mut codec, &text);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0], Err(VlcbError::MalformedFrame));
+        assert!(results[1].is_ok());
+    }
+}
@@ -12,4 +12,27 @@ pub mod dcc;
 pub mod fast_clock;
 pub mod vlcb;
 pub mod can;
-pub mod macros;
\ No newline at end of file
+pub mod message;
+pub mod message_ref;
+pub mod builder;
+pub mod opcode;
+pub mod macros;
+pub mod error;
+pub mod event;
+pub mod clock;
+pub mod request;
+pub mod extended;
+pub mod frame;
+pub mod enumeration;
+pub mod tx;
+pub mod gridconnect;
+#[cfg(feature = "std")]
+pub mod transport;
+#[cfg(feature = "std")]
+pub mod tcp;
+#[cfg(feature = "std")]
+pub mod capture;
+pub mod load;
+pub mod event_table;
+#[cfg(all(feature = "socketcan", target_os = "linux"))]
+pub mod socketcan;
\ No newline at end of file
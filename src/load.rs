@@ -0,0 +1,262 @@
+use crate::can::{VlcbCanId, CANID_MASK};
+use crate::clock::Clock;
+use crate::frame::VlcbFrame;
+use crate::OpCode;
+
+/// Bit rate of a CBUS network in bit/s
+pub const CBUS_BITRATE: u32 = 125_000;
+
+/// Worst-case length in bits of a CAN frame with `data_len` data octets
+///
+/// Counts the whole frame including the interframe space, with the maximum
+/// number of stuff bits the frame can carry.
+pub const fn frame_bits(data_len: usize, extended: bool) -> u32 {
+    let data_bits = 8 * data_len as u32;
+    // bits subject to stuffing: SOF, arbitration and control fields, data and CRC
+    let stuffed = if extended { 54 } else { 34 } + data_bits;
+    // CRC delimiter, ACK, EOF and interframe space are never stuffed
+    stuffed + 13 + (stuffed - 1) / 4
+}
+
+/// Worst-case length in bits of a frame carrying a message with `opcode`
+pub const fn opcode_frame_bits(opcode: OpCode) -> u32 {
+    frame_bits(opcode.frame_len(), false)
+}
+
+/// Worst-case length in bits of a frame
+pub fn vlcb_frame_bits(frame: &VlcbFrame<'_>) -> u32 {
+    match frame {
+        VlcbFrame::Extended { data, .. } => frame_bits(data.len(), true),
+        _ => frame_bits(frame.data().len(), false),
+    }
+}
+
+/// Warning returned when the bus load exceeds the budget
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LoadWarning {
+    /// Bus utilisation when the budget was exceeded, from 0 to 1
+    pub utilisation: f32,
+    /// The configured budget
+    pub budget: f32,
+}
+
+/// Bits of frames sent in one window, in total, by CAN ID and by opcode
+#[derive(Debug, Clone)]
+struct Window {
+    total: u32,
+    by_can_id: [u32; 128],
+    by_opcode: [u32; 256],
+}
+
+impl Window {
+    const EMPTY: Self = Self {
+        total: 0,
+        by_can_id: [0; 128],
+        by_opcode: [0; 256],
+    };
+}
+
+/// Estimator of the utilisation of a CBUS network
+///
+/// Each recorded frame counts with its worst-case length, see [`frame_bits`].
+/// The utilisation is the share of the bus time used by frames in a sliding window
+/// of `window_ms` milliseconds, estimated by weighting the previous fixed window by
+/// its overlap with the sliding window. It is tracked in total, by [`VlcbCanId`] and
+/// by [`OpCode`].
+#[derive(Debug, Clone)]
+pub struct BusLoadEstimator {
+    window_ms: u64,
+    bitrate: u32,
+    budget: Option<f32>,
+    over_budget: bool,
+    start_ms: u64,
+    previous: Window,
+    current: Window,
+}
+
+impl BusLoadEstimator {
+    /// Construct an estimator of a CBUS network using a window of `window_ms` milliseconds
+    ///
+    /// A window of 0 is raised to 1 ms.
+    pub const fn new(window_ms: u64) -> Self {
+        Self {
+            window_ms: if window_ms == 0 { 1 } else { window_ms },
+            bitrate: CBUS_BITRATE,
+            budget: None,
+            over_budget: false,
+            start_ms: 0,
+            previous: Window::EMPTY,
+            current: Window::EMPTY,
+        }
+    }
+
+    /// Set the bit rate of the network, [`CBUS_BITRATE`] by default
+    pub const fn with_bitrate(mut self, bitrate: u32) -> Self {
+        self.bitrate = if bitrate == 0 { 1 } else { bitrate };
+        self
+    }
+
+    /// Set the utilisation budget, from 0 to 1
+    pub const fn with_budget(mut self, budget: f32) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Record a frame sent on the bus
+    ///
+    /// Returns a warning when the utilisation goes over the budget. The warning is
+    /// repeated only after the utilisation has dropped back to the budget.
+    pub fn record(&mut self, clock: &impl Clock, frame: &VlcbFrame<'_>) -> Option<LoadWarning> {
+        let now = clock.now_ms();
+        self.advance(now);
+
+        let bits = vlcb_frame_bits(frame);
+        self.current.total = self.current.total.saturating_add(bits);
+        if let Some(header) = frame.header() {
+            let slot = &mut self.current.by_can_id[(header.can_id.0[0] & CANID_MASK) as usize];
+            *slot = slot.saturating_add(bits);
+        }
+        if let Some(message) = frame.message_ref() {
+            let slot = &mut self.current.by_opcode[message.opcode() as usize];
+            *slot = slot.saturating_add(bits);
+        }
+
+        let budget = self.budget?;
+        let utilisation = self.utilisation(clock);
+        let was_over = core::mem::replace(&mut self.over_budget, utilisation > budget);
+        (self.over_budget && !was_over).then_some(LoadWarning {
+            utilisation,
+            budget,
+        })
+    }
+
+    /// Return the total utilisation of the bus, from 0 to 1
+    pub fn utilisation(&self, clock: &impl Clock) -> f32 {
+        self.estimate(clock.now_ms(), |window| window.total)
+    }
+
+    /// Return the utilisation of the bus by frames sent with a CAN ID
+    pub fn can_id_utilisation(&self, clock: &impl Clock, can_id: VlcbCanId) -> f32 {
+        let index = (can_id.0[0] & CANID_MASK) as usize;
+        self.estimate(clock.now_ms(), |window| window.by_can_id[index])
+    }
+
+    /// Return the utilisation of the bus by messages with an opcode
+    pub fn opcode_utilisation(&self, clock: &impl Clock, opcode: OpCode) -> f32 {
+        self.estimate(clock.now_ms(), |window| window.by_opcode[opcode as usize])
+    }
+
+    /// Check whether the utilisation was over the budget at the last recorded frame
+    pub fn is_over_budget(&self) -> bool {
+        self.over_budget
+    }
+
+    /// Forget all recorded frames
+    pub fn reset(&mut self) {
+        self.previous = Window::EMPTY;
+        self.current = Window::EMPTY;
+        self.over_budget = false;
+    }
+
+    /// Move the fixed windows forward to include `now`
+    fn advance(&mut self, now: u64) {
+        let elapsed = now.saturating_sub(self.start_ms);
+        if elapsed < self.window_ms {
+            return;
+        }
+
+        if elapsed < 2 * self.window_ms {
+            self.previous = core::mem::replace(&mut self.current, Window::EMPTY);
+        } else {
+            self.previous = Window::EMPTY;
+            self.current = Window::EMPTY;
+        }
+        self.start_ms = now - elapsed % self.window_ms;
+    }
+
+    /// Estimate the share of the sliding window ending at `now` used by the bits of `select`
+    fn estimate(&self, now: u64, select: impl Fn(&Window) -> u32) -> f32 {
+        let elapsed = now.saturating_sub(self.start_ms);
+        let window = self.window_ms as f32;
+        let (previous, current, offset) = if elapsed < self.window_ms {
+            (select(&self.previous), select(&self.current), elapsed)
+        } else if elapsed < 2 * self.window_ms {
+            (select(&self.current), 0, elapsed - self.window_ms)
+        } else {
+            return 0.0;
+        };
+
+        let overlap = 1.0 - offset as f32 / window;
+        let bits = previous as f32 * overlap + current as f32;
+        let capacity = self.bitrate as f32 * window / 1000.0;
+        bits / capacity
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::can::CanHeader;
+    use core::cell::Cell;
+
+    #[test]
+    fn test_frame_bits() {
+        assert_eq!(frame_bits(0, false), 55);
+        assert_eq!(frame_bits(8, false), 135);
+        assert_eq!(frame_bits(0, true), 80);
+        assert_eq!(frame_bits(8, true), 160);
+        assert_eq!(opcode_frame_bits(OpCode::LongEventAccessoryOn), 105);
+        assert_eq!(
+            vlcb_frame_bits(&VlcbFrame::Extended {
+                id: 4,
+                data: &[0; 8]
+            }),
+            160
+        );
+    }
+
+    #[test]
+    fn test_utilisation() {
+        let now = Cell::new(0u64);
+        let clock = || now.get();
+        let mut estimator = BusLoadEstimator::new(100).with_budget(0.5);
+
+        let acon = [0x90, 0, 1, 0, 2];
+        let a = CanHeader::for_opcode(VlcbCanId([1]), OpCode::LongEventAccessoryOn);
+        let b = CanHeader::for_opcode(VlcbCanId([2]), OpCode::LongEventAccessoryOn);
+        // 100 ms at 125 kbit/s hold 12500 bits, 50 frames of 105 bits are 42%
+        for i in 0..50 {
+            let header = if i % 2 == 0 { a } else { b };
+            let frame = VlcbFrame::message(header, &acon).unwrap();
+            assert_eq!(estimator.record(&clock, &frame), None);
+        }
+        assert!((estimator.utilisation(&clock) - 0.42).abs() < 1e-4);
+        assert!((estimator.can_id_utilisation(&clock, VlcbCanId([1])) - 0.21).abs() < 1e-4);
+        assert!(
+            (estimator.opcode_utilisation(&clock, OpCode::LongEventAccessoryOn) - 0.42).abs()
+                < 1e-4
+        );
+        assert_eq!(estimator.opcode_utilisation(&clock, OpCode::BusHalt), 0.0);
+
+        // half of the previous window slides out
+        now.set(150);
+        assert!((estimator.utilisation(&clock) - 0.21).abs() < 1e-4);
+
+        now.set(160);
+        let frame = VlcbFrame::message(a, &acon).unwrap();
+        let mut warning = None;
+        for _ in 0..60 {
+            warning = warning.or(estimator.record(&clock, &frame));
+        }
+        let warning = warning.unwrap();
+        assert!(warning.utilisation > 0.5);
+        assert!(estimator.is_over_budget());
+        assert_eq!(estimator.record(&clock, &frame), None);
+
+        now.set(1000);
+        assert_eq!(estimator.utilisation(&clock), 0.0);
+        estimator.record(&clock, &VlcbFrame::Empty { header: a });
+        assert!(!estimator.is_over_budget());
+    }
+}
@@ -0,0 +1,2111 @@
+use byteorder::{ByteOrder, NetworkEndian};
+use num_enum::{FromPrimitive, TryFromPrimitive};
+
+use crate::can::{VlcbCanId, CANID_MASK};
+use crate::dcc::{LocoAddress, LocoFunctionRange};
+use crate::error::DecodeError;
+use crate::extended::ExtendedMessage;
+use crate::fast_clock::{FastClockMonth, FastClockWeekday};
+use crate::vlcb::{EventId, VlcbNodeNumber, EVENT_SIZE, NODENUM_SIZE};
+use crate::{
+    CabDataCode, CommandError, DccError, DccServiceModeStatus, GenericResponseStatus, ModuleFlags,
+    ModuleMode, OpCode, ServiceType,
+};
+
+/// Maximum size of a VLCB message in octets, including the opcode.
+pub const MAX_MESSAGE_SIZE: usize = 8;
+
+/// Result reported by a [`OpCode::GenericResponse`] (GRSP) message
+///
+/// GRSP carries either a [`GenericResponseStatus`] or the [`CommandError`] a module
+/// would report with CMDERR, the codes of both do not overlap.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum GenericResponseResult {
+    /// A status, including [`GenericResponseStatus::Ok`]
+    Status(GenericResponseStatus),
+    /// A command error
+    CommandError(CommandError),
+}
+
+impl GenericResponseResult {
+    /// Check whether the result reports success
+    pub fn is_ok(&self) -> bool {
+        *self == Self::Status(GenericResponseStatus::Ok)
+    }
+}
+
+impl From<GenericResponseStatus> for GenericResponseResult {
+    fn from(status: GenericResponseStatus) -> Self {
+        Self::Status(status)
+    }
+}
+
+impl From<CommandError> for GenericResponseResult {
+    fn from(error: CommandError) -> Self {
+        Self::CommandError(error)
+    }
+}
+
+impl From<GenericResponseResult> for u8 {
+    fn from(result: GenericResponseResult) -> Self {
+        match result {
+            GenericResponseResult::Status(status) => status.into(),
+            GenericResponseResult::CommandError(error) => error.into(),
+        }
+    }
+}
+
+impl TryFrom<u8> for GenericResponseResult {
+    type Error = DecodeError;
+
+    /// Returns [`DecodeError::InvalidEnumValue`] if `value` is neither a status nor
+    /// a command error.
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        if let Ok(status) = GenericResponseStatus::try_from_primitive(value) {
+            return Ok(Self::Status(status));
+        }
+        if let Ok(error) = CommandError::try_from_primitive(value) {
+            return Ok(Self::CommandError(error));
+        }
+        Err(DecodeError::InvalidEnumValue {
+            type_name: "GenericResponseResult",
+            value,
+        })
+    }
+}
+
+/// A decoded VLCB message
+///
+/// Every variant corresponds to the [`OpCode`] of the same name and carries
+/// the parameters of the message in their typed form.
+///
+/// Short events are decoded with [`EventId::short_from_bytes`], so the node number
+/// part of the event is retained but ignored when matching the event.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum VlcbMessage {
+    /// [`OpCode::GeneralAck`]
+    GeneralAck,
+    /// [`OpCode::GeneralNack`]
+    GeneralNack,
+    /// [`OpCode::BusHalt`]
+    BusHalt,
+    /// [`OpCode::BusResume`]
+    BusResume,
+    /// [`OpCode::DccTrackPoweredOff`]
+    DccTrackPoweredOff,
+    /// [`OpCode::DccTrackPoweredOn`]
+    DccTrackPoweredOn,
+    /// [`OpCode::DccEmergencyStopEngaged`]
+    DccEmergencyStopEngaged,
+    /// [`OpCode::RestartAllNodes`]
+    RestartAllNodes,
+    /// [`OpCode::DccTrackPowerOff`]
+    DccTrackPowerOff,
+    /// [`OpCode::DccTrackPowerOn`]
+    DccTrackPowerOn,
+    /// [`OpCode::DccEmergencyStop`]
+    DccEmergencyStop,
+    /// [`OpCode::DccQueryCommandStationStatus`]
+    DccQueryCommandStationStatus,
+    /// [`OpCode::QueryNodeInfo`]
+    QueryNodeInfo,
+    /// [`OpCode::QueryNodeParameters`]
+    QueryNodeParameters,
+    /// [`OpCode::QueryModuleName`]
+    QueryModuleName,
+    /// [`OpCode::DccReleaseSession`]
+    DccReleaseSession { session: u8 },
+    /// [`OpCode::DccQueryLocoStatus`]
+    DccQueryLocoStatus { session: u8 },
+    /// [`OpCode::DccSessionKeepAlive`]
+    DccSessionKeepAlive { session: u8 },
+    /// [`OpCode::DebugMsg1`]
+    DebugMsg1 { status: u8 },
+    /// [`OpCode::DccRequestNewSession`]
+    DccRequestNewSession { address: LocoAddress },
+    /// [`OpCode::DccQueryConsist`]
+    DccQueryConsist { consist: u8, index: u8 },
+    /// [`OpCode::SetNodeNumber`]
+    SetNodeNumber { node_number: VlcbNodeNumber },
+    /// [`OpCode::DccAllocateLocoToActivity`]
+    DccAllocateLocoToActivity { session: u8, allocation_code: u8 },
+    /// [`OpCode::DccSetThrottleMode`]
+    DccSetThrottleMode { session: u8, mode: u8 },
+    /// [`OpCode::DccConsistAddLoco`]
+    DccConsistAddLoco { session: u8, consist: u8 },
+    /// [`OpCode::DccConsistRemoveLoco`]
+    DccConsistRemoveLoco { session: u8, consist: u8 },
+    /// [`OpCode::DccSetLocoThrottle`]
+    DccSetLocoThrottle { session: u8, speed_dir: u8 },
+    /// [`OpCode::DccSetLocoFlags`]
+    DccSetLocoFlags { session: u8, flags: u8 },
+    /// [`OpCode::DccLocoFunctionOn`]
+    DccLocoFunctionOn { session: u8, function: u8 },
+    /// [`OpCode::DccLocoFunctionOff`]
+    DccLocoFunctionOff { session: u8, function: u8 },
+    /// [`OpCode::DccServiceModeStatus`]
+    DccServiceModeStatus {
+        session: u8,
+        status: DccServiceModeStatus,
+    },
+    /// [`OpCode::ResetModuleToFactory`]
+    ResetModuleToFactory { node_number: VlcbNodeNumber },
+    /// [`OpCode::RequestNewNodeNumber`]
+    RequestNewNodeNumber { node_number: VlcbNodeNumber },
+    /// [`OpCode::NodeNumberReleased`]
+    NodeNumberReleased { node_number: VlcbNodeNumber },
+    /// [`OpCode::NodeNumberAck`]
+    NodeNumberAck { node_number: VlcbNodeNumber },
+    /// [`OpCode::PutNodeIntoLearnMode`]
+    PutNodeIntoLearnMode { node_number: VlcbNodeNumber },
+    /// [`OpCode::ReleaseNodeFromLearnMode`]
+    ReleaseNodeFromLearnMode { node_number: VlcbNodeNumber },
+    /// [`OpCode::ForgetAllLearnedEvents`]
+    ForgetAllLearnedEvents { node_number: VlcbNodeNumber },
+    /// [`OpCode::QueryAvailableEventSlots`]
+    QueryAvailableEventSlots { node_number: VlcbNodeNumber },
+    /// [`OpCode::QueryAllLearnedEvents`]
+    QueryAllLearnedEvents { node_number: VlcbNodeNumber },
+    /// [`OpCode::QueryLearnedEventCount`]
+    QueryLearnedEventCount { node_number: VlcbNodeNumber },
+    /// [`OpCode::WriteAck`]
+    WriteAck { node_number: VlcbNodeNumber },
+    /// [`OpCode::QueryNodeData`]
+    QueryNodeData { node_number: VlcbNodeNumber },
+    /// [`OpCode::RequestDeviceDataShortMode`]
+    RequestDeviceDataShortMode { device_number: u16 },
+    /// [`OpCode::RebootIntoBootloader`]
+    RebootIntoBootloader { node_number: VlcbNodeNumber },
+    /// [`OpCode::ForceCanEnumeration`]
+    ForceCanEnumeration { node_number: VlcbNodeNumber },
+    /// [`OpCode::RestartNode`]
+    RestartNode { node_number: VlcbNodeNumber },
+    /// [`OpCode::DccSetLocoFunctions`]
+    DccSetLocoFunctions {
+        session: u8,
+        range: LocoFunctionRange,
+        functions: u8,
+    },
+    /// [`OpCode::DccQueryLocoSession`]
+    DccQueryLocoSession { address: LocoAddress, flags: u8 },
+    /// [`OpCode::DccCommandStationError`]
+    DccCommandStationError { data: [u8; 2], error: DccError },
+    /// [`OpCode::NodeConfigurationError`]
+    NodeConfigurationError {
+        node_number: VlcbNodeNumber,
+        error: CommandError,
+    },
+    /// [`OpCode::AvailableEventSlots`]
+    AvailableEventSlots {
+        node_number: VlcbNodeNumber,
+        spaces: u8,
+    },
+    /// [`OpCode::QueryNodeVariable`]
+    QueryNodeVariable {
+        node_number: VlcbNodeNumber,
+        index: u8,
+    },
+    /// [`OpCode::QueryLearnedEventByIndex`]
+    QueryLearnedEventByIndex {
+        node_number: VlcbNodeNumber,
+        event_index: u8,
+    },
+    /// [`OpCode::QueryNodeParameterByIndex`]
+    QueryNodeParameterByIndex {
+        node_number: VlcbNodeNumber,
+        /// Index of the parameter, see [`crate::ModuleParam::try_from`] for the defined indices
+        param: u8,
+    },
+    /// [`OpCode::LearnedEventCount`]
+    LearnedEventCount {
+        node_number: VlcbNodeNumber,
+        count: u8,
+    },
+    /// [`OpCode::SetNodeCanId`]
+    SetNodeCanId {
+        node_number: VlcbNodeNumber,
+        can_id: VlcbCanId,
+    },
+    /// [`OpCode::PutNodeIntoMode`]
+    PutNodeIntoMode {
+        node_number: VlcbNodeNumber,
+        mode: ModuleMode,
+    },
+    /// [`OpCode::ServiceDiscoveryQuery`]
+    ServiceDiscoveryQuery {
+        node_number: VlcbNodeNumber,
+        service_index: u8,
+    },
+    /// [`OpCode::DccSendRawPacket3`]
+    DccSendRawPacket3 { repeat: u8, packet: [u8; 3] },
+    /// [`OpCode::DccWriteCvByteInOpsMode`]
+    DccWriteCvByteInOpsMode { session: u8, cv: u16, value: u8 },
+    /// [`OpCode::DcWriteCvBitInOpsMode`]
+    DcWriteCvBitInOpsMode { session: u8, cv: u16, value: u8 },
+    /// [`OpCode::DccReadCv`]
+    DccReadCv { session: u8, cv: u16, mode: u8 },
+    /// [`OpCode::DccCvValue`]
+    DccCvValue { session: u8, cv: u16, value: u8 },
+    /// [`OpCode::QueryDiagnosticData`]
+    QueryDiagnosticData {
+        node_number: VlcbNodeNumber,
+        service_index: u8,
+        diagnostic_code: u8,
+    },
+    /// [`OpCode::SetNodeVariable`]
+    SetNodeVariable {
+        node_number: VlcbNodeNumber,
+        index: u8,
+        value: u8,
+    },
+    /// [`OpCode::LongEventAccessoryOn`]
+    LongEventAccessoryOn { event: EventId },
+    /// [`OpCode::LongEventAccessoryOff`]
+    LongEventAccessoryOff { event: EventId },
+    /// [`OpCode::QueryLongEventAccessoryState`]
+    QueryLongEventAccessoryState { event: EventId },
+    /// [`OpCode::LongEventAccessoryStateOn`]
+    LongEventAccessoryStateOn { event: EventId },
+    /// [`OpCode::LongEventAccessoryStateOff`]
+    LongEventAccessoryStateOff { event: EventId },
+    /// [`OpCode::ForgetLearnedEvent`]
+    ForgetLearnedEvent { event: EventId },
+    /// [`OpCode::LegacySetNodeVariable`]
+    LegacySetNodeVariable {
+        node_number: VlcbNodeNumber,
+        index: u8,
+        value: u8,
+    },
+    /// [`OpCode::NodeVariableValue`]
+    NodeVariableValue {
+        node_number: VlcbNodeNumber,
+        index: u8,
+        value: u8,
+    },
+    /// [`OpCode::ShortEventAccessoryOn`]
+    ShortEventAccessoryOn { event: EventId },
+    /// [`OpCode::ShortEventAccessoryOff`]
+    ShortEventAccessoryOff { event: EventId },
+    /// [`OpCode::QueryShortEventAccessoryState`]
+    QueryShortEventAccessoryState { event: EventId },
+    /// [`OpCode::NodeParameterValue`]
+    NodeParameterValue {
+        node_number: VlcbNodeNumber,
+        /// Index of the parameter, see [`crate::ModuleParam::try_from`] for the defined indices
+        param: u8,
+        value: u8,
+    },
+    /// [`OpCode::QueryEventVariable`]
+    QueryEventVariable {
+        node_number: VlcbNodeNumber,
+        event_index: u8,
+        ev_index: u8,
+    },
+    /// [`OpCode::ShortEventAccessoryStateOn`]
+    ShortEventAccessoryStateOn { event: EventId },
+    /// [`OpCode::ShortEventAccessoryStateOff`]
+    ShortEventAccessoryStateOff { event: EventId },
+    /// [`OpCode::DccSendRawPacket4`]
+    DccSendRawPacket4 { repeat: u8, packet: [u8; 4] },
+    /// [`OpCode::DccWriteCvInServiceMode`]
+    DccWriteCvInServiceMode {
+        session: u8,
+        cv: u16,
+        mode: u8,
+        value: u8,
+    },
+    /// [`OpCode::Heartbeat`]
+    Heartbeat {
+        node_number: VlcbNodeNumber,
+        sequence: u8,
+        status: u8,
+        status_bits: u8,
+    },
+    /// [`OpCode::ServiceDiscoveryResponse`]
+    ServiceDiscoveryResponse {
+        node_number: VlcbNodeNumber,
+        service_index: u8,
+        service: ServiceType,
+        version: u8,
+    },
+    /// [`OpCode::GenericResponse`]
+    GenericResponse {
+        node_number: VlcbNodeNumber,
+        opcode: OpCode,
+        service: ServiceType,
+        result: GenericResponseResult,
+    },
+    /// [`OpCode::LongEventAccessoryOn1`]
+    LongEventAccessoryOn1 { event: EventId, data: [u8; 1] },
+    /// [`OpCode::LongEventAccessoryOff1`]
+    LongEventAccessoryOff1 { event: EventId, data: [u8; 1] },
+    /// [`OpCode::QueryEventVariableInLearnMode`]
+    QueryEventVariableInLearnMode { event: EventId, ev_index: u8 },
+    /// [`OpCode::LongEventAccessoryStateOn1`]
+    LongEventAccessoryStateOn1 { event: EventId, data: [u8; 1] },
+    /// [`OpCode::LongEventAccessoryStateOff1`]
+    LongEventAccessoryStateOff1 { event: EventId, data: [u8; 1] },
+    /// [`OpCode::EventVariableValue`]
+    EventVariableValue {
+        node_number: VlcbNodeNumber,
+        event_index: u8,
+        ev_index: u8,
+        value: u8,
+    },
+    /// [`OpCode::NodeInfo`]
+    NodeInfo {
+        node_number: VlcbNodeNumber,
+        /// Manufacturer ID, see [`crate::Manufacturer::try_from`] for the known manufacturers
+        manufacturer: u8,
+        module_type: u8,
+        flags: ModuleFlags,
+    },
+    /// [`OpCode::ShortEventAccessoryOn1`]
+    ShortEventAccessoryOn1 { event: EventId, data: [u8; 1] },
+    /// [`OpCode::ShortEventAccessoryOff1`]
+    ShortEventAccessoryOff1 { event: EventId, data: [u8; 1] },
+    /// [`OpCode::ShortEventAccessoryStateOn1`]
+    ShortEventAccessoryStateOn1 { event: EventId, data: [u8; 1] },
+    /// [`OpCode::ShortEventAccessoryStateOff1`]
+    ShortEventAccessoryStateOff1 { event: EventId, data: [u8; 1] },
+    /// [`OpCode::DccSendRawPacket5`]
+    DccSendRawPacket5 { repeat: u8, packet: [u8; 5] },
+    /// [`OpCode::DccWriteCvByteInOpsModeByAddress`]
+    DccWriteCvByteInOpsModeByAddress {
+        address: LocoAddress,
+        cv: u16,
+        mode: u8,
+        value: u8,
+    },
+    /// [`OpCode::DccSendDataToCab`]
+    DccSendDataToCab {
+        address: LocoAddress,
+        code: CabDataCode,
+        data: [u8; 3],
+    },
+    /// [`OpCode::DiagnosticData`]
+    DiagnosticData {
+        node_number: VlcbNodeNumber,
+        service_index: u8,
+        diagnostic_code: u8,
+        value: u16,
+    },
+    /// [`OpCode::FastClock`]
+    FastClock {
+        minutes: u8,
+        hours: u8,
+        weekday: FastClockWeekday,
+        month: FastClockMonth,
+        divider: u8,
+        day: u8,
+        temperature: i8,
+    },
+    /// [`OpCode::LongEventAccessoryOn2`]
+    LongEventAccessoryOn2 { event: EventId, data: [u8; 2] },
+    /// [`OpCode::LongEventAccessoryOff2`]
+    LongEventAccessoryOff2 { event: EventId, data: [u8; 2] },
+    /// [`OpCode::TeachEvent`]
+    TeachEvent {
+        event: EventId,
+        ev_index: u8,
+        value: u8,
+    },
+    /// [`OpCode::EventVariableValueInLearnMode`]
+    EventVariableValueInLearnMode {
+        event: EventId,
+        ev_index: u8,
+        value: u8,
+    },
+    /// [`OpCode::LongEventAccessoryStateOn2`]
+    LongEventAccessoryStateOn2 { event: EventId, data: [u8; 2] },
+    /// [`OpCode::LongEventAccessoryStateOff2`]
+    LongEventAccessoryStateOff2 { event: EventId, data: [u8; 2] },
+    /// [`OpCode::ShortEventAccessoryOn2`]
+    ShortEventAccessoryOn2 { event: EventId, data: [u8; 2] },
+    /// [`OpCode::ShortEventAccessoryOff2`]
+    ShortEventAccessoryOff2 { event: EventId, data: [u8; 2] },
+    /// [`OpCode::ShortEventAccessoryStateOn2`]
+    ShortEventAccessoryStateOn2 { event: EventId, data: [u8; 2] },
+    /// [`OpCode::ShortEventAccessoryStateOff2`]
+    ShortEventAccessoryStateOff2 { event: EventId, data: [u8; 2] },
+    /// [`OpCode::DccSendRawPacket6`]
+    DccSendRawPacket6 { repeat: u8, packet: [u8; 6] },
+    /// [`OpCode::DccLocoReport`]
+    DccLocoReport {
+        session: u8,
+        address: LocoAddress,
+        speed_dir: u8,
+        functions: [u8; 3],
+    },
+    /// [`OpCode::ModuleName`]
+    ModuleName { name: [u8; 7] },
+    /// [`OpCode::DccCommandStationStatus`]
+    DccCommandStationStatus {
+        node_number: VlcbNodeNumber,
+        cs_number: u8,
+        flags: u8,
+        major: u8,
+        minor: u8,
+        build: u8,
+    },
+    /// [`OpCode::EventAck`]
+    EventAck {
+        node_number: VlcbNodeNumber,
+        opcode: OpCode,
+        event: EventId,
+    },
+    /// [`OpCode::ExtendedServiceDiscoveryResponse`]
+    ExtendedServiceDiscoveryResponse {
+        node_number: VlcbNodeNumber,
+        service_index: u8,
+        service: ServiceType,
+        data: [u8; 3],
+    },
+    /// [`OpCode::StreamPacket`]
+    StreamPacket { data: [u8; 7] },
+    /// [`OpCode::NodeParametersReport`]
+    NodeParametersReport { params: [u8; 7] },
+    /// [`OpCode::LongEventAccessoryOn3`]
+    LongEventAccessoryOn3 { event: EventId, data: [u8; 3] },
+    /// [`OpCode::LongEventAccessoryOff3`]
+    LongEventAccessoryOff3 { event: EventId, data: [u8; 3] },
+    /// [`OpCode::LearnedEventResponse`]
+    LearnedEventResponse {
+        node_number: VlcbNodeNumber,
+        event: EventId,
+        event_index: u8,
+    },
+    /// [`OpCode::LongEventAccessoryStateOn3`]
+    LongEventAccessoryStateOn3 { event: EventId, data: [u8; 3] },
+    /// [`OpCode::LongEventAccessoryStateOff3`]
+    LongEventAccessoryStateOff3 { event: EventId, data: [u8; 3] },
+    /// [`OpCode::TeachEventByIndex`]
+    TeachEventByIndex {
+        event: EventId,
+        event_index: u8,
+        ev_index: u8,
+        value: u8,
+    },
+    /// [`OpCode::DataEventAccessory`]
+    DataEventAccessory {
+        node_number: VlcbNodeNumber,
+        data: [u8; 5],
+    },
+    /// [`OpCode::NodeDataEventResponse`]
+    NodeDataEventResponse {
+        node_number: VlcbNodeNumber,
+        data: [u8; 5],
+    },
+    /// [`OpCode::ShortEventAccessoryOn3`]
+    ShortEventAccessoryOn3 { event: EventId, data: [u8; 3] },
+    /// [`OpCode::ShortEventAccessoryOff3`]
+    ShortEventAccessoryOff3 { event: EventId, data: [u8; 3] },
+    /// [`OpCode::DeviceDataEventShortMode`]
+    DeviceDataEventShortMode { device_number: u16, data: [u8; 5] },
+    /// [`OpCode::DeviceDataResponseShortMode`]
+    DeviceDataResponseShortMode { device_number: u16, data: [u8; 5] },
+    /// [`OpCode::WriteData`]
+    WriteData { device_number: u16, data: [u8; 5] },
+    /// [`OpCode::ShortEventAccessoryStateOn3`]
+    ShortEventAccessoryStateOn3 { event: EventId, data: [u8; 3] },
+    /// [`OpCode::ShortEventAccessoryStateOff3`]
+    ShortEventAccessoryStateOff3 { event: EventId, data: [u8; 3] },
+    /// Any of the extended opcodes [`OpCode::ExtOpCode`] - [`OpCode::ExtOpCode6`]
+    Extended(ExtendedMessage),
+}
+
+impl VlcbMessage {
+    /// Decode a message from a sequence of octets, starting with the opcode.
+    ///
+    /// The length of `data` has to match the length defined by the opcode exactly,
+    /// see [`OpCode::validate_frame`].
+    pub fn decode(data: &[u8]) -> Result<Self, DecodeError> {
+        let opcode = OpCode::validate_frame(data)?;
+
+        let mut r = Reader(&data[1..]);
+        let message = match opcode {
+            OpCode::GeneralAck => Self::GeneralAck,
+            OpCode::GeneralNack => Self::GeneralNack,
+            OpCode::BusHalt => Self::BusHalt,
+            OpCode::BusResume => Self::BusResume,
+            OpCode::DccTrackPoweredOff => Self::DccTrackPoweredOff,
+            OpCode::DccTrackPoweredOn => Self::DccTrackPoweredOn,
+            OpCode::DccEmergencyStopEngaged => Self::DccEmergencyStopEngaged,
+            OpCode::RestartAllNodes => Self::RestartAllNodes,
+            OpCode::DccTrackPowerOff => Self::DccTrackPowerOff,
+            OpCode::DccTrackPowerOn => Self::DccTrackPowerOn,
+            OpCode::DccEmergencyStop => Self::DccEmergencyStop,
+            OpCode::DccQueryCommandStationStatus => Self::DccQueryCommandStationStatus,
+            OpCode::QueryNodeInfo => Self::QueryNodeInfo,
+            OpCode::QueryNodeParameters => Self::QueryNodeParameters,
+            OpCode::QueryModuleName => Self::QueryModuleName,
+            OpCode::DccReleaseSession => Self::DccReleaseSession { session: r.u8() },
+            OpCode::DccQueryLocoStatus => Self::DccQueryLocoStatus { session: r.u8() },
+            OpCode::DccSessionKeepAlive => Self::DccSessionKeepAlive { session: r.u8() },
+            OpCode::DebugMsg1 => Self::DebugMsg1 { status: r.u8() },
+            OpCode::DccRequestNewSession => Self::DccRequestNewSession {
+                address: r.loco_address()?,
+            },
+            OpCode::DccQueryConsist => Self::DccQueryConsist {
+                consist: r.u8(),
+                index: r.u8(),
+            },
+            OpCode::SetNodeNumber => Self::SetNodeNumber {
+                node_number: r.node_number(),
+            },
+            OpCode::DccAllocateLocoToActivity => Self::DccAllocateLocoToActivity {
+                session: r.u8(),
+                allocation_code: r.u8(),
+            },
+            OpCode::DccSetThrottleMode => Self::DccSetThrottleMode {
+                session: r.u8(),
+                mode: r.u8(),
+            },
+            OpCode::DccConsistAddLoco => Self::DccConsistAddLoco {
+                session: r.u8(),
+                consist: r.u8(),
+            },
+            OpCode::DccConsistRemoveLoco => Self::DccConsistRemoveLoco {
+                session: r.u8(),
+                consist: r.u8(),
+            },
+            OpCode::DccSetLocoThrottle => Self::DccSetLocoThrottle {
+                session: r.u8(),
+                speed_dir: r.u8(),
+            },
+            OpCode::DccSetLocoFlags => Self::DccSetLocoFlags {
+                session: r.u8(),
+                flags: r.u8(),
+            },
+            OpCode::DccLocoFunctionOn => Self::DccLocoFunctionOn {
+                session: r.u8(),
+                function: r.u8(),
+            },
+            OpCode::DccLocoFunctionOff => Self::DccLocoFunctionOff {
+                session: r.u8(),
+                function: r.u8(),
+            },
+            OpCode::DccServiceModeStatus => Self::DccServiceModeStatus {
+                session: r.u8(),
+                status: r.try_enum()?,
+            },
+            OpCode::ResetModuleToFactory => Self::ResetModuleToFactory {
+                node_number: r.node_number(),
+            },
+            OpCode::RequestNewNodeNumber => Self::RequestNewNodeNumber {
+                node_number: r.node_number(),
+            },
+            OpCode::NodeNumberReleased => Self::NodeNumberReleased {
+                node_number: r.node_number(),
+            },
+            OpCode::NodeNumberAck => Self::NodeNumberAck {
+                node_number: r.node_number(),
+            },
+            OpCode::PutNodeIntoLearnMode => Self::PutNodeIntoLearnMode {
+                node_number: r.node_number(),
+            },
+            OpCode::ReleaseNodeFromLearnMode => Self::ReleaseNodeFromLearnMode {
+                node_number: r.node_number(),
+            },
+            OpCode::ForgetAllLearnedEvents => Self::ForgetAllLearnedEvents {
+                node_number: r.node_number(),
+            },
+            OpCode::QueryAvailableEventSlots => Self::QueryAvailableEventSlots {
+                node_number: r.node_number(),
+            },
+            OpCode::QueryAllLearnedEvents => Self::QueryAllLearnedEvents {
+                node_number: r.node_number(),
+            },
+            OpCode::QueryLearnedEventCount => Self::QueryLearnedEventCount {
+                node_number: r.node_number(),
+            },
+            OpCode::WriteAck => Self::WriteAck {
+                node_number: r.node_number(),
+            },
+            OpCode::QueryNodeData => Self::QueryNodeData {
+                node_number: r.node_number(),
+            },
+            OpCode::RequestDeviceDataShortMode => Self::RequestDeviceDataShortMode {
+                device_number: r.u16(),
+            },
+            OpCode::RebootIntoBootloader => Self::RebootIntoBootloader {
+                node_number: r.node_number(),
+            },
+            OpCode::ForceCanEnumeration => Self::ForceCanEnumeration {
+                node_number: r.node_number(),
+            },
+            OpCode::RestartNode => Self::RestartNode {
+                node_number: r.node_number(),
+            },
+            OpCode::DccSetLocoFunctions => Self::DccSetLocoFunctions {
+                session: r.u8(),
+                range: r.try_enum()?,
+                functions: r.u8(),
+            },
+            OpCode::DccQueryLocoSession => Self::DccQueryLocoSession {
+                address: r.loco_address()?,
+                flags: r.u8(),
+            },
+            OpCode::DccCommandStationError => Self::DccCommandStationError {
+                data: r.take(),
+                error: r.try_enum()?,
+            },
+            OpCode::NodeConfigurationError => Self::NodeConfigurationError {
+                node_number: r.node_number(),
+                error: r.try_enum()?,
+            },
+            OpCode::AvailableEventSlots => Self::AvailableEventSlots {
+                node_number: r.node_number(),
+                spaces: r.u8(),
+            },
+            OpCode::QueryNodeVariable => Self::QueryNodeVariable {
+                node_number: r.node_number(),
+                index: r.u8(),
+            },
+            OpCode::QueryLearnedEventByIndex => Self::QueryLearnedEventByIndex {
+                node_number: r.node_number(),
+                event_index: r.u8(),
+            },
+            OpCode::QueryNodeParameterByIndex => Self::QueryNodeParameterByIndex {
+                node_number: r.node_number(),
+                param: r.u8(),
+            },
+            OpCode::LearnedEventCount => Self::LearnedEventCount {
+                node_number: r.node_number(),
+                count: r.u8(),
+            },
+            OpCode::SetNodeCanId => Self::SetNodeCanId {
+                node_number: r.node_number(),
+                can_id: r.can_id()?,
+            },
+            OpCode::PutNodeIntoMode => Self::PutNodeIntoMode {
+                node_number: r.node_number(),
+                mode: r.checked_enum("ModuleMode")?,
+            },
+            OpCode::ServiceDiscoveryQuery => Self::ServiceDiscoveryQuery {
+                node_number: r.node_number(),
+                service_index: r.u8(),
+            },
+            OpCode::DccSendRawPacket3 => Self::DccSendRawPacket3 {
+                repeat: r.u8(),
+                packet: r.take(),
+            },
+            OpCode::DccWriteCvByteInOpsMode => Self::DccWriteCvByteInOpsMode {
+                session: r.u8(),
+                cv: r.u16(),
+                value: r.u8(),
+            },
+            OpCode::DcWriteCvBitInOpsMode => Self::DcWriteCvBitInOpsMode {
+                session: r.u8(),
+                cv: r.u16(),
+                value: r.u8(),
+            },
+            OpCode::DccReadCv => Self::DccReadCv {
+                session: r.u8(),
+                cv: r.u16(),
+                mode: r.u8(),
+            },
+            OpCode::DccCvValue => Self::DccCvValue {
+                session: r.u8(),
+                cv: r.u16(),
+                value: r.u8(),
+            },
+            OpCode::QueryDiagnosticData => Self::QueryDiagnosticData {
+                node_number: r.node_number(),
+                service_index: r.u8(),
+                diagnostic_code: r.u8(),
+            },
+            OpCode::SetNodeVariable => Self::SetNodeVariable {
+                node_number: r.node_number(),
+                index: r.u8(),
+                value: r.u8(),
+            },
+            OpCode::LongEventAccessoryOn => Self::LongEventAccessoryOn { event: r.event() },
+            OpCode::LongEventAccessoryOff => Self::LongEventAccessoryOff { event: r.event() },
+            OpCode::QueryLongEventAccessoryState => {
+                Self::QueryLongEventAccessoryState { event: r.event() }
+            }
+            OpCode::LongEventAccessoryStateOn => {
+                Self::LongEventAccessoryStateOn { event: r.event() }
+            }
+            OpCode::LongEventAccessoryStateOff => {
+                Self::LongEventAccessoryStateOff { event: r.event() }
+            }
+            OpCode::ForgetLearnedEvent => Self::ForgetLearnedEvent { event: r.event() },
+            OpCode::LegacySetNodeVariable => Self::LegacySetNodeVariable {
+                node_number: r.node_number(),
+                index: r.u8(),
+                value: r.u8(),
+            },
+            OpCode::NodeVariableValue => Self::NodeVariableValue {
+                node_number: r.node_number(),
+                index: r.u8(),
+                value: r.u8(),
+            },
+            OpCode::ShortEventAccessoryOn => Self::ShortEventAccessoryOn {
+                event: r.short_event(),
+            },
+            OpCode::ShortEventAccessoryOff => Self::ShortEventAccessoryOff {
+                event: r.short_event(),
+            },
+            OpCode::QueryShortEventAccessoryState => Self::QueryShortEventAccessoryState {
+                event: r.short_event(),
+            },
+            OpCode::NodeParameterValue => Self::NodeParameterValue {
+                node_number: r.node_number(),
+                param: r.u8(),
+                value: r.u8(),
+            },
+            OpCode::QueryEventVariable => Self::QueryEventVariable {
+                node_number: r.node_number(),
+                event_index: r.u8(),
+                ev_index: r.u8(),
+            },
+            OpCode::ShortEventAccessoryStateOn => Self::ShortEventAccessoryStateOn {
+                event: r.short_event(),
+            },
+            OpCode::ShortEventAccessoryStateOff => Self::ShortEventAccessoryStateOff {
+                event: r.short_event(),
+            },
+            OpCode::DccSendRawPacket4 => Self::DccSendRawPacket4 {
+                repeat: r.u8(),
+                packet: r.take(),
+            },
+            OpCode::DccWriteCvInServiceMode => Self::DccWriteCvInServiceMode {
+                session: r.u8(),
+                cv: r.u16(),
+                mode: r.u8(),
+                value: r.u8(),
+            },
+            OpCode::Heartbeat => Self::Heartbeat {
+                node_number: r.node_number(),
+                sequence: r.u8(),
+                status: r.u8(),
+                status_bits: r.u8(),
+            },
+            OpCode::ServiceDiscoveryResponse => Self::ServiceDiscoveryResponse {
+                node_number: r.node_number(),
+                service_index: r.u8(),
+                service: r.try_enum()?,
+                version: r.u8(),
+            },
+            OpCode::GenericResponse => Self::GenericResponse {
+                node_number: r.node_number(),
+                opcode: r.try_enum()?,
+                service: r.try_enum()?,
+                result: GenericResponseResult::try_from(r.u8())?,
+            },
+            OpCode::LongEventAccessoryOn1 => Self::LongEventAccessoryOn1 {
+                event: r.event(),
+                data: r.take(),
+            },
+            OpCode::LongEventAccessoryOff1 => Self::LongEventAccessoryOff1 {
+                event: r.event(),
+                data: r.take(),
+            },
+            OpCode::QueryEventVariableInLearnMode => Self::QueryEventVariableInLearnMode {
+                event: r.event(),
+                ev_index: r.u8(),
+            },
+            OpCode::LongEventAccessoryStateOn1 => Self::LongEventAccessoryStateOn1 {
+                event: r.event(),
+                data: r.take(),
+            },
+            OpCode::LongEventAccessoryStateOff1 => Self::LongEventAccessoryStateOff1 {
+                event: r.event(),
+                data: r.take(),
+            },
+            OpCode::EventVariableValue => Self::EventVariableValue {
+                node_number: r.node_number(),
+                event_index: r.u8(),
+                ev_index: r.u8(),
+                value: r.u8(),
+            },
+            OpCode::NodeInfo => Self::NodeInfo {
+                node_number: r.node_number(),
+                manufacturer: r.u8(),
+                module_type: r.u8(),
+                flags: ModuleFlags::from_bits_retain(r.u8()),
+            },
+            OpCode::ShortEventAccessoryOn1 => Self::ShortEventAccessoryOn1 {
+                event: r.short_event(),
+                data: r.take(),
+            },
+            OpCode::ShortEventAccessoryOff1 => Self::ShortEventAccessoryOff1 {
+                event: r.short_event(),
+                data: r.take(),
+            },
+            OpCode::ShortEventAccessoryStateOn1 => Self::ShortEventAccessoryStateOn1 {
+                event: r.short_event(),
+                data: r.take(),
+            },
+            OpCode::ShortEventAccessoryStateOff1 => Self::ShortEventAccessoryStateOff1 {
+                event: r.short_event(),
+                data: r.take(),
+            },
+            OpCode::DccSendRawPacket5 => Self::DccSendRawPacket5 {
+                repeat: r.u8(),
+                packet: r.take(),
+            },
+            OpCode::DccWriteCvByteInOpsModeByAddress => Self::DccWriteCvByteInOpsModeByAddress {
+                address: r.loco_address()?,
+                cv: r.u16(),
+                mode: r.u8(),
+                value: r.u8(),
+            },
+            OpCode::DccSendDataToCab => Self::DccSendDataToCab {
+                address: r.loco_address()?,
+                code: r.try_enum()?,
+                data: r.take(),
+            },
+            OpCode::DiagnosticData => Self::DiagnosticData {
+                node_number: r.node_number(),
+                service_index: r.u8(),
+                diagnostic_code: r.u8(),
+                value: r.u16(),
+            },
+            OpCode::FastClock => {
+                let minutes = r.u8();
+                let hours = r.u8();
+                let wdmon = r.u8();
+                Self::FastClock {
+                    minutes,
+                    hours,
+                    weekday: checked_from(wdmon & 0x0F, "FastClockWeekday")?,
+                    month: checked_from(wdmon >> 4, "FastClockMonth")?,
+                    divider: r.u8(),
+                    day: r.u8(),
+                    temperature: r.u8() as i8,
+                }
+            }
+            OpCode::LongEventAccessoryOn2 => Self::LongEventAccessoryOn2 {
+                event: r.event(),
+                data: r.take(),
+            },
+            OpCode::LongEventAccessoryOff2 => Self::LongEventAccessoryOff2 {
+                event: r.event(),
+                data: r.take(),
+            },
+            OpCode::TeachEvent => Self::TeachEvent {
+                event: r.event(),
+                ev_index: r.u8(),
+                value: r.u8(),
+            },
+            OpCode::EventVariableValueInLearnMode => Self::EventVariableValueInLearnMode {
+                event: r.event(),
+                ev_index: r.u8(),
+                value: r.u8(),
+            },
+            OpCode::LongEventAccessoryStateOn2 => Self::LongEventAccessoryStateOn2 {
+                event: r.event(),
+                data: r.take(),
+            },
+            OpCode::LongEventAccessoryStateOff2 => Self::LongEventAccessoryStateOff2 {
+                event: r.event(),
+                data: r.take(),
+            },
+            OpCode::ShortEventAccessoryOn2 => Self::ShortEventAccessoryOn2 {
+                event: r.short_event(),
+                data: r.take(),
+            },
+            OpCode::ShortEventAccessoryOff2 => Self::ShortEventAccessoryOff2 {
+                event: r.short_event(),
+                data: r.take(),
+            },
+            OpCode::ShortEventAccessoryStateOn2 => Self::ShortEventAccessoryStateOn2 {
+                event: r.short_event(),
+                data: r.take(),
+            },
+            OpCode::ShortEventAccessoryStateOff2 => Self::ShortEventAccessoryStateOff2 {
+                event: r.short_event(),
+                data: r.take(),
+            },
+            OpCode::DccSendRawPacket6 => Self::DccSendRawPacket6 {
+                repeat: r.u8(),
+                packet: r.take(),
+            },
+            OpCode::DccLocoReport => Self::DccLocoReport {
+                session: r.u8(),
+                address: r.loco_address()?,
+                speed_dir: r.u8(),
+                functions: r.take(),
+            },
+            OpCode::ModuleName => Self::ModuleName { name: r.take() },
+            OpCode::DccCommandStationStatus => Self::DccCommandStationStatus {
+                node_number: r.node_number(),
+                cs_number: r.u8(),
+                flags: r.u8(),
+                major: r.u8(),
+                minor: r.u8(),
+                build: r.u8(),
+            },
+            OpCode::EventAck => Self::EventAck {
+                node_number: r.node_number(),
+                opcode: r.try_enum()?,
+                event: r.event(),
+            },
+            OpCode::ExtendedServiceDiscoveryResponse => Self::ExtendedServiceDiscoveryResponse {
+                node_number: r.node_number(),
+                service_index: r.u8(),
+                service: r.try_enum()?,
+                data: r.take(),
+            },
+            OpCode::StreamPacket => Self::StreamPacket { data: r.take() },
+            OpCode::NodeParametersReport => Self::NodeParametersReport { params: r.take() },
+            OpCode::LongEventAccessoryOn3 => Self::LongEventAccessoryOn3 {
+                event: r.event(),
+                data: r.take(),
+            },
+            OpCode::LongEventAccessoryOff3 => Self::LongEventAccessoryOff3 {
+                event: r.event(),
+                data: r.take(),
+            },
+            OpCode::LearnedEventResponse => Self::LearnedEventResponse {
+                node_number: r.node_number(),
+                event: r.event(),
+                event_index: r.u8(),
+            },
+            OpCode::LongEventAccessoryStateOn3 => Self::LongEventAccessoryStateOn3 {
+                event: r.event(),
+                data: r.take(),
+            },
+            OpCode::LongEventAccessoryStateOff3 => Self::LongEventAccessoryStateOff3 {
+                event: r.event(),
+                data: r.take(),
+            },
+            OpCode::TeachEventByIndex => Self::TeachEventByIndex {
+                event: r.event(),
+                event_index: r.u8(),
+                ev_index: r.u8(),
+                value: r.u8(),
+            },
+            OpCode::DataEventAccessory => Self::DataEventAccessory {
+                node_number: r.node_number(),
+                data: r.take(),
+            },
+            OpCode::NodeDataEventResponse => Self::NodeDataEventResponse {
+                node_number: r.node_number(),
+                data: r.take(),
+            },
+            OpCode::ShortEventAccessoryOn3 => Self::ShortEventAccessoryOn3 {
+                event: r.short_event(),
+                data: r.take(),
+            },
+            OpCode::ShortEventAccessoryOff3 => Self::ShortEventAccessoryOff3 {
+                event: r.short_event(),
+                data: r.take(),
+            },
+            OpCode::DeviceDataEventShortMode => Self::DeviceDataEventShortMode {
+                device_number: r.u16(),
+                data: r.take(),
+            },
+            OpCode::DeviceDataResponseShortMode => Self::DeviceDataResponseShortMode {
+                device_number: r.u16(),
+                data: r.take(),
+            },
+            OpCode::WriteData => Self::WriteData {
+                device_number: r.u16(),
+                data: r.take(),
+            },
+            OpCode::ShortEventAccessoryStateOn3 => Self::ShortEventAccessoryStateOn3 {
+                event: r.short_event(),
+                data: r.take(),
+            },
+            OpCode::ShortEventAccessoryStateOff3 => Self::ShortEventAccessoryStateOff3 {
+                event: r.short_event(),
+                data: r.take(),
+            },
+            OpCode::ExtOpCode
+            | OpCode::ExtOpCode1
+            | OpCode::ExtOpCode2
+            | OpCode::ExtOpCode3
+            | OpCode::ExtOpCode4
+            | OpCode::ExtOpCode5
+            | OpCode::ExtOpCode6 => Self::Extended(ExtendedMessage::decode(data)?),
+        };
+
+        Ok(message)
+    }
+
+    /// Encode the message into `buf`, starting with the opcode.
+    ///
+    /// Returns the number of octets written.
+    ///
+    /// # Panics
+    /// The function panics if `buf` is shorter than [`VlcbMessage::encoded_len`].
+    pub fn encode(&self, buf: &mut [u8]) -> usize {
+        let len = self.encoded_len();
+        buf[0] = self.opcode().into();
+
+        let mut w = Writer {
+            buf: &mut buf[1..len],
+            pos: 0,
+        };
+        match *self {
+            Self::DccReleaseSession { session } => w.u8(session),
+            Self::DccQueryLocoStatus { session } => w.u8(session),
+            Self::DccSessionKeepAlive { session } => w.u8(session),
+            Self::DebugMsg1 { status } => w.u8(status),
+            Self::DccRequestNewSession { address } => w.put(&address.as_bytes_sanitized()),
+            Self::DccQueryConsist { consist, index } => {
+                w.u8(consist);
+                w.u8(index);
+            }
+            Self::SetNodeNumber { node_number } => w.put(node_number.as_bytes()),
+            Self::DccAllocateLocoToActivity {
+                session,
+                allocation_code,
+            } => {
+                w.u8(session);
+                w.u8(allocation_code);
+            }
+            Self::DccSetThrottleMode { session, mode } => {
+                w.u8(session);
+                w.u8(mode);
+            }
+            Self::DccConsistAddLoco { session, consist } => {
+                w.u8(session);
+                w.u8(consist);
+            }
+            Self::DccConsistRemoveLoco { session, consist } => {
+                w.u8(session);
+                w.u8(consist);
+            }
+            Self::DccSetLocoThrottle { session, speed_dir } => {
+                w.u8(session);
+                w.u8(speed_dir);
+            }
+            Self::DccSetLocoFlags { session, flags } => {
+                w.u8(session);
+                w.u8(flags);
+            }
+            Self::DccLocoFunctionOn { session, function } => {
+                w.u8(session);
+                w.u8(function);
+            }
+            Self::DccLocoFunctionOff { session, function } => {
+                w.u8(session);
+                w.u8(function);
+            }
+            Self::DccServiceModeStatus { session, status } => {
+                w.u8(session);
+                w.u8(status.into());
+            }
+            Self::ResetModuleToFactory { node_number } => w.put(node_number.as_bytes()),
+            Self::RequestNewNodeNumber { node_number } => w.put(node_number.as_bytes()),
+            Self::NodeNumberReleased { node_number } => w.put(node_number.as_bytes()),
+            Self::NodeNumberAck { node_number } => w.put(node_number.as_bytes()),
+            Self::PutNodeIntoLearnMode { node_number } => w.put(node_number.as_bytes()),
+            Self::ReleaseNodeFromLearnMode { node_number } => w.put(node_number.as_bytes()),
+            Self::ForgetAllLearnedEvents { node_number } => w.put(node_number.as_bytes()),
+            Self::QueryAvailableEventSlots { node_number } => w.put(node_number.as_bytes()),
+            Self::QueryAllLearnedEvents { node_number } => w.put(node_number.as_bytes()),
+            Self::QueryLearnedEventCount { node_number } => w.put(node_number.as_bytes()),
+            Self::WriteAck { node_number } => w.put(node_number.as_bytes()),
+            Self::QueryNodeData { node_number } => w.put(node_number.as_bytes()),
+            Self::RequestDeviceDataShortMode { device_number } => w.u16(device_number),
+            Self::RebootIntoBootloader { node_number } => w.put(node_number.as_bytes()),
+            Self::ForceCanEnumeration { node_number } => w.put(node_number.as_bytes()),
+            Self::RestartNode { node_number } => w.put(node_number.as_bytes()),
+            Self::DccSetLocoFunctions {
+                session,
+                range,
+                functions,
+            } => {
+                w.u8(session);
+                w.u8(range.into());
+                w.u8(functions);
+            }
+            Self::DccQueryLocoSession { address, flags } => {
+                w.put(&address.as_bytes_sanitized());
+                w.u8(flags);
+            }
+            Self::DccCommandStationError { data, error } => {
+                w.put(&data);
+                w.u8(error.into());
+            }
+            Self::NodeConfigurationError { node_number, error } => {
+                w.put(node_number.as_bytes());
+                w.u8(error.into());
+            }
+            Self::AvailableEventSlots {
+                node_number,
+                spaces,
+            } => {
+                w.put(node_number.as_bytes());
+                w.u8(spaces);
+            }
+            Self::QueryNodeVariable { node_number, index } => {
+                w.put(node_number.as_bytes());
+                w.u8(index);
+            }
+            Self::QueryLearnedEventByIndex {
+                node_number,
+                event_index,
+            } => {
+                w.put(node_number.as_bytes());
+                w.u8(event_index);
+            }
+            Self::QueryNodeParameterByIndex { node_number, param } => {
+                w.put(node_number.as_bytes());
+                w.u8(param);
+            }
+            Self::LearnedEventCount { node_number, count } => {
+                w.put(node_number.as_bytes());
+                w.u8(count);
+            }
+            Self::SetNodeCanId {
+                node_number,
+                can_id,
+            } => {
+                w.put(node_number.as_bytes());
+                w.u8(can_id.into());
+            }
+            Self::PutNodeIntoMode { node_number, mode } => {
+                w.put(node_number.as_bytes());
+                w.u8(mode.into());
+            }
+            Self::ServiceDiscoveryQuery {
+                node_number,
+                service_index,
+            } => {
+                w.put(node_number.as_bytes());
+                w.u8(service_index);
+            }
+            Self::DccSendRawPacket3 { repeat, packet } => {
+                w.u8(repeat);
+                w.put(&packet);
+            }
+            Self::DccWriteCvByteInOpsMode { session, cv, value } => {
+                w.u8(session);
+                w.u16(cv);
+                w.u8(value);
+            }
+            Self::DcWriteCvBitInOpsMode { session, cv, value } => {
+                w.u8(session);
+                w.u16(cv);
+                w.u8(value);
+            }
+            Self::DccReadCv { session, cv, mode } => {
+                w.u8(session);
+                w.u16(cv);
+                w.u8(mode);
+            }
+            Self::DccCvValue { session, cv, value } => {
+                w.u8(session);
+                w.u16(cv);
+                w.u8(value);
+            }
+            Self::QueryDiagnosticData {
+                node_number,
+                service_index,
+                diagnostic_code,
+            } => {
+                w.put(node_number.as_bytes());
+                w.u8(service_index);
+                w.u8(diagnostic_code);
+            }
+            Self::SetNodeVariable {
+                node_number,
+                index,
+                value,
+            } => {
+                w.put(node_number.as_bytes());
+                w.u8(index);
+                w.u8(value);
+            }
+            Self::LongEventAccessoryOn { event } => w.put(event.as_bytes()),
+            Self::LongEventAccessoryOff { event } => w.put(event.as_bytes()),
+            Self::QueryLongEventAccessoryState { event } => w.put(event.as_bytes()),
+            Self::LongEventAccessoryStateOn { event } => w.put(event.as_bytes()),
+            Self::LongEventAccessoryStateOff { event } => w.put(event.as_bytes()),
+            Self::ForgetLearnedEvent { event } => w.put(event.as_bytes()),
+            Self::LegacySetNodeVariable {
+                node_number,
+                index,
+                value,
+            } => {
+                w.put(node_number.as_bytes());
+                w.u8(index);
+                w.u8(value);
+            }
+            Self::NodeVariableValue {
+                node_number,
+                index,
+                value,
+            } => {
+                w.put(node_number.as_bytes());
+                w.u8(index);
+                w.u8(value);
+            }
+            Self::ShortEventAccessoryOn { event } => w.put(event.as_bytes()),
+            Self::ShortEventAccessoryOff { event } => w.put(event.as_bytes()),
+            Self::QueryShortEventAccessoryState { event } => w.put(event.as_bytes()),
+            Self::NodeParameterValue {
+                node_number,
+                param,
+                value,
+            } => {
+                w.put(node_number.as_bytes());
+                w.u8(param);
+                w.u8(value);
+            }
+            Self::QueryEventVariable {
+                node_number,
+                event_index,
+                ev_index,
+            } => {
+                w.put(node_number.as_bytes());
+                w.u8(event_index);
+                w.u8(ev_index);
+            }
+            Self::ShortEventAccessoryStateOn { event } => w.put(event.as_bytes()),
+            Self::ShortEventAccessoryStateOff { event } => w.put(event.as_bytes()),
+            Self::DccSendRawPacket4 { repeat, packet } => {
+                w.u8(repeat);
+                w.put(&packet);
+            }
+            Self::DccWriteCvInServiceMode {
+                session,
+                cv,
+                mode,
+                value,
+            } => {
+                w.u8(session);
+                w.u16(cv);
+                w.u8(mode);
+                w.u8(value);
+            }
+            Self::Heartbeat {
+                node_number,
+                sequence,
+                status,
+                status_bits,
+            } => {
+                w.put(node_number.as_bytes());
+                w.u8(sequence);
+                w.u8(status);
+                w.u8(status_bits);
+            }
+            Self::ServiceDiscoveryResponse {
+                node_number,
+                service_index,
+                service,
+                version,
+            } => {
+                w.put(node_number.as_bytes());
+                w.u8(service_index);
+                w.u8(service.into());
+                w.u8(version);
+            }
+            Self::GenericResponse {
+                node_number,
+                opcode,
+                service,
+                result,
+            } => {
+                w.put(node_number.as_bytes());
+                w.u8(opcode.into());
+                w.u8(service.into());
+                w.u8(result.into());
+            }
+            Self::LongEventAccessoryOn1 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::LongEventAccessoryOff1 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::QueryEventVariableInLearnMode { event, ev_index } => {
+                w.put(event.as_bytes());
+                w.u8(ev_index);
+            }
+            Self::LongEventAccessoryStateOn1 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::LongEventAccessoryStateOff1 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::EventVariableValue {
+                node_number,
+                event_index,
+                ev_index,
+                value,
+            } => {
+                w.put(node_number.as_bytes());
+                w.u8(event_index);
+                w.u8(ev_index);
+                w.u8(value);
+            }
+            Self::NodeInfo {
+                node_number,
+                manufacturer,
+                module_type,
+                flags,
+            } => {
+                w.put(node_number.as_bytes());
+                w.u8(manufacturer);
+                w.u8(module_type);
+                w.u8(flags.bits());
+            }
+            Self::ShortEventAccessoryOn1 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::ShortEventAccessoryOff1 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::ShortEventAccessoryStateOn1 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::ShortEventAccessoryStateOff1 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::DccSendRawPacket5 { repeat, packet } => {
+                w.u8(repeat);
+                w.put(&packet);
+            }
+            Self::DccWriteCvByteInOpsModeByAddress {
+                address,
+                cv,
+                mode,
+                value,
+            } => {
+                w.put(&address.as_bytes_sanitized());
+                w.u16(cv);
+                w.u8(mode);
+                w.u8(value);
+            }
+            Self::DccSendDataToCab {
+                address,
+                code,
+                data,
+            } => {
+                w.put(&address.as_bytes_sanitized());
+                w.u8(code.into());
+                w.put(&data);
+            }
+            Self::DiagnosticData {
+                node_number,
+                service_index,
+                diagnostic_code,
+                value,
+            } => {
+                w.put(node_number.as_bytes());
+                w.u8(service_index);
+                w.u8(diagnostic_code);
+                w.u16(value);
+            }
+            Self::FastClock {
+                minutes,
+                hours,
+                weekday,
+                month,
+                divider,
+                day,
+                temperature,
+            } => {
+                w.u8(minutes);
+                w.u8(hours);
+                w.u8(u8::from(weekday) | (u8::from(month) << 4));
+                w.u8(divider);
+                w.u8(day);
+                w.u8(temperature as u8);
+            }
+            Self::LongEventAccessoryOn2 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::LongEventAccessoryOff2 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::TeachEvent {
+                event,
+                ev_index,
+                value,
+            } => {
+                w.put(event.as_bytes());
+                w.u8(ev_index);
+                w.u8(value);
+            }
+            Self::EventVariableValueInLearnMode {
+                event,
+                ev_index,
+                value,
+            } => {
+                w.put(event.as_bytes());
+                w.u8(ev_index);
+                w.u8(value);
+            }
+            Self::LongEventAccessoryStateOn2 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::LongEventAccessoryStateOff2 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::ShortEventAccessoryOn2 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::ShortEventAccessoryOff2 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::ShortEventAccessoryStateOn2 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::ShortEventAccessoryStateOff2 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::DccSendRawPacket6 { repeat, packet } => {
+                w.u8(repeat);
+                w.put(&packet);
+            }
+            Self::DccLocoReport {
+                session,
+                address,
+                speed_dir,
+                functions,
+            } => {
+                w.u8(session);
+                w.put(&address.as_bytes_sanitized());
+                w.u8(speed_dir);
+                w.put(&functions);
+            }
+            Self::ModuleName { name } => w.put(&name),
+            Self::DccCommandStationStatus {
+                node_number,
+                cs_number,
+                flags,
+                major,
+                minor,
+                build,
+            } => {
+                w.put(node_number.as_bytes());
+                w.u8(cs_number);
+                w.u8(flags);
+                w.u8(major);
+                w.u8(minor);
+                w.u8(build);
+            }
+            Self::EventAck {
+                node_number,
+                opcode,
+                event,
+            } => {
+                w.put(node_number.as_bytes());
+                w.u8(opcode.into());
+                w.put(event.as_bytes());
+            }
+            Self::ExtendedServiceDiscoveryResponse {
+                node_number,
+                service_index,
+                service,
+                data,
+            } => {
+                w.put(node_number.as_bytes());
+                w.u8(service_index);
+                w.u8(service.into());
+                w.put(&data);
+            }
+            Self::StreamPacket { data } => w.put(&data),
+            Self::NodeParametersReport { params } => w.put(&params),
+            Self::LongEventAccessoryOn3 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::LongEventAccessoryOff3 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::LearnedEventResponse {
+                node_number,
+                event,
+                event_index,
+            } => {
+                w.put(node_number.as_bytes());
+                w.put(event.as_bytes());
+                w.u8(event_index);
+            }
+            Self::LongEventAccessoryStateOn3 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::LongEventAccessoryStateOff3 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::TeachEventByIndex {
+                event,
+                event_index,
+                ev_index,
+                value,
+            } => {
+                w.put(event.as_bytes());
+                w.u8(event_index);
+                w.u8(ev_index);
+                w.u8(value);
+            }
+            Self::DataEventAccessory { node_number, data } => {
+                w.put(node_number.as_bytes());
+                w.put(&data);
+            }
+            Self::NodeDataEventResponse { node_number, data } => {
+                w.put(node_number.as_bytes());
+                w.put(&data);
+            }
+            Self::ShortEventAccessoryOn3 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::ShortEventAccessoryOff3 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::DeviceDataEventShortMode {
+                device_number,
+                data,
+            } => {
+                w.u16(device_number);
+                w.put(&data);
+            }
+            Self::DeviceDataResponseShortMode {
+                device_number,
+                data,
+            } => {
+                w.u16(device_number);
+                w.put(&data);
+            }
+            Self::WriteData {
+                device_number,
+                data,
+            } => {
+                w.u16(device_number);
+                w.put(&data);
+            }
+            Self::ShortEventAccessoryStateOn3 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::ShortEventAccessoryStateOff3 { event, data } => {
+                w.put(event.as_bytes());
+                w.put(&data);
+            }
+            Self::Extended(ref msg) => {
+                w.u8(msg.ext_opcode().code());
+                w.put(msg.data());
+            }
+            // messages without parameters consist of the opcode only
+            _ => {}
+        }
+
+        len
+    }
+
+    /// Number of octets the message occupies when encoded, including the opcode.
+    pub fn encoded_len(&self) -> usize {
+        self.opcode().frame_len()
+    }
+
+    /// Return the opcode of the message
+    pub fn opcode(&self) -> OpCode {
+        match self {
+            Self::GeneralAck => OpCode::GeneralAck,
+            Self::GeneralNack => OpCode::GeneralNack,
+            Self::BusHalt => OpCode::BusHalt,
+            Self::BusResume => OpCode::BusResume,
+            Self::DccTrackPoweredOff => OpCode::DccTrackPoweredOff,
+            Self::DccTrackPoweredOn => OpCode::DccTrackPoweredOn,
+            Self::DccEmergencyStopEngaged => OpCode::DccEmergencyStopEngaged,
+            Self::RestartAllNodes => OpCode::RestartAllNodes,
+            Self::DccTrackPowerOff => OpCode::DccTrackPowerOff,
+            Self::DccTrackPowerOn => OpCode::DccTrackPowerOn,
+            Self::DccEmergencyStop => OpCode::DccEmergencyStop,
+            Self::DccQueryCommandStationStatus => OpCode::DccQueryCommandStationStatus,
+            Self::QueryNodeInfo => OpCode::QueryNodeInfo,
+            Self::QueryNodeParameters => OpCode::QueryNodeParameters,
+            Self::QueryModuleName => OpCode::QueryModuleName,
+            Self::DccReleaseSession { .. } => OpCode::DccReleaseSession,
+            Self::DccQueryLocoStatus { .. } => OpCode::DccQueryLocoStatus,
+            Self::DccSessionKeepAlive { .. } => OpCode::DccSessionKeepAlive,
+            Self::DebugMsg1 { .. } => OpCode::DebugMsg1,
+            Self::DccRequestNewSession { .. } => OpCode::DccRequestNewSession,
+            Self::DccQueryConsist { .. } => OpCode::DccQueryConsist,
+            Self::SetNodeNumber { .. } => OpCode::SetNodeNumber,
+            Self::DccAllocateLocoToActivity { .. } => OpCode::DccAllocateLocoToActivity,
+            Self::DccSetThrottleMode { .. } => OpCode::DccSetThrottleMode,
+            Self::DccConsistAddLoco { .. } => OpCode::DccConsistAddLoco,
+            Self::DccConsistRemoveLoco { .. } => OpCode::DccConsistRemoveLoco,
+            Self::DccSetLocoThrottle { .. } => OpCode::DccSetLocoThrottle,
+            Self::DccSetLocoFlags { .. } => OpCode::DccSetLocoFlags,
+            Self::DccLocoFunctionOn { .. } => OpCode::DccLocoFunctionOn,
+            Self::DccLocoFunctionOff { .. } => OpCode::DccLocoFunctionOff,
+            Self::DccServiceModeStatus { .. } => OpCode::DccServiceModeStatus,
+            Self::ResetModuleToFactory { .. } => OpCode::ResetModuleToFactory,
+            Self::RequestNewNodeNumber { .. } => OpCode::RequestNewNodeNumber,
+            Self::NodeNumberReleased { .. } => OpCode::NodeNumberReleased,
+            Self::NodeNumberAck { .. } => OpCode::NodeNumberAck,
+            Self::PutNodeIntoLearnMode { .. } => OpCode::PutNodeIntoLearnMode,
+            Self::ReleaseNodeFromLearnMode { .. } => OpCode::ReleaseNodeFromLearnMode,
+            Self::ForgetAllLearnedEvents { .. } => OpCode::ForgetAllLearnedEvents,
+            Self::QueryAvailableEventSlots { .. } => OpCode::QueryAvailableEventSlots,
+            Self::QueryAllLearnedEvents { .. } => OpCode::QueryAllLearnedEvents,
+            Self::QueryLearnedEventCount { .. } => OpCode::QueryLearnedEventCount,
+            Self::WriteAck { .. } => OpCode::WriteAck,
+            Self::QueryNodeData { .. } => OpCode::QueryNodeData,
+            Self::RequestDeviceDataShortMode { .. } => OpCode::RequestDeviceDataShortMode,
+            Self::RebootIntoBootloader { .. } => OpCode::RebootIntoBootloader,
+            Self::ForceCanEnumeration { .. } => OpCode::ForceCanEnumeration,
+            Self::RestartNode { .. } => OpCode::RestartNode,
+            Self::DccSetLocoFunctions { .. } => OpCode::DccSetLocoFunctions,
+            Self::DccQueryLocoSession { .. } => OpCode::DccQueryLocoSession,
+            Self::DccCommandStationError { .. } => OpCode::DccCommandStationError,
+            Self::NodeConfigurationError { .. } => OpCode::NodeConfigurationError,
+            Self::AvailableEventSlots { .. } => OpCode::AvailableEventSlots,
+            Self::QueryNodeVariable { .. } => OpCode::QueryNodeVariable,
+            Self::QueryLearnedEventByIndex { .. } => OpCode::QueryLearnedEventByIndex,
+            Self::QueryNodeParameterByIndex { .. } => OpCode::QueryNodeParameterByIndex,
+            Self::LearnedEventCount { .. } => OpCode::LearnedEventCount,
+            Self::SetNodeCanId { .. } => OpCode::SetNodeCanId,
+            Self::PutNodeIntoMode { .. } => OpCode::PutNodeIntoMode,
+            Self::ServiceDiscoveryQuery { .. } => OpCode::ServiceDiscoveryQuery,
+            Self::DccSendRawPacket3 { .. } => OpCode::DccSendRawPacket3,
+            Self::DccWriteCvByteInOpsMode { .. } => OpCode::DccWriteCvByteInOpsMode,
+            Self::DcWriteCvBitInOpsMode { .. } => OpCode::DcWriteCvBitInOpsMode,
+            Self::DccReadCv { .. } => OpCode::DccReadCv,
+            Self::DccCvValue { .. } => OpCode::DccCvValue,
+            Self::QueryDiagnosticData { .. } => OpCode::QueryDiagnosticData,
+            Self::SetNodeVariable { .. } => OpCode::SetNodeVariable,
+            Self::LongEventAccessoryOn { .. } => OpCode::LongEventAccessoryOn,
+            Self::LongEventAccessoryOff { .. } => OpCode::LongEventAccessoryOff,
+            Self::QueryLongEventAccessoryState { .. } => OpCode::QueryLongEventAccessoryState,
+            Self::LongEventAccessoryStateOn { .. } => OpCode::LongEventAccessoryStateOn,
+            Self::LongEventAccessoryStateOff { .. } => OpCode::LongEventAccessoryStateOff,
+            Self::ForgetLearnedEvent { .. } => OpCode::ForgetLearnedEvent,
+            Self::LegacySetNodeVariable { .. } => OpCode::LegacySetNodeVariable,
+            Self::NodeVariableValue { .. } => OpCode::NodeVariableValue,
+            Self::ShortEventAccessoryOn { .. } => OpCode::ShortEventAccessoryOn,
+            Self::ShortEventAccessoryOff { .. } => OpCode::ShortEventAccessoryOff,
+            Self::QueryShortEventAccessoryState { .. } => OpCode::QueryShortEventAccessoryState,
+            Self::NodeParameterValue { .. } => OpCode::NodeParameterValue,
+            Self::QueryEventVariable { .. } => OpCode::QueryEventVariable,
+            Self::ShortEventAccessoryStateOn { .. } => OpCode::ShortEventAccessoryStateOn,
+            Self::ShortEventAccessoryStateOff { .. } => OpCode::ShortEventAccessoryStateOff,
+            Self::DccSendRawPacket4 { .. } => OpCode::DccSendRawPacket4,
+            Self::DccWriteCvInServiceMode { .. } => OpCode::DccWriteCvInServiceMode,
+            Self::Heartbeat { .. } => OpCode::Heartbeat,
+            Self::ServiceDiscoveryResponse { .. } => OpCode::ServiceDiscoveryResponse,
+            Self::GenericResponse { .. } => OpCode::GenericResponse,
+            Self::LongEventAccessoryOn1 { .. } => OpCode::LongEventAccessoryOn1,
+            Self::LongEventAccessoryOff1 { .. } => OpCode::LongEventAccessoryOff1,
+            Self::QueryEventVariableInLearnMode { .. } => OpCode::QueryEventVariableInLearnMode,
+            Self::LongEventAccessoryStateOn1 { .. } => OpCode::LongEventAccessoryStateOn1,
+            Self::LongEventAccessoryStateOff1 { .. } => OpCode::LongEventAccessoryStateOff1,
+            Self::EventVariableValue { .. } => OpCode::EventVariableValue,
+            Self::NodeInfo { .. } => OpCode::NodeInfo,
+            Self::ShortEventAccessoryOn1 { .. } => OpCode::ShortEventAccessoryOn1,
+            Self::ShortEventAccessoryOff1 { .. } => OpCode::ShortEventAccessoryOff1,
+            Self::ShortEventAccessoryStateOn1 { .. } => OpCode::ShortEventAccessoryStateOn1,
+            Self::ShortEventAccessoryStateOff1 { .. } => OpCode::ShortEventAccessoryStateOff1,
+            Self::DccSendRawPacket5 { .. } => OpCode::DccSendRawPacket5,
+            Self::DccWriteCvByteInOpsModeByAddress { .. } => {
+                OpCode::DccWriteCvByteInOpsModeByAddress
+            }
+            Self::DccSendDataToCab { .. } => OpCode::DccSendDataToCab,
+            Self::DiagnosticData { .. } => OpCode::DiagnosticData,
+            Self::FastClock { .. } => OpCode::FastClock,
+            Self::LongEventAccessoryOn2 { .. } => OpCode::LongEventAccessoryOn2,
+            Self::LongEventAccessoryOff2 { .. } => OpCode::LongEventAccessoryOff2,
+            Self::TeachEvent { .. } => OpCode::TeachEvent,
+            Self::EventVariableValueInLearnMode { .. } => OpCode::EventVariableValueInLearnMode,
+            Self::LongEventAccessoryStateOn2 { .. } => OpCode::LongEventAccessoryStateOn2,
+            Self::LongEventAccessoryStateOff2 { .. } => OpCode::LongEventAccessoryStateOff2,
+            Self::ShortEventAccessoryOn2 { .. } => OpCode::ShortEventAccessoryOn2,
+            Self::ShortEventAccessoryOff2 { .. } => OpCode::ShortEventAccessoryOff2,
+            Self::ShortEventAccessoryStateOn2 { .. } => OpCode::ShortEventAccessoryStateOn2,
+            Self::ShortEventAccessoryStateOff2 { .. } => OpCode::ShortEventAccessoryStateOff2,
+            Self::DccSendRawPacket6 { .. } => OpCode::DccSendRawPacket6,
+            Self::DccLocoReport { .. } => OpCode::DccLocoReport,
+            Self::ModuleName { .. } => OpCode::ModuleName,
+            Self::DccCommandStationStatus { .. } => OpCode::DccCommandStationStatus,
+            Self::EventAck { .. } => OpCode::EventAck,
+            Self::ExtendedServiceDiscoveryResponse { .. } => {
+                OpCode::ExtendedServiceDiscoveryResponse
+            }
+            Self::StreamPacket { .. } => OpCode::StreamPacket,
+            Self::NodeParametersReport { .. } => OpCode::NodeParametersReport,
+            Self::LongEventAccessoryOn3 { .. } => OpCode::LongEventAccessoryOn3,
+            Self::LongEventAccessoryOff3 { .. } => OpCode::LongEventAccessoryOff3,
+            Self::LearnedEventResponse { .. } => OpCode::LearnedEventResponse,
+            Self::LongEventAccessoryStateOn3 { .. } => OpCode::LongEventAccessoryStateOn3,
+            Self::LongEventAccessoryStateOff3 { .. } => OpCode::LongEventAccessoryStateOff3,
+            Self::TeachEventByIndex { .. } => OpCode::TeachEventByIndex,
+            Self::DataEventAccessory { .. } => OpCode::DataEventAccessory,
+            Self::NodeDataEventResponse { .. } => OpCode::NodeDataEventResponse,
+            Self::ShortEventAccessoryOn3 { .. } => OpCode::ShortEventAccessoryOn3,
+            Self::ShortEventAccessoryOff3 { .. } => OpCode::ShortEventAccessoryOff3,
+            Self::DeviceDataEventShortMode { .. } => OpCode::DeviceDataEventShortMode,
+            Self::DeviceDataResponseShortMode { .. } => OpCode::DeviceDataResponseShortMode,
+            Self::WriteData { .. } => OpCode::WriteData,
+            Self::ShortEventAccessoryStateOn3 { .. } => OpCode::ShortEventAccessoryStateOn3,
+            Self::ShortEventAccessoryStateOff3 { .. } => OpCode::ShortEventAccessoryStateOff3,
+            Self::Extended(msg) => msg.ext_opcode().opcode(),
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for VlcbMessage {
+    type Error = DecodeError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Self::decode(data)
+    }
+}
+
+/// Cursor over the data octets of a message which was already checked for length.
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> [u8; N] {
+        let (head, tail) = self.0.split_at(N);
+        self.0 = tail;
+        let mut bytes = [0; N];
+        bytes.copy_from_slice(head);
+        bytes
+    }
+
+    fn u8(&mut self) -> u8 {
+        self.take::<1>()[0]
+    }
+
+    fn u16(&mut self) -> u16 {
+        NetworkEndian::read_u16(&self.take::<2>())
+    }
+
+    fn node_number(&mut self) -> VlcbNodeNumber {
+        VlcbNodeNumber(self.take::<NODENUM_SIZE>())
+    }
+
+    fn event(&mut self) -> EventId {
+        EventId::from_array(self.take::<EVENT_SIZE>())
+    }
+
+    fn short_event(&mut self) -> EventId {
+        EventId::short_from_array(self.take::<EVENT_SIZE>())
+    }
+
+    fn loco_address(&mut self) -> Result<LocoAddress, DecodeError> {
+        LocoAddress::from_bytes(self.take())
+    }
+
+    fn can_id(&mut self) -> Result<VlcbCanId, DecodeError> {
+        let value = self.u8();
+        if value & !CANID_MASK != 0 {
+            return Err(DecodeError::ReservedBitsSet {
+                type_name: "VlcbCanId",
+                value,
+            });
+        }
+        Ok(VlcbCanId([value]))
+    }
+
+    fn try_enum<T: TryFromPrimitive<Primitive = u8>>(&mut self) -> Result<T, DecodeError> {
+        let value = self.u8();
+        T::try_from_primitive(value).map_err(|_| DecodeError::InvalidEnumValue {
+            type_name: T::NAME,
+            value,
+        })
+    }
+
+    fn checked_enum<T>(&mut self, type_name: &'static str) -> Result<T, DecodeError>
+    where
+        T: FromPrimitive<Primitive = u8> + Into<u8> + Copy,
+    {
+        checked_from(self.u8(), type_name)
+    }
+}
+
+/// Convert a value into an enum with a default variant, rejecting the values the enum
+/// does not define instead of mapping them to the default
+fn checked_from<T>(value: u8, type_name: &'static str) -> Result<T, DecodeError>
+where
+    T: FromPrimitive<Primitive = u8> + Into<u8> + Copy,
+{
+    let variant = T::from_primitive(value);
+    if variant.into() != value {
+        return Err(DecodeError::InvalidEnumValue { type_name, value });
+    }
+    Ok(variant)
+}
+
+/// Cursor over the data octets of a message buffer.
+struct Writer<'a> {
+    buf: &'a mut [u8],
+    pos: usize,
+}
+
+impl Writer<'_> {
+    fn put(&mut self, data: &[u8]) {
+        self.buf[self.pos..self.pos + data.len()].copy_from_slice(data);
+        self.pos += data.len();
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.put(&[value]);
+    }
+
+    fn u16(&mut self, value: u16) {
+        let mut bytes = [0; 2];
+        NetworkEndian::write_u16(&mut bytes, value);
+        self.put(&bytes);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn round_trip(message: VlcbMessage, expected: &[u8]) {
+        let mut buf = [0u8; MAX_MESSAGE_SIZE];
+        let len = message.encode(&mut buf);
+        assert_eq!(&buf[..len], expected);
+        assert_eq!(VlcbMessage::decode(expected), Ok(message));
+    }
+
+    #[test]
+    fn test_no_data() {
+        round_trip(VlcbMessage::BusHalt, &[0x02]);
+        round_trip(VlcbMessage::QueryNodeParameters, &[0x10]);
+    }
+
+    #[test]
+    fn test_long_event() {
+        let event = EventId::from_array([0x01, 0x02, 0x00, 0x10]);
+        round_trip(
+            VlcbMessage::LongEventAccessoryOn { event },
+            &[0x90, 0x01, 0x02, 0x00, 0x10],
+        );
+        round_trip(
+            VlcbMessage::LongEventAccessoryOff2 {
+                event,
+                data: [0xAA, 0xBB],
+            },
+            &[0xD1, 0x01, 0x02, 0x00, 0x10, 0xAA, 0xBB],
+        );
+    }
+
+    #[test]
+    fn test_extended() {
+        use crate::extended::ExtendedOpCode;
+        use crate::opcode::LengthClass;
+
+        let ext = ExtendedOpCode::new(LengthClass::Data3, 0x42);
+        round_trip(
+            VlcbMessage::Extended(ExtendedMessage::new(ext, &[0x01, 0x02]).unwrap()),
+            &[0x7F, 0x42, 0x01, 0x02],
+        );
+        assert_eq!(
+            VlcbMessage::decode(&[0x7F, 0x42, 0x01, 0x02]).map(|msg| msg.opcode()),
+            Ok(OpCode::ExtOpCode2)
+        );
+    }
+
+    #[test]
+    fn test_generic_response() {
+        round_trip(
+            VlcbMessage::GenericResponse {
+                node_number: VlcbNodeNumber::new(0x01, 0x00),
+                opcode: OpCode::SetNodeVariable,
+                service: ServiceType::NodeVariable,
+                result: GenericResponseStatus::Ok.into(),
+            },
+            &[0xAF, 0x01, 0x00, 0x8E, 0x02, 0x00],
+        );
+    }
+
+    #[test]
+    fn test_loco_report() {
+        round_trip(
+            VlcbMessage::DccLocoReport {
+                session: 1,
+                address: LocoAddress::new_long(1234),
+                speed_dir: 0x80,
+                functions: [0x01, 0x00, 0x00],
+            },
+            &[0xE1, 0x01, 0xC4, 0xD2, 0x80, 0x01, 0x00, 0x00],
+        );
+    }
+
+    #[test]
+    fn test_fast_clock() {
+        round_trip(
+            VlcbMessage::FastClock {
+                minutes: 30,
+                hours: 12,
+                weekday: FastClockWeekday::Monday,
+                month: FastClockMonth::March,
+                divider: 4,
+                day: 15,
+                temperature: -5,
+            },
+            &[0xCF, 30, 12, 0x32, 4, 15, 0xFB],
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(
+            VlcbMessage::decode(&[]),
+            Err(DecodeError::WrongLength {
+                expected: 1,
+                actual: 0
+            })
+        );
+        assert_eq!(
+            VlcbMessage::decode(&[0x0B]),
+            Err(DecodeError::UnknownOpcode(0x0B))
+        );
+        assert_eq!(
+            VlcbMessage::decode(&[0x90, 0x01, 0x02]),
+            Err(DecodeError::WrongLength {
+                expected: 5,
+                actual: 3
+            })
+        );
+        assert_eq!(
+            VlcbMessage::decode(&[0x6F, 0x01, 0x00, 0x7F]),
+            Err(DecodeError::InvalidEnumValue {
+                type_name: "CommandError",
+                value: 0x7F
+            })
+        );
+        assert_eq!(
+            VlcbMessage::decode(&[0x75, 0x01, 0x00, 0x80]),
+            Err(DecodeError::ReservedBitsSet {
+                type_name: "VlcbCanId",
+                value: 0x80
+            })
+        );
+        assert_eq!(
+            VlcbMessage::decode(&[0x76, 0x01, 0x00, 0x03]),
+            Err(DecodeError::InvalidEnumValue {
+                type_name: "ModuleMode",
+                value: 0x03
+            })
+        );
+        assert_eq!(
+            VlcbMessage::decode(&[0xCF, 0, 0, 0x18, 1, 1, 20]),
+            Err(DecodeError::InvalidEnumValue {
+                type_name: "FastClockWeekday",
+                value: 0x08
+            })
+        );
+        assert_eq!(
+            VlcbMessage::decode(&[0xAF, 0x01, 0x00, 0xD2, 0x02, 0x7F]),
+            Err(DecodeError::InvalidEnumValue {
+                type_name: "GenericResponseResult",
+                value: 0x7F
+            })
+        );
+    }
+
+    #[test]
+    fn test_unlisted_node_parameter() {
+        // parameter 12 is the second octet of the load address, not a ModuleParam
+        assert_eq!(
+            VlcbMessage::decode(&[0x73, 0x01, 0x02, 12]),
+            Ok(VlcbMessage::QueryNodeParameterByIndex {
+                node_number: VlcbNodeNumber::new(0x01, 0x02),
+                param: 12,
+            })
+        );
+        assert!(crate::ModuleParam::try_from(12).is_err());
+        for param in [12, 13, 14, 16, 17, 18, 21, 0xFF] {
+            round_trip(
+                VlcbMessage::NodeParameterValue {
+                    node_number: VlcbNodeNumber::new(0x01, 0x02),
+                    param,
+                    value: 0x42,
+                },
+                &[0x9B, 0x01, 0x02, param, 0x42],
+            );
+        }
+        assert_eq!(
+            crate::builder::Message::paran(VlcbNodeNumber::new(0x01, 0x02), 12, 0x42).to_message(),
+            Ok(VlcbMessage::NodeParameterValue {
+                node_number: VlcbNodeNumber::new(0x01, 0x02),
+                param: 12,
+                value: 0x42,
+            })
+        );
+
+        // an unknown manufacturer does not fail the whole PNN
+        round_trip(
+            VlcbMessage::NodeInfo {
+                node_number: VlcbNodeNumber::new(0x01, 0x02),
+                manufacturer: 0x01,
+                module_type: 0x20,
+                flags: ModuleFlags::empty(),
+            },
+            &[0xB6, 0x01, 0x02, 0x01, 0x20, 0x00],
+        );
+    }
+
+    #[test]
+    fn test_generic_response_result() {
+        assert_eq!(
+            VlcbMessage::decode(&[0xAF, 0x01, 0x00, 0xD2, 0x02, 0x04]),
+            Ok(VlcbMessage::GenericResponse {
+                node_number: VlcbNodeNumber::new(0x01, 0x00),
+                opcode: OpCode::TeachEvent,
+                service: ServiceType::NodeVariable,
+                result: GenericResponseResult::CommandError(CommandError::TooManyEvents),
+            })
+        );
+        assert!(GenericResponseResult::from(GenericResponseStatus::Ok).is_ok());
+        assert_eq!(
+            GenericResponseResult::try_from(0xFB),
+            Ok(GenericResponseStatus::InvalidCommandParameter.into())
+        );
+    }
+
+    #[test]
+    fn test_round_trip_every_opcode() {
+        for op in 0..=u8::MAX {
+            let Ok(opcode) = OpCode::try_from(op) else {
+                continue;
+            };
+            let mut decoded = 0;
+            // fill the parameters with one value, the first one also with 0 to cover
+            // DCC addresses followed by an enum
+            for (first, fill) in (0..=u8::MAX).flat_map(|fill| [(fill, fill), (0, fill)]) {
+                let mut frame = [fill; MAX_MESSAGE_SIZE];
+                frame[0] = op;
+                frame[1] = first;
+                let frame = &frame[..opcode.frame_len()];
+                let Ok(msg) = VlcbMessage::decode(frame) else {
+                    continue;
+                };
+                decoded += 1;
+
+                let mut buf = [0; MAX_MESSAGE_SIZE];
+                let len = msg.encode(&mut buf);
+                assert_eq!(&buf[..len], frame, "{:?} re-encoded differently", opcode);
+                assert_eq!(VlcbMessage::decode(&buf[..len]), Ok(msg));
+            }
+            assert!(decoded > 0, "no frame of {:?} decodes", opcode);
+        }
+    }
+}
@@ -0,0 +1,427 @@
+use crate::builder::Message;
+use crate::dcc::LocoAddress;
+use crate::error::DecodeError;
+use crate::extended::ExtendedOpCode;
+use crate::message::VlcbMessage;
+use crate::vlcb::{EventId, VlcbNodeNumber, EVENT_SIZE, NODENUM_SIZE};
+use crate::OpCode;
+
+/// A borrowed, zero-copy view of a received VLCB message
+///
+/// The view only validates the opcode and the message length on construction,
+/// individual parameters are read on demand. Every accessor returns `None`
+/// when the opcode of the message does not carry the requested parameter.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct MessageRef<'a> {
+    opcode: OpCode,
+    data: &'a [u8],
+}
+
+impl<'a> MessageRef<'a> {
+    /// Wrap a received message, starting with the opcode.
+    ///
+    /// The length of `data` has to match the length defined by the opcode exactly,
+    /// see [`OpCode::validate_frame`].
+    pub fn new(data: &'a [u8]) -> Result<Self, DecodeError> {
+        let opcode = OpCode::validate_frame(data)?;
+        Ok(Self { opcode, data })
+    }
+
+    /// Return the opcode of the message
+    pub fn opcode(&self) -> OpCode {
+        self.opcode
+    }
+
+    /// Return the whole message as a sequence of octets, opcode included
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Return the data octets following the opcode
+    pub fn payload(&self) -> &'a [u8] {
+        &self.data[1..]
+    }
+
+    /// Decode the message into an owned [`VlcbMessage`]
+    pub fn to_message(&self) -> Result<VlcbMessage, DecodeError> {
+        VlcbMessage::decode(self.data)
+    }
+
+    /// Return the node number of the message
+    ///
+    /// For event messages this is the node number part of the event as sent
+    /// by the producer, including short events.
+    pub fn node_number(&self) -> Option<VlcbNodeNumber> {
+        let offset = match self.opcode {
+            OpCode::SetNodeNumber
+            | OpCode::ResetModuleToFactory
+            | OpCode::RequestNewNodeNumber
+            | OpCode::NodeNumberReleased
+            | OpCode::NodeNumberAck
+            | OpCode::PutNodeIntoLearnMode
+            | OpCode::ReleaseNodeFromLearnMode
+            | OpCode::ForgetAllLearnedEvents
+            | OpCode::QueryAvailableEventSlots
+            | OpCode::QueryAllLearnedEvents
+            | OpCode::QueryLearnedEventCount
+            | OpCode::WriteAck
+            | OpCode::QueryNodeData
+            | OpCode::RebootIntoBootloader
+            | OpCode::ForceCanEnumeration
+            | OpCode::RestartNode
+            | OpCode::NodeConfigurationError
+            | OpCode::AvailableEventSlots
+            | OpCode::QueryNodeVariable
+            | OpCode::QueryLearnedEventByIndex
+            | OpCode::QueryNodeParameterByIndex
+            | OpCode::LearnedEventCount
+            | OpCode::SetNodeCanId
+            | OpCode::PutNodeIntoMode
+            | OpCode::ServiceDiscoveryQuery
+            | OpCode::QueryDiagnosticData
+            | OpCode::SetNodeVariable
+            | OpCode::LongEventAccessoryOn
+            | OpCode::LongEventAccessoryOff
+            | OpCode::QueryLongEventAccessoryState
+            | OpCode::LongEventAccessoryStateOn
+            | OpCode::LongEventAccessoryStateOff
+            | OpCode::ForgetLearnedEvent
+            | OpCode::LegacySetNodeVariable
+            | OpCode::NodeVariableValue
+            | OpCode::ShortEventAccessoryOn
+            | OpCode::ShortEventAccessoryOff
+            | OpCode::QueryShortEventAccessoryState
+            | OpCode::NodeParameterValue
+            | OpCode::QueryEventVariable
+            | OpCode::ShortEventAccessoryStateOn
+            | OpCode::ShortEventAccessoryStateOff
+            | OpCode::Heartbeat
+            | OpCode::ServiceDiscoveryResponse
+            | OpCode::GenericResponse
+            | OpCode::LongEventAccessoryOn1
+            | OpCode::LongEventAccessoryOff1
+            | OpCode::QueryEventVariableInLearnMode
+            | OpCode::LongEventAccessoryStateOn1
+            | OpCode::LongEventAccessoryStateOff1
+            | OpCode::EventVariableValue
+            | OpCode::NodeInfo
+            | OpCode::ShortEventAccessoryOn1
+            | OpCode::ShortEventAccessoryOff1
+            | OpCode::ShortEventAccessoryStateOn1
+            | OpCode::ShortEventAccessoryStateOff1
+            | OpCode::DiagnosticData
+            | OpCode::LongEventAccessoryOn2
+            | OpCode::LongEventAccessoryOff2
+            | OpCode::TeachEvent
+            | OpCode::EventVariableValueInLearnMode
+            | OpCode::LongEventAccessoryStateOn2
+            | OpCode::LongEventAccessoryStateOff2
+            | OpCode::ShortEventAccessoryOn2
+            | OpCode::ShortEventAccessoryOff2
+            | OpCode::ShortEventAccessoryStateOn2
+            | OpCode::ShortEventAccessoryStateOff2
+            | OpCode::DccCommandStationStatus
+            | OpCode::EventAck
+            | OpCode::ExtendedServiceDiscoveryResponse
+            | OpCode::LongEventAccessoryOn3
+            | OpCode::LongEventAccessoryOff3
+            | OpCode::LearnedEventResponse
+            | OpCode::LongEventAccessoryStateOn3
+            | OpCode::LongEventAccessoryStateOff3
+            | OpCode::TeachEventByIndex
+            | OpCode::DataEventAccessory
+            | OpCode::NodeDataEventResponse
+            | OpCode::ShortEventAccessoryOn3
+            | OpCode::ShortEventAccessoryOff3
+            | OpCode::ShortEventAccessoryStateOn3
+            | OpCode::ShortEventAccessoryStateOff3 => Some(0),
+            _ => None,
+        }?;
+        VlcbNodeNumber::from_bytes(self.param(offset, NODENUM_SIZE)).ok()
+    }
+
+    /// Return the event carried by the message
+    ///
+    /// Short events are constructed with [`EventId::short_from_bytes`].
+    pub fn event(&self) -> Option<EventId> {
+        let long_offset = match self.opcode {
+            OpCode::LongEventAccessoryOn
+            | OpCode::LongEventAccessoryOff
+            | OpCode::QueryLongEventAccessoryState
+            | OpCode::LongEventAccessoryStateOn
+            | OpCode::LongEventAccessoryStateOff
+            | OpCode::ForgetLearnedEvent
+            | OpCode::LongEventAccessoryOn1
+            | OpCode::LongEventAccessoryOff1
+            | OpCode::QueryEventVariableInLearnMode
+            | OpCode::LongEventAccessoryStateOn1
+            | OpCode::LongEventAccessoryStateOff1
+            | OpCode::LongEventAccessoryOn2
+            | OpCode::LongEventAccessoryOff2
+            | OpCode::TeachEvent
+            | OpCode::EventVariableValueInLearnMode
+            | OpCode::LongEventAccessoryStateOn2
+            | OpCode::LongEventAccessoryStateOff2
+            | OpCode::LongEventAccessoryOn3
+            | OpCode::LongEventAccessoryOff3
+            | OpCode::LongEventAccessoryStateOn3
+            | OpCode::LongEventAccessoryStateOff3
+            | OpCode::TeachEventByIndex => Some(0),
+            OpCode::LearnedEventResponse => Some(2),
+            OpCode::EventAck => Some(3),
+            _ => None,
+        };
+        if let Some(offset) = long_offset {
+            return EventId::from_bytes(self.param(offset, EVENT_SIZE)).ok();
+        }
+
+        let short_offset = match self.opcode {
+            OpCode::ShortEventAccessoryOn
+            | OpCode::ShortEventAccessoryOff
+            | OpCode::QueryShortEventAccessoryState
+            | OpCode::ShortEventAccessoryStateOn
+            | OpCode::ShortEventAccessoryStateOff
+            | OpCode::ShortEventAccessoryOn1
+            | OpCode::ShortEventAccessoryOff1
+            | OpCode::ShortEventAccessoryStateOn1
+            | OpCode::ShortEventAccessoryStateOff1
+            | OpCode::ShortEventAccessoryOn2
+            | OpCode::ShortEventAccessoryOff2
+            | OpCode::ShortEventAccessoryStateOn2
+            | OpCode::ShortEventAccessoryStateOff2
+            | OpCode::ShortEventAccessoryOn3
+            | OpCode::ShortEventAccessoryOff3
+            | OpCode::ShortEventAccessoryStateOn3
+            | OpCode::ShortEventAccessoryStateOff3 => Some(0),
+            _ => None,
+        }?;
+        EventId::short_from_bytes(self.param(short_offset, EVENT_SIZE)).ok()
+    }
+
+    /// Return the DCC session of the message
+    pub fn session(&self) -> Option<u8> {
+        let offset = match self.opcode {
+            OpCode::DccReleaseSession
+            | OpCode::DccQueryLocoStatus
+            | OpCode::DccSessionKeepAlive
+            | OpCode::DccAllocateLocoToActivity
+            | OpCode::DccSetThrottleMode
+            | OpCode::DccConsistAddLoco
+            | OpCode::DccConsistRemoveLoco
+            | OpCode::DccSetLocoThrottle
+            | OpCode::DccSetLocoFlags
+            | OpCode::DccLocoFunctionOn
+            | OpCode::DccLocoFunctionOff
+            | OpCode::DccServiceModeStatus
+            | OpCode::DccSetLocoFunctions
+            | OpCode::DccWriteCvByteInOpsMode
+            | OpCode::DcWriteCvBitInOpsMode
+            | OpCode::DccReadCv
+            | OpCode::DccCvValue
+            | OpCode::DccWriteCvInServiceMode
+            | OpCode::DccLocoReport => Some(0),
+            _ => None,
+        }?;
+        Some(self.param(offset, 1)[0])
+    }
+
+    /// Return the DCC speed and direction of the message
+    ///
+    /// The most significant bit is the direction, the 7 least significant bits are the speed.
+    pub fn speed_dir(&self) -> Option<u8> {
+        let offset = match self.opcode {
+            OpCode::DccSetLocoThrottle => Some(1),
+            OpCode::DccLocoReport => Some(3),
+            _ => None,
+        }?;
+        Some(self.param(offset, 1)[0])
+    }
+
+    /// Return the DCC locomotive address of the message
+    pub fn loco_address(&self) -> Option<LocoAddress> {
+        let offset = match self.opcode {
+            OpCode::DccRequestNewSession
+            | OpCode::DccQueryLocoSession
+            | OpCode::DccWriteCvByteInOpsModeByAddress
+            | OpCode::DccSendDataToCab => Some(0),
+            OpCode::DccLocoReport => Some(1),
+            _ => None,
+        }?;
+        let bytes = self.param(offset, 2);
+        Some(LocoAddress::from_bytes_sanitized([bytes[0], bytes[1]]))
+    }
+
+    /// Return the extended opcode of the message
+    ///
+    /// Only messages with one of the extended opcodes [`OpCode::ExtOpCode`] - [`OpCode::ExtOpCode6`]
+    /// carry an extended opcode.
+    pub fn extended_opcode(&self) -> Option<ExtendedOpCode> {
+        ExtendedOpCode::from_frame(self.data).ok()
+    }
+
+    /// Return the parameter octets at `offset` of the payload
+    ///
+    /// Offsets come from the tables above, which are checked by the tests
+    /// against the parameters of each opcode, see [`OpCode::parameters`].
+    fn param(&self, offset: usize, len: usize) -> &'a [u8] {
+        &self.payload()[offset..offset + len]
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for MessageRef<'a> {
+    type Error = DecodeError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        Self::new(data)
+    }
+}
+
+impl<'a> From<&'a Message> for MessageRef<'a> {
+    /// Borrow a built message, which is always valid
+    fn from(message: &'a Message) -> Self {
+        Self {
+            opcode: message.opcode(),
+            data: message.as_bytes(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Return the offset in the payload of the first parameter named one of `names`
+    /// by [`OpCode::parameters`]
+    fn spec_offset(opcode: OpCode, names: &[&str]) -> Option<usize> {
+        let mut offset = 0;
+        for param in opcode.parameters() {
+            if names.contains(&param.name) {
+                return Some(offset);
+            }
+            offset += param.size;
+        }
+        None
+    }
+
+    /// Return the offset of the event, a node number followed by an event number
+    fn spec_event_offset(opcode: OpCode) -> Option<usize> {
+        let params = opcode.parameters();
+        let mut offset = 0;
+        for (index, param) in params.iter().enumerate() {
+            let next = params.get(index + 1).map(|p| p.name);
+            match (param.name, next) {
+                ("NN", Some("EN")) | ("EN3", _) | ("EventNNh", _) => return Some(offset),
+                _ => offset += param.size,
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn test_offsets_match_spec() {
+        for op in 0..=u8::MAX {
+            let Ok(opcode) = OpCode::try_from(op) else {
+                continue;
+            };
+            let frame = [op, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77];
+            let msg = MessageRef::new(&frame[..opcode.frame_len()]).unwrap();
+            let payload = msg.payload();
+
+            let expected = spec_offset(opcode, &["NN"])
+                .map(|offset| VlcbNodeNumber::from_bytes(&payload[offset..offset + 2]).unwrap());
+            assert_eq!(msg.node_number(), expected, "node number of {:?}", opcode);
+
+            let expected = spec_event_offset(opcode).map(|offset| &payload[offset..offset + 4]);
+            let event = msg.event();
+            assert_eq!(
+                event.as_ref().map(EventId::as_bytes),
+                expected,
+                "event of {:?}",
+                opcode
+            );
+            if let Some(event) = event {
+                let short = ["AS", "ARS"]
+                    .iter()
+                    .any(|p| opcode.mnemonic().starts_with(p));
+                assert_eq!(event.is_short(), short, "short event of {:?}", opcode);
+            }
+
+            let expected = spec_offset(opcode, &["Session", "Session ID"]).map(|o| payload[o]);
+            assert_eq!(msg.session(), expected, "session of {:?}", opcode);
+
+            let expected = spec_offset(opcode, &["Speed/Dir"]).map(|o| payload[o]);
+            assert_eq!(msg.speed_dir(), expected, "speed of {:?}", opcode);
+
+            // the specification of CABDAT has no parameter table
+            let expected = match opcode {
+                OpCode::DccSendDataToCab => {
+                    assert!(opcode.parameters().is_empty());
+                    Some(0)
+                }
+                _ => spec_offset(opcode, &["Addr", "Dat1"]),
+            }
+            .map(|o| LocoAddress::from_bytes_sanitized([payload[o], payload[o + 1]]));
+            assert_eq!(msg.loco_address(), expected, "address of {:?}", opcode);
+        }
+    }
+
+    #[test]
+    fn test_event_accessors() {
+        let frame = [0xB0, 0x01, 0x02, 0x00, 0x10, 0xFF];
+        let msg = MessageRef::new(&frame).unwrap();
+        assert_eq!(msg.opcode(), OpCode::LongEventAccessoryOn1);
+        assert_eq!(msg.node_number(), Some(VlcbNodeNumber::new(0x01, 0x02)));
+        assert_eq!(msg.event(), EventId::from_bytes(&frame[1..5]).ok());
+        assert_eq!(msg.session(), None);
+
+        let frame = [0x98, 0x01, 0x02, 0x00, 0x10];
+        let msg = MessageRef::new(&frame).unwrap();
+        assert_eq!(msg.node_number(), Some(VlcbNodeNumber::new(0x01, 0x02)));
+        assert!(msg.event().unwrap().is_short());
+        assert_eq!(msg.event().unwrap().as_bytes(), &frame[1..5]);
+    }
+
+    #[test]
+    fn test_dcc_accessors() {
+        let frame = [0x47, 0x05, 0x85];
+        let msg = MessageRef::new(&frame).unwrap();
+        assert_eq!(msg.session(), Some(0x05));
+        assert_eq!(msg.speed_dir(), Some(0x85));
+        assert_eq!(msg.node_number(), None);
+
+        let frame = [0xE1, 0x01, 0xC4, 0xD2, 0x80, 0x01, 0x00, 0x00];
+        let msg = MessageRef::new(&frame).unwrap();
+        assert_eq!(msg.session(), Some(0x01));
+        assert_eq!(msg.loco_address(), Some(LocoAddress::new_long(1234)));
+        assert_eq!(msg.speed_dir(), Some(0x80));
+    }
+
+    #[test]
+    fn test_accessors_within_frame() {
+        for op in 0..=u8::MAX {
+            let Ok(opcode) = OpCode::try_from(op) else {
+                continue;
+            };
+            let mut frame = [0u8; 8];
+            frame[0] = op;
+            let msg = MessageRef::new(&frame[..opcode.frame_len()]).unwrap();
+            msg.node_number();
+            msg.event();
+            msg.session();
+            msg.speed_dir();
+            msg.loco_address();
+        }
+    }
+
+    #[test]
+    fn test_extended_opcode() {
+        let msg = MessageRef::new(&[0x5F, 0x42, 0x01]).unwrap();
+        assert_eq!(msg.extended_opcode().map(|ext| ext.code()), Some(0x42));
+        assert_eq!(MessageRef::new(&[0x02]).unwrap().extended_opcode(), None);
+    }
+
+    #[test]
+    fn test_invalid_length() {
+        assert!(MessageRef::new(&[0x47, 0x05]).is_err());
+    }
+}
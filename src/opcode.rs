@@ -0,0 +1,258 @@
+use core::fmt;
+use core::str::FromStr;
+
+use crate::error::DecodeError;
+use crate::OpCode;
+
+/// Opcode length class
+///
+/// The top three bits of an opcode define the number of data octets
+/// that follow the opcode in a message.
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum LengthClass {
+    /// Opcodes `0x00` - `0x1F` without any data
+    Data0 = 0,
+    /// Opcodes `0x20` - `0x3F` with one data octet
+    Data1 = 1,
+    /// Opcodes `0x40` - `0x5F` with two data octets
+    Data2 = 2,
+    /// Opcodes `0x60` - `0x7F` with three data octets
+    Data3 = 3,
+    /// Opcodes `0x80` - `0x9F` with four data octets
+    Data4 = 4,
+    /// Opcodes `0xA0` - `0xBF` with five data octets
+    Data5 = 5,
+    /// Opcodes `0xC0` - `0xDF` with six data octets
+    Data6 = 6,
+    /// Opcodes `0xE0` - `0xFF` with seven data octets
+    Data7 = 7,
+}
+
+impl LengthClass {
+    /// Return the length class of a raw opcode value
+    ///
+    /// This works for opcodes that are not known to the library as well.
+    pub const fn from_opcode(opcode: u8) -> Self {
+        match opcode >> 5 {
+            0 => Self::Data0,
+            1 => Self::Data1,
+            2 => Self::Data2,
+            3 => Self::Data3,
+            4 => Self::Data4,
+            5 => Self::Data5,
+            6 => Self::Data6,
+            _ => Self::Data7,
+        }
+    }
+
+    /// Number of data octets following the opcode
+    pub const fn data_len(self) -> usize {
+        self as usize
+    }
+
+    /// Number of octets of the whole message, opcode included
+    pub const fn frame_len(self) -> usize {
+        self.data_len() + 1
+    }
+
+    /// Return the extended opcode reserved for this length class
+    ///
+    /// The extended opcode is the last opcode of the class. There is none
+    /// for [`LengthClass::Data0`] because the extended opcode needs a data octet
+    /// to carry the second opcode byte.
+    pub const fn ext_opcode(self) -> Option<OpCode> {
+        match self {
+            Self::Data0 => None,
+            Self::Data1 => Some(OpCode::ExtOpCode),
+            Self::Data2 => Some(OpCode::ExtOpCode1),
+            Self::Data3 => Some(OpCode::ExtOpCode2),
+            Self::Data4 => Some(OpCode::ExtOpCode3),
+            Self::Data5 => Some(OpCode::ExtOpCode4),
+            Self::Data6 => Some(OpCode::ExtOpCode5),
+            Self::Data7 => Some(OpCode::ExtOpCode6),
+        }
+    }
+}
+
+/// Parameter of an opcode as defined by the opcode specification
+///
+/// Parameters are listed by [`OpCode::parameters`] in the order they follow the opcode.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OpCodeParameter {
+    /// Name of the parameter as used by the specification, e.g. `NN`
+    pub name: &'static str,
+    /// Size of the parameter in octets
+    pub size: usize,
+    /// Description of the parameter, may be empty
+    pub description: &'static str,
+}
+
+/// Error returned when parsing an unknown opcode name
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ParseOpCodeError;
+
+impl fmt::Display for ParseOpCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("unknown opcode name")
+    }
+}
+
+impl OpCode {
+    /// Return the length class of the opcode
+    pub const fn length_class(self) -> LengthClass {
+        LengthClass::from_opcode(self as u8)
+    }
+
+    /// Number of data octets following the opcode
+    pub const fn data_len(self) -> usize {
+        self.length_class().data_len()
+    }
+
+    /// Number of octets of the whole message, opcode included
+    ///
+    /// This is the DLC of a CAN frame carrying the message.
+    pub const fn frame_len(self) -> usize {
+        self.length_class().frame_len()
+    }
+
+    /// Check whether the opcode is one of the extended opcodes `EXTC` - `EXTC6`
+    pub const fn is_extended(self) -> bool {
+        self as u8 & 0x1F == 0x1F && !matches!(self.length_class(), LengthClass::Data0)
+    }
+
+    /// Validate that the frame data starts with a known opcode and
+    /// that its length matches the length defined by the opcode.
+    ///
+    /// Returns the opcode of the frame.
+    pub fn validate_frame(frame: &[u8]) -> Result<Self, DecodeError> {
+        let &op = frame.first().ok_or(DecodeError::WrongLength {
+            expected: 1,
+            actual: 0,
+        })?;
+        let opcode = Self::try_from(op).map_err(|_| DecodeError::UnknownOpcode(op))?;
+
+        if frame.len() != opcode.frame_len() {
+            return Err(DecodeError::WrongLength {
+                expected: opcode.frame_len(),
+                actual: frame.len(),
+            });
+        }
+
+        Ok(opcode)
+    }
+}
+
+impl FromStr for OpCode {
+    type Err = ParseOpCodeError;
+
+    /// Parse an opcode from its mnemonic (e.g. `ACON`) or its Rust name
+    /// (e.g. `LongEventAccessoryOn`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_name(s).ok_or(ParseOpCodeError)
+    }
+}
+
+impl fmt::Display for OpCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.mnemonic())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_lengths() {
+        assert_eq!(OpCode::BusHalt.data_len(), 0);
+        assert_eq!(OpCode::QueryNodeParameterByIndex.data_len(), 3);
+        assert_eq!(OpCode::LongEventAccessoryOn.frame_len(), 5);
+        assert_eq!(OpCode::DccLocoReport.frame_len(), 8);
+        assert_eq!(OpCode::ExtOpCode2.length_class(), LengthClass::Data3);
+    }
+
+    #[test]
+    fn test_extended() {
+        assert_eq!(LengthClass::Data0.ext_opcode(), None);
+        for class in 1..8u8 {
+            let class = LengthClass::from_opcode(class << 5);
+            let ext = class.ext_opcode().unwrap();
+            assert!(ext.is_extended());
+            assert_eq!(ext.length_class(), class);
+        }
+        assert!(!OpCode::BusHalt.is_extended());
+    }
+
+    #[test]
+    fn test_parameters_match_length() {
+        for op in 0..=u8::MAX {
+            let Ok(opcode) = OpCode::try_from(op) else {
+                continue;
+            };
+            let params = opcode.parameters();
+            if params.is_empty() {
+                continue;
+            }
+            let size: usize = params.iter().map(|p| p.size).sum();
+            assert_eq!(size, opcode.data_len(), "{:?}", opcode);
+        }
+    }
+
+    #[test]
+    fn test_metadata() {
+        use crate::{MessageDirection, OpCodePriority, ServiceType};
+
+        assert_eq!(OpCode::BusHalt.priority(), OpCodePriority::High);
+        assert_eq!(OpCode::LongEventAccessoryOn.priority(), OpCodePriority::Low);
+        assert_eq!(
+            OpCode::QueryNodeParameterByIndex.services(),
+            &[ServiceType::MinimumNodeService]
+        );
+        assert_eq!(
+            OpCode::NodeParameterValue.direction(),
+            MessageDirection::FromModule
+        );
+        assert_eq!(OpCode::SetNodeCanId.parameters()[1].name, "CAN_ID");
+        assert_eq!(OpCode::GeneralAck.modes(), &["Normal", "Learn", "NOHEARTB"]);
+        assert!(OpCode::BusHalt.modes().is_empty());
+    }
+
+    #[test]
+    fn test_mnemonic() {
+        assert_eq!(OpCode::LongEventAccessoryOn.mnemonic(), "ACON");
+        assert_eq!(OpCode::QueryNodeParameterByIndex.to_string(), "RQNPN");
+        assert_eq!("DSPD".parse(), Ok(OpCode::DccSetLocoThrottle));
+        assert_eq!("BusHalt".parse(), Ok(OpCode::BusHalt));
+        assert_eq!("NOPE".parse::<OpCode>(), Err(ParseOpCodeError));
+
+        for op in 0..=u8::MAX {
+            if let Ok(opcode) = OpCode::try_from(op) {
+                assert_eq!(opcode.mnemonic().parse(), Ok(opcode));
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_frame() {
+        assert_eq!(
+            OpCode::validate_frame(&[0x90, 0, 1, 0, 1]),
+            Ok(OpCode::LongEventAccessoryOn)
+        );
+        assert_eq!(
+            OpCode::validate_frame(&[0x90, 0, 1, 0, 1, 0]),
+            Err(DecodeError::WrongLength {
+                expected: 5,
+                actual: 6
+            })
+        );
+        assert_eq!(
+            OpCode::validate_frame(&[0x0B]),
+            Err(DecodeError::UnknownOpcode(0x0B))
+        );
+    }
+}
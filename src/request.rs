@@ -0,0 +1,241 @@
+use heapless::Vec;
+
+use crate::clock::Clock;
+use crate::error::VlcbError;
+use crate::message_ref::MessageRef;
+use crate::vlcb::VlcbNodeNumber;
+use crate::OpCode;
+
+/// A request waiting for its response
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct PendingRequest {
+    /// Opcode of the request
+    pub opcode: OpCode,
+    /// Node number the request was addressed to, if the request carries one
+    ///
+    /// Learn mode requests carry the node number of an event instead, they are
+    /// addressed to the node in learn mode and tracked without a node number.
+    pub node_number: Option<VlcbNodeNumber>,
+    /// Time in milliseconds after which the request is considered unanswered
+    pub deadline_ms: u64,
+}
+
+impl PendingRequest {
+    /// Check whether a received message answers the request
+    ///
+    /// The opcode of the message has to be one of [`OpCode::expected_responses`]
+    /// and the node numbers have to match when the request was addressed to a node.
+    /// A [`OpCode::GenericResponse`] also has to name the opcode of the request.
+    pub fn is_answered_by(&self, response: &MessageRef<'_>) -> bool {
+        if !self
+            .opcode
+            .expected_responses()
+            .contains(&response.opcode())
+        {
+            return false;
+        }
+        if self.node_number.is_some() && response.node_number() != self.node_number {
+            return false;
+        }
+        if response.opcode() == OpCode::GenericResponse {
+            return response.payload()[2] == u8::from(self.opcode);
+        }
+        true
+    }
+}
+
+/// Tracker of outstanding requests
+///
+/// Matches received messages to the requests sent earlier using the
+/// [`OpCode::expected_responses`] table and the node number of the request.
+/// A request is completed by the first message answering it, requests which
+/// are answered by a series of messages need to be tracked again by the caller.
+///
+/// Up to `N` requests can be outstanding at once. Time is taken from a [`Clock`]
+/// passed to the methods that need it.
+#[derive(Debug, Clone)]
+pub struct RequestTracker<const N: usize> {
+    pending: Vec<PendingRequest, N>,
+    timeout_ms: u64,
+}
+
+impl<const N: usize> RequestTracker<N> {
+    /// Construct a tracker that expires requests after `timeout_ms` milliseconds
+    pub const fn new(timeout_ms: u64) -> Self {
+        Self {
+            pending: Vec::new(),
+            timeout_ms,
+        }
+    }
+
+    /// Start tracking a sent request
+    ///
+    /// Returns [`VlcbError::UnexpectedOpcode`] if no response is expected for the opcode
+    /// and [`VlcbError::CapacityExceeded`] if `N` requests are already outstanding.
+    pub fn track(&mut self, clock: &impl Clock, request: MessageRef<'_>) -> Result<(), VlcbError> {
+        if request.opcode().expected_responses().is_empty() {
+            return Err(VlcbError::UnexpectedOpcode(request.opcode()));
+        }
+
+        let node_number = match request.opcode() {
+            OpCode::TeachEvent
+            | OpCode::TeachEventByIndex
+            | OpCode::ForgetLearnedEvent
+            | OpCode::QueryEventVariableInLearnMode => None,
+            _ => request.node_number(),
+        };
+        let pending = PendingRequest {
+            opcode: request.opcode(),
+            node_number,
+            deadline_ms: clock.now_ms().saturating_add(self.timeout_ms),
+        };
+        self.pending
+            .push(pending)
+            .map_err(|_| VlcbError::CapacityExceeded)
+    }
+
+    /// Match a received message against the outstanding requests
+    ///
+    /// Returns the oldest request answered by the message and stops tracking it.
+    pub fn on_message(&mut self, response: MessageRef<'_>) -> Option<PendingRequest> {
+        let index = self
+            .pending
+            .iter()
+            .position(|pending| pending.is_answered_by(&response))?;
+        Some(self.pending.remove(index))
+    }
+
+    /// Return a request whose deadline has passed and stop tracking it
+    ///
+    /// Call repeatedly until it returns `None` to collect all expired requests.
+    pub fn poll_expired(&mut self, clock: &impl Clock) -> Option<PendingRequest> {
+        let now = clock.now_ms();
+        let index = self
+            .pending
+            .iter()
+            .position(|pending| pending.deadline_ms <= now)?;
+        Some(self.pending.remove(index))
+    }
+
+    /// Return the outstanding requests, oldest first
+    pub fn pending(&self) -> &[PendingRequest] {
+        &self.pending
+    }
+
+    /// Check whether there are no outstanding requests
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Stop tracking all requests
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::cell::Cell;
+
+    #[test]
+    fn test_expected_responses() {
+        assert_eq!(
+            OpCode::QueryNodeParameterByIndex.expected_responses(),
+            &[
+                OpCode::NodeParameterValue,
+                OpCode::NodeConfigurationError,
+                OpCode::GenericResponse
+            ]
+        );
+        assert_eq!(
+            OpCode::QueryEventVariable.expected_responses(),
+            &[
+                OpCode::EventVariableValue,
+                OpCode::NodeConfigurationError,
+                OpCode::GenericResponse
+            ]
+        );
+        assert!(OpCode::LongEventAccessoryOn.expected_responses().is_empty());
+    }
+
+    #[test]
+    fn test_matching() {
+        let now = Cell::new(0u64);
+        let clock = || now.get();
+        let mut tracker = RequestTracker::<2>::new(1000);
+
+        let rqnpn = [0x73, 0x01, 0x02, 0x03];
+        tracker
+            .track(&clock, MessageRef::new(&rqnpn).unwrap())
+            .unwrap();
+        let nvrd = [0x71, 0x01, 0x02, 0x01];
+        tracker
+            .track(&clock, MessageRef::new(&nvrd).unwrap())
+            .unwrap();
+        assert_eq!(
+            tracker.track(&clock, MessageRef::new(&rqnpn).unwrap()),
+            Err(VlcbError::CapacityExceeded)
+        );
+        assert_eq!(
+            tracker.track(&clock, MessageRef::new(&[0x02]).unwrap()),
+            Err(VlcbError::UnexpectedOpcode(OpCode::BusHalt))
+        );
+
+        // PARAN from another node
+        let paran = [0x9B, 0x01, 0x03, 0x03, 0x10];
+        assert_eq!(tracker.on_message(MessageRef::new(&paran).unwrap()), None);
+
+        // GRSP for another opcode
+        let grsp = [0xAF, 0x01, 0x02, 0x96, 0x02, 0x00];
+        assert_eq!(tracker.on_message(MessageRef::new(&grsp).unwrap()), None);
+
+        let nvans = [0x97, 0x01, 0x02, 0x01, 0x10];
+        let answered = tracker
+            .on_message(MessageRef::new(&nvans).unwrap())
+            .unwrap();
+        assert_eq!(answered.opcode, OpCode::QueryNodeVariable);
+        assert_eq!(answered.node_number, Some(VlcbNodeNumber::new(0x01, 0x02)));
+        assert_eq!(tracker.pending().len(), 1);
+    }
+
+    #[test]
+    fn test_learn_mode() {
+        let now = Cell::new(0u64);
+        let clock = || now.get();
+        let mut tracker = RequestTracker::<2>::new(1000);
+
+        // EVLRN of an event of node 7, taught to the node 256 in learn mode
+        let evlrn = [0xD2, 0x00, 0x07, 0x00, 0x01, 0x01, 0x05];
+        tracker
+            .track(&clock, MessageRef::new(&evlrn).unwrap())
+            .unwrap();
+        assert_eq!(tracker.pending()[0].node_number, None);
+
+        let wrack = [0x59, 0x01, 0x00];
+        let answered = tracker
+            .on_message(MessageRef::new(&wrack).unwrap())
+            .unwrap();
+        assert_eq!(answered.opcode, OpCode::TeachEvent);
+        assert!(tracker.is_empty());
+    }
+
+    #[test]
+    fn test_timeout() {
+        let now = Cell::new(500u64);
+        let clock = || now.get();
+        let mut tracker = RequestTracker::<4>::new(1000);
+
+        tracker
+            .track(&clock, MessageRef::new(&[0x0D]).unwrap())
+            .unwrap();
+        now.set(1499);
+        assert_eq!(tracker.poll_expired(&clock), None);
+        now.set(1500);
+        assert_eq!(
+            tracker.poll_expired(&clock).map(|p| p.opcode),
+            Some(OpCode::QueryNodeInfo)
+        );
+        assert!(tracker.is_empty());
+    }
+}
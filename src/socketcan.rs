@@ -0,0 +1,492 @@
+use std::ffi::CString;
+use std::io;
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, OwnedFd, RawFd};
+use std::time::{Duration, Instant};
+
+use crate::can::CanHeader;
+use crate::capture::TimestampedFrame;
+use crate::frame::{CanFrame, FrameFilter, FrameId, MAX_FRAME_DATA};
+use crate::transport::Transport;
+
+/// Raw CAN socket bound to a Linux SocketCAN interface, such as `can0` or `vcan0`
+///
+/// Received frames carry the receive timestamp of the kernel as the time since the
+/// Unix epoch, the same as candump logs, so they can be written with a
+/// [`CaptureWriter`](crate::capture::CaptureWriter) as they are. Frames which do not
+/// pass the [`FrameFilter`] of the socket are discarded when receiving.
+///
+/// Receiving can block ([`CanSocket::receive_blocking`]), wait for a limited time
+/// ([`CanSocket::receive_timeout`]) or return immediately ([`CanSocket::try_receive`]).
+/// For async receive, register the socket through [`AsFd`] with the reactor of the
+/// runtime, e.g. `tokio::io::unix::AsyncFd`, and call [`CanSocket::try_receive`]
+/// whenever it is readable.
+///
+/// Frames sent by the socket are not received by itself, but by the other sockets
+/// bound to the interface.
+#[derive(Debug)]
+pub struct CanSocket {
+    fd: OwnedFd,
+    filter: FrameFilter,
+}
+
+impl CanSocket {
+    /// Open a socket on the interface named `interface`
+    pub fn open(interface: &str) -> io::Result<Self> {
+        let name = CString::new(interface)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        Ok(Self {
+            fd: sys::open(&name)?,
+            filter: FrameFilter::new(),
+        })
+    }
+
+    /// Return the filter of received frames
+    pub fn filter(&self) -> FrameFilter {
+        self.filter
+    }
+
+    /// Set the filter of received frames
+    pub fn set_filter(&mut self, filter: FrameFilter) {
+        self.filter = filter;
+    }
+
+    /// Send a frame, blocking while the transmit queue of the interface is full
+    pub fn send(&self, frame: &CanFrame) -> io::Result<()> {
+        sys::send(&self.fd, &to_raw(frame))
+    }
+
+    /// Wait for a frame passing the filter, blocking until one is received
+    pub fn receive_blocking(&self) -> io::Result<TimestampedFrame> {
+        loop {
+            if let Some(frame) = self.read(false)? {
+                return Ok(frame);
+            }
+        }
+    }
+
+    /// Wait up to `timeout` for a frame passing the filter
+    ///
+    /// Returns `None` if no frame has been received in time.
+    pub fn receive_timeout(&self, timeout: Duration) -> io::Result<Option<TimestampedFrame>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if sys::poll(&self.fd, remaining)? {
+                if let Some(frame) = self.read(true)? {
+                    return Ok(Some(frame));
+                }
+            }
+            if remaining.is_zero() {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Return a received frame passing the filter without waiting
+    ///
+    /// Returns `None` if no frame is pending.
+    pub fn try_receive(&self) -> io::Result<Option<TimestampedFrame>> {
+        loop {
+            match sys::receive(&self.fd, true)? {
+                None => return Ok(None),
+                Some((raw, timestamp)) => {
+                    if let Some(frame) = self.accept(&raw, timestamp)? {
+                        return Ok(Some(frame));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Read one frame from the socket, returning it if it passes the filter
+    fn read(&self, nonblocking: bool) -> io::Result<Option<TimestampedFrame>> {
+        match sys::receive(&self.fd, nonblocking)? {
+            Some((raw, timestamp)) => self.accept(&raw, timestamp),
+            None => Ok(None),
+        }
+    }
+
+    fn accept(
+        &self,
+        raw: &sys::RawFrame,
+        timestamp: Duration,
+    ) -> io::Result<Option<TimestampedFrame>> {
+        let frame = from_raw(raw)?;
+        Ok(self
+            .filter
+            .matches_can_frame(&frame)
+            .then_some(TimestampedFrame { timestamp, frame }))
+    }
+}
+
+impl Transport for CanSocket {
+    fn send(&mut self, frame: &CanFrame) -> io::Result<()> {
+        CanSocket::send(self, frame)
+    }
+
+    fn receive(&mut self, timeout: Duration) -> io::Result<Option<CanFrame>> {
+        Ok(self
+            .receive_timeout(timeout)?
+            .map(|received| received.frame))
+    }
+}
+
+impl AsFd for CanSocket {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+impl AsRawFd for CanSocket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+fn to_raw(frame: &CanFrame) -> sys::RawFrame {
+    let mut id = match frame.id() {
+        FrameId::Standard(header) => u32::from(header.to_standard_id()),
+        FrameId::Extended(raw) => raw | libc::CAN_EFF_FLAG,
+    };
+    if frame.is_remote() {
+        id |= libc::CAN_RTR_FLAG;
+    }
+
+    let mut data = [0; MAX_FRAME_DATA];
+    data[..frame.data().len()].copy_from_slice(frame.data());
+    sys::RawFrame {
+        id,
+        len: frame.data().len() as u8,
+        data,
+    }
+}
+
+fn from_raw(raw: &sys::RawFrame) -> io::Result<CanFrame> {
+    let invalid = |err| io::Error::new(io::ErrorKind::InvalidData, err);
+
+    let id = if raw.id & libc::CAN_EFF_FLAG != 0 {
+        FrameId::Extended(raw.id & libc::CAN_EFF_MASK)
+    } else {
+        let header = CanHeader::from_standard_id((raw.id & libc::CAN_SFF_MASK) as u16);
+        FrameId::Standard(header.map_err(invalid)?)
+    };
+    let frame = if raw.id & libc::CAN_RTR_FLAG != 0 {
+        CanFrame::remote(id)
+    } else {
+        let len = usize::from(raw.len).min(MAX_FRAME_DATA);
+        CanFrame::new(id, &raw.data[..len])
+    };
+    frame.map_err(invalid)
+}
+
+/// System calls of the socket
+#[allow(unsafe_code)]
+mod sys {
+    use std::ffi::CStr;
+    use std::io;
+    use std::mem;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::ptr;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use crate::frame::MAX_FRAME_DATA;
+
+    /// Frame as exchanged with the kernel, the identifier includes the flags
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub struct RawFrame {
+        pub id: u32,
+        pub len: u8,
+        pub data: [u8; MAX_FRAME_DATA],
+    }
+
+    fn check(result: libc::c_int) -> io::Result<libc::c_int> {
+        if result < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(result)
+        }
+    }
+
+    pub fn open(interface: &CStr) -> io::Result<OwnedFd> {
+        // SAFETY: `interface` is a valid NUL terminated string
+        let index = unsafe { libc::if_nametoindex(interface.as_ptr()) };
+        if index == 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        // SAFETY: plain system call without pointers
+        let fd = check(unsafe {
+            libc::socket(
+                libc::AF_CAN,
+                libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+                libc::CAN_RAW,
+            )
+        })?;
+        // SAFETY: the descriptor was just opened and is not owned by anything else
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        let enable: libc::c_int = 1;
+        // SAFETY: the option value points to a c_int of the given size
+        check(unsafe {
+            libc::setsockopt(
+                fd.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_TIMESTAMP,
+                ptr::addr_of!(enable).cast(),
+                mem::size_of_val(&enable) as libc::socklen_t,
+            )
+        })?;
+
+        // SAFETY: sockaddr_can is plain data, all zeroes is a valid value
+        let mut addr: libc::sockaddr_can = unsafe { mem::zeroed() };
+        addr.can_family = libc::AF_CAN as libc::sa_family_t;
+        addr.can_ifindex = index as libc::c_int;
+        // SAFETY: the address points to a sockaddr_can of the given size
+        check(unsafe {
+            libc::bind(
+                fd.as_raw_fd(),
+                ptr::addr_of!(addr).cast(),
+                mem::size_of_val(&addr) as libc::socklen_t,
+            )
+        })?;
+        Ok(fd)
+    }
+
+    pub fn send(fd: &OwnedFd, frame: &RawFrame) -> io::Result<()> {
+        // SAFETY: can_frame is plain data, all zeroes is a valid value
+        let mut raw: libc::can_frame = unsafe { mem::zeroed() };
+        raw.can_id = frame.id;
+        raw.can_dlc = frame.len;
+        raw.data = frame.data;
+
+        // SAFETY: the buffer points to a can_frame of the given size
+        let written = unsafe {
+            libc::write(
+                fd.as_raw_fd(),
+                ptr::addr_of!(raw).cast(),
+                mem::size_of_val(&raw),
+            )
+        };
+        match written {
+            ..=-1 => Err(io::Error::last_os_error()),
+            len if len as usize == mem::size_of_val(&raw) => Ok(()),
+            _ => Err(io::ErrorKind::WriteZero.into()),
+        }
+    }
+
+    /// Wait up to `timeout` for the socket to become readable
+    pub fn poll(fd: &OwnedFd, timeout: Duration) -> io::Result<bool> {
+        let mut pollfd = libc::pollfd {
+            fd: fd.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // round up, so a wait shorter than a millisecond does not spin
+        let millis = (timeout.as_nanos() + 999_999) / 1_000_000;
+        let millis = millis.min(libc::c_int::MAX as u128) as libc::c_int;
+        // SAFETY: the pointer refers to a single pollfd
+        match check(unsafe { libc::poll(&mut pollfd, 1, millis) }) {
+            Ok(ready) => Ok(ready > 0),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Read a frame and its receive timestamp
+    ///
+    /// Returns `None` if `nonblocking` is set and no frame is pending.
+    pub fn receive(fd: &OwnedFd, nonblocking: bool) -> io::Result<Option<(RawFrame, Duration)>> {
+        // SAFETY: can_frame is plain data, all zeroes is a valid value
+        let mut raw: libc::can_frame = unsafe { mem::zeroed() };
+        let mut iov = libc::iovec {
+            iov_base: ptr::addr_of_mut!(raw).cast(),
+            iov_len: mem::size_of_val(&raw),
+        };
+        // u64 elements align the buffer for the control message headers
+        let mut control = [0u64; 8];
+        // SAFETY: msghdr is plain data, all zeroes is a valid value
+        let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = control.as_mut_ptr().cast();
+        msg.msg_controllen = mem::size_of_val(&control) as _;
+
+        let flags = if nonblocking { libc::MSG_DONTWAIT } else { 0 };
+        // SAFETY: the message header points to the frame and the control buffer above
+        let len = unsafe { libc::recvmsg(fd.as_raw_fd(), &mut msg, flags) };
+        if len < 0 {
+            let err = io::Error::last_os_error();
+            return match err.kind() {
+                io::ErrorKind::WouldBlock if nonblocking => Ok(None),
+                io::ErrorKind::Interrupted => Ok(None),
+                _ => Err(err),
+            };
+        }
+        if len as usize != mem::size_of_val(&raw) {
+            return Err(io::ErrorKind::InvalidData.into());
+        }
+
+        let mut timestamp = None;
+        // SAFETY: the control buffer was filled by recvmsg, the macros stay within
+        // msg_controllen and the timestamp is read unaligned
+        unsafe {
+            let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+            while !cmsg.is_null() {
+                if (*cmsg).cmsg_level == libc::SOL_SOCKET
+                    && (*cmsg).cmsg_type == libc::SCM_TIMESTAMP
+                {
+                    let time: libc::timeval = ptr::read_unaligned(libc::CMSG_DATA(cmsg).cast());
+                    timestamp = Some(Duration::new(
+                        time.tv_sec as u64,
+                        time.tv_usec as u32 * 1000,
+                    ));
+                }
+                cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+            }
+        }
+        // the kernel always sends the timestamp once enabled, the clock is a fallback
+        let timestamp = match timestamp {
+            Some(timestamp) => timestamp,
+            None => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default(),
+        };
+
+        Ok(Some((
+            RawFrame {
+                id: raw.can_id,
+                len: raw.can_dlc,
+                data: raw.data,
+            },
+            timestamp,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::can::VlcbCanId;
+    use crate::OpCode;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    /// Virtual interface the socket tests run against
+    ///
+    /// Create it with `ip link add dev vcan0 type vcan && ip link set up vcan0`,
+    /// the tests which need it pass without checking anything if it is missing.
+    const INTERFACE: &str = "vcan0";
+
+    const TIMEOUT: Duration = Duration::from_secs(1);
+
+    fn frame(can_id: u8, data: &[u8]) -> CanFrame {
+        let header = CanHeader::for_opcode(VlcbCanId([can_id]), OpCode::BusHalt);
+        CanFrame::new(FrameId::Standard(header), data).unwrap()
+    }
+
+    fn open_pair() -> Option<(CanSocket, CanSocket)> {
+        match (CanSocket::open(INTERFACE), CanSocket::open(INTERFACE)) {
+            (Ok(a), Ok(b)) => Some((a, b)),
+            (Err(err), _) | (_, Err(err)) => {
+                eprintln!("skipping, {INTERFACE} is not available: {err}");
+                None
+            }
+        }
+    }
+
+    #[test]
+    fn test_raw() {
+        let frames = [
+            frame(0x05, &[0x02]),
+            frame(0x7F, &[0x90, 0, 1, 0, 2]),
+            CanFrame::remote(FrameId::Standard(CanHeader::default())).unwrap(),
+            CanFrame::new(FrameId::Extended(0x1000_0004), &[1, 2, 3, 4, 5, 6, 7, 8]).unwrap(),
+            CanFrame::remote(FrameId::Extended(4)).unwrap(),
+        ];
+        for frame in frames {
+            assert_eq!(from_raw(&to_raw(&frame)).unwrap(), frame);
+        }
+
+        let raw = to_raw(&frames[1]);
+        assert_eq!(raw.id, 0x47F);
+        assert_eq!(raw.len, 5);
+        assert_eq!(to_raw(&frames[3]).id, 0x9000_0004);
+        assert_eq!(to_raw(&frames[4]).id, 0xC000_0004);
+    }
+
+    #[test]
+    fn test_open_unknown_interface() {
+        assert!(CanSocket::open("vlcb-missing0").is_err());
+        assert_eq!(
+            CanSocket::open("can\0").unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn test_send_receive() {
+        let Some((a, mut b)) = open_pair() else {
+            return;
+        };
+        let before = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+
+        let extended = CanFrame::new(FrameId::Extended(0x1000_0004), &[1, 2]).unwrap();
+        a.send(&frame(0x05, &[0x02])).unwrap();
+        a.send(&extended).unwrap();
+
+        let received = b.receive_blocking().unwrap();
+        assert_eq!(received.frame, frame(0x05, &[0x02]));
+        assert!(received.timestamp >= before);
+        assert!(received.timestamp < before + Duration::from_secs(60));
+        assert_eq!(Transport::receive(&mut b, TIMEOUT).unwrap(), Some(extended));
+
+        // the sending socket does not receive its own frames
+        assert_eq!(a.receive_timeout(Duration::from_millis(20)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_try_receive() {
+        let Some((a, b)) = open_pair() else {
+            return;
+        };
+        assert_eq!(b.try_receive().unwrap(), None);
+
+        a.send(&frame(0x05, &[0x02])).unwrap();
+        let start = Instant::now();
+        let received = loop {
+            if let Some(received) = b.try_receive().unwrap() {
+                break received;
+            }
+            assert!(start.elapsed() < TIMEOUT, "frame was not received");
+            std::thread::sleep(Duration::from_millis(1));
+        };
+        assert_eq!(received.frame, frame(0x05, &[0x02]));
+    }
+
+    #[test]
+    fn test_filter() {
+        let Some((a, mut b)) = open_pair() else {
+            return;
+        };
+        b.set_filter(
+            FrameFilter::new()
+                .opcode(OpCode::BusHalt)
+                .can_id(VlcbCanId([0x05])),
+        );
+
+        let acon = CanFrame::new(
+            FrameId::Standard(CanHeader::for_opcode(
+                VlcbCanId([0x05]),
+                OpCode::LongEventAccessoryOn,
+            )),
+            &[0x90, 0, 1, 0, 2],
+        )
+        .unwrap();
+        a.send(&frame(0x06, &[0x02])).unwrap();
+        a.send(&acon).unwrap();
+        a.send(&frame(0x05, &[0x02])).unwrap();
+
+        let received = b.receive_timeout(TIMEOUT).unwrap().unwrap();
+        assert_eq!(received.frame, frame(0x05, &[0x02]));
+        assert_eq!(b.receive_timeout(Duration::from_millis(20)).unwrap(), None);
+    }
+}
@@ -0,0 +1,481 @@
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+use crate::frame::CanFrame;
+use crate::gridconnect::GridConnectCodec;
+use crate::transport::Transport;
+
+/// Port used by the CBUS TCP servers of JMRI and CbusServer
+pub const DEFAULT_PORT: u16 = 5550;
+
+/// Size of the buffer receiving GridConnect text
+const READ_BUFFER_SIZE: usize = 512;
+
+/// Longest time spent writing to a client before it is disconnected
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Number of client frames queued for the bridged interface
+///
+/// Frames sent by clients while the queue is full are dropped.
+pub const FRAME_QUEUE_CAPACITY: usize = 256;
+
+/// Client of a GridConnect TCP server
+///
+/// Frames are exchanged as GridConnect text, see [`GridConnectCodec`].
+#[derive(Debug)]
+pub struct GridConnectClient {
+    stream: TcpStream,
+    codec: GridConnectCodec,
+    received: VecDeque<CanFrame>,
+}
+
+impl GridConnectClient {
+    /// Connect to a server
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        Ok(Self::from_stream(TcpStream::connect(addr)?))
+    }
+
+    /// Use an established connection
+    pub fn from_stream(stream: TcpStream) -> Self {
+        Self {
+            stream,
+            codec: GridConnectCodec::new(),
+            received: VecDeque::new(),
+        }
+    }
+
+    /// Return the address of the server
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.stream.peer_addr()
+    }
+
+    /// Send a frame
+    pub fn send(&mut self, frame: &CanFrame) -> io::Result<()> {
+        self.stream
+            .write_all(GridConnectCodec::encode(frame).as_bytes())
+    }
+
+    /// Wait for a frame, blocking until one is received
+    ///
+    /// Malformed frames are returned as [`io::ErrorKind::InvalidData`], the connection
+    /// stays usable. A closed connection is returned as [`io::ErrorKind::UnexpectedEof`].
+    pub fn receive_blocking(&mut self) -> io::Result<CanFrame> {
+        self.stream.set_read_timeout(None)?;
+        loop {
+            if let Some(frame) = self.read()? {
+                return Ok(frame);
+            }
+        }
+    }
+
+    /// Read once from the connection, returning the first received frame
+    fn read(&mut self) -> io::Result<Option<CanFrame>> {
+        if let Some(frame) = self.received.pop_front() {
+            return Ok(Some(frame));
+        }
+
+        let mut buf = [0; READ_BUFFER_SIZE];
+        let len = self.stream.read(&mut buf)?;
+        if len == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        let mut error = None;
+        for result in self.codec.decode(&buf[..len]) {
+            match result {
+                Ok(frame) => self.received.push_back(frame),
+                Err(err) => error = Some(err),
+            }
+        }
+        match error {
+            Some(err) if self.received.is_empty() => {
+                Err(io::Error::new(io::ErrorKind::InvalidData, err))
+            }
+            _ => Ok(self.received.pop_front()),
+        }
+    }
+}
+
+impl Transport for GridConnectClient {
+    fn send(&mut self, frame: &CanFrame) -> io::Result<()> {
+        GridConnectClient::send(self, frame)
+    }
+
+    fn receive(&mut self, timeout: Duration) -> io::Result<Option<CanFrame>> {
+        if let Some(frame) = self.received.pop_front() {
+            return Ok(Some(frame));
+        }
+
+        // a zero timeout is rejected by the socket
+        self.stream
+            .set_read_timeout(Some(timeout.max(Duration::from_millis(1))))?;
+        match self.read() {
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                Ok(None)
+            }
+            result => result,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Connection {
+    id: usize,
+    // serializes writes to the client, so frames of different threads do not interleave
+    stream: Arc<Mutex<TcpStream>>,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    connections: Mutex<Vec<Connection>>,
+    next_id: AtomicUsize,
+    shutdown: AtomicBool,
+}
+
+impl Shared {
+    fn connections(&self) -> MutexGuard<'_, Vec<Connection>> {
+        // a panicking client thread does not leave the list inconsistent
+        self.connections
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Send GridConnect text to every client except `except`, dropping failed clients
+    ///
+    /// The clients are written outside the connection list lock, a client which does
+    /// not read is dropped after [`WRITE_TIMEOUT`] without stalling the others.
+    fn fan_out(&self, text: &[u8], except: Option<usize>) {
+        let targets: Vec<_> = self
+            .connections()
+            .iter()
+            .filter(|conn| Some(conn.id) != except)
+            .map(|conn| (conn.id, Arc::clone(&conn.stream)))
+            .collect();
+
+        let mut failed = Vec::new();
+        for (id, stream) in targets {
+            let mut stream = stream
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            if stream.write_all(text).is_err() {
+                // the client thread notices the shutdown and stops
+                let _ = stream.shutdown(Shutdown::Both);
+                failed.push(id);
+            }
+        }
+        if !failed.is_empty() {
+            self.connections().retain(|conn| !failed.contains(&conn.id));
+        }
+    }
+
+    fn remove(&self, id: usize) {
+        self.connections().retain(|conn| conn.id != id);
+    }
+}
+
+/// Multi-client GridConnect TCP server
+///
+/// Every frame sent by a client is forwarded to all other clients and queued for
+/// the bridged interface, see [`GridConnectServer::receive`]. At most
+/// [`FRAME_QUEUE_CAPACITY`] frames are queued, newer frames are dropped until the
+/// queue is read again. Frames of the bridged
+/// interface are sent to all clients with [`GridConnectServer::broadcast`], or both
+/// directions are pumped with [`GridConnectServer::bridge`].
+///
+/// Clients are served by background threads, which stop when the server is dropped.
+#[derive(Debug)]
+pub struct GridConnectServer {
+    local_addr: SocketAddr,
+    shared: Arc<Shared>,
+    frames: Receiver<CanFrame>,
+}
+
+impl GridConnectServer {
+    /// Start a server listening on `addr`
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let local_addr = listener.local_addr()?;
+        let shared = Arc::new(Shared::default());
+        let (sender, frames) = mpsc::sync_channel(FRAME_QUEUE_CAPACITY);
+
+        let accept_shared = Arc::clone(&shared);
+        thread::Builder::new()
+            .name("gridconnect-accept".into())
+            .spawn(move || accept(listener, accept_shared, sender))?;
+
+        Ok(Self {
+            local_addr,
+            shared,
+            frames,
+        })
+    }
+
+    /// Return the address the server listens on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Return the number of connected clients
+    pub fn client_count(&self) -> usize {
+        self.shared.connections().len()
+    }
+
+    /// Send a frame of the bridged interface to all clients
+    pub fn broadcast(&self, frame: &CanFrame) {
+        self.shared
+            .fan_out(GridConnectCodec::encode(frame).as_bytes(), None);
+    }
+
+    /// Wait up to `timeout` for a frame sent by a client
+    pub fn receive(&self, timeout: Duration) -> Option<CanFrame> {
+        self.frames.recv_timeout(timeout).ok()
+    }
+
+    /// Exchange frames between the clients and a transport until an error occurs
+    ///
+    /// Frames of the clients are sent to the transport, frames received by the
+    /// transport are sent to all clients. `poll` is the longest time spent waiting
+    /// on the transport before checking the clients again.
+    pub fn bridge<T: Transport>(&self, mut transport: T, poll: Duration) -> io::Result<()> {
+        loop {
+            while let Ok(frame) = self.frames.try_recv() {
+                transport.send(&frame)?;
+            }
+            if let Some(frame) = transport.receive(poll)? {
+                self.broadcast(&frame);
+            }
+        }
+    }
+}
+
+impl Drop for GridConnectServer {
+    fn drop(&mut self) {
+        self.shared.shutdown.store(true, Ordering::SeqCst);
+        // wake up the accept thread
+        let _ = TcpStream::connect(self.local_addr);
+        for conn in self.shared.connections().drain(..) {
+            let stream = conn
+                .stream
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+fn accept(listener: TcpListener, shared: Arc<Shared>, sender: SyncSender<CanFrame>) {
+    for stream in listener.incoming() {
+        if shared.shutdown.load(Ordering::SeqCst) {
+            return;
+        }
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let Ok(writer) = stream.try_clone() else {
+            continue;
+        };
+        if writer.set_write_timeout(Some(WRITE_TIMEOUT)).is_err() {
+            continue;
+        }
+
+        let id = shared.next_id.fetch_add(1, Ordering::Relaxed);
+        shared.connections().push(Connection {
+            id,
+            stream: Arc::new(Mutex::new(writer)),
+        });
+
+        let client_shared = Arc::clone(&shared);
+        let sender = sender.clone();
+        let spawned = thread::Builder::new()
+            .name("gridconnect-client".into())
+            .spawn(move || serve(id, stream, client_shared, sender));
+        if spawned.is_err() {
+            shared.remove(id);
+        }
+    }
+}
+
+fn serve(id: usize, mut stream: TcpStream, shared: Arc<Shared>, sender: SyncSender<CanFrame>) {
+    let mut codec = GridConnectCodec::new();
+    let mut buf = [0; READ_BUFFER_SIZE];
+    while let Ok(len @ 1..) = stream.read(&mut buf) {
+        // malformed frames are dropped, they would be rejected by the other side anyway
+        for frame in codec.decode(&buf[..len]).flatten() {
+            shared.fan_out(GridConnectCodec::encode(&frame).as_bytes(), Some(id));
+            // a full queue drops the frame rather than stalling the client
+            let _ = sender.try_send(frame);
+        }
+    }
+    shared.remove(id);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::can::{CanHeader, VlcbCanId};
+    use crate::frame::FrameId;
+    use crate::OpCode;
+    use std::time::Instant;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn frame(data: &[u8]) -> CanFrame {
+        let header = CanHeader::for_opcode(VlcbCanId([0x05]), OpCode::BusHalt);
+        CanFrame::new(FrameId::Standard(header), data).unwrap()
+    }
+
+    fn wait_for_clients(server: &GridConnectServer, count: usize) {
+        let start = Instant::now();
+        while server.client_count() != count {
+            assert!(start.elapsed() < TIMEOUT, "clients did not connect");
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_fan_out() {
+        let server = GridConnectServer::bind("127.0.0.1:0").unwrap();
+        let mut a = GridConnectClient::connect(server.local_addr()).unwrap();
+        let mut b = GridConnectClient::connect(server.local_addr()).unwrap();
+        wait_for_clients(&server, 2);
+
+        a.send(&frame(&[0x02])).unwrap();
+        assert_eq!(b.receive_blocking().unwrap(), frame(&[0x02]));
+        assert_eq!(server.receive(TIMEOUT), Some(frame(&[0x02])));
+        assert_eq!(a.receive(Duration::from_millis(50)).unwrap(), None);
+
+        server.broadcast(&frame(&[0x04]));
+        assert_eq!(a.receive(TIMEOUT).unwrap(), Some(frame(&[0x04])));
+        assert_eq!(b.receive(TIMEOUT).unwrap(), Some(frame(&[0x04])));
+
+        drop(b);
+        wait_for_clients(&server, 1);
+        drop(server);
+        assert_eq!(
+            a.receive_blocking().unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn test_queue_full() {
+        let server = GridConnectServer::bind("127.0.0.1:0").unwrap();
+        let mut a = GridConnectClient::connect(server.local_addr()).unwrap();
+        let mut b = GridConnectClient::connect(server.local_addr()).unwrap();
+        wait_for_clients(&server, 2);
+
+        let count = FRAME_QUEUE_CAPACITY + 10;
+        for i in 0..count {
+            a.send(&frame(&[i as u8])).unwrap();
+        }
+        // frames are forwarded to the other clients before being queued
+        for i in 0..count {
+            assert_eq!(b.receive(TIMEOUT).unwrap(), Some(frame(&[i as u8])));
+        }
+
+        for i in 0..FRAME_QUEUE_CAPACITY {
+            assert_eq!(server.receive(TIMEOUT), Some(frame(&[i as u8])));
+        }
+        assert_eq!(server.receive(Duration::from_millis(50)), None);
+    }
+
+    #[test]
+    fn test_stalled_client() {
+        let server = GridConnectServer::bind("127.0.0.1:0").unwrap();
+        let stalled = TcpStream::connect(server.local_addr()).unwrap();
+        let mut reader = GridConnectClient::connect(server.local_addr()).unwrap();
+        wait_for_clients(&server, 2);
+
+        let done = Arc::new(AtomicBool::new(false));
+        let reader_done = Arc::clone(&done);
+        let reader = thread::spawn(move || {
+            while !reader_done.load(Ordering::SeqCst) {
+                reader.receive(Duration::from_millis(10)).unwrap();
+            }
+            reader
+        });
+
+        // fill the socket buffers of the client which does not read
+        let start = Instant::now();
+        while server.client_count() != 1 {
+            assert!(
+                start.elapsed() < 4 * TIMEOUT,
+                "stalled client was not dropped"
+            );
+            server.broadcast(&frame(&[0x01; 8]));
+        }
+        done.store(true, Ordering::SeqCst);
+        let mut reader = reader.join().unwrap();
+
+        server.broadcast(&frame(&[0x04]));
+        while let Some(received) = reader.receive(TIMEOUT).unwrap() {
+            if received == frame(&[0x04]) {
+                break;
+            }
+        }
+        drop(stalled);
+    }
+
+    #[test]
+    fn test_malformed() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = GridConnectClient::connect(listener.local_addr().unwrap()).unwrap();
+        let (mut peer, _) = listener.accept().unwrap();
+
+        peer.write_all(b":SB020N9;").unwrap();
+        assert_eq!(
+            client.receive_blocking().unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        peer.write_all(b"\r\n:SA0A0N02;").unwrap();
+        assert!(client.receive_blocking().is_ok());
+    }
+
+    #[derive(Default)]
+    struct Loopback {
+        sent: Vec<CanFrame>,
+        incoming: VecDeque<CanFrame>,
+    }
+
+    impl Transport for Loopback {
+        fn send(&mut self, frame: &CanFrame) -> io::Result<()> {
+            self.sent.push(*frame);
+            if frame.data() == [0x00] {
+                return Err(io::ErrorKind::BrokenPipe.into());
+            }
+            Ok(())
+        }
+
+        fn receive(&mut self, _timeout: Duration) -> io::Result<Option<CanFrame>> {
+            Ok(self.incoming.pop_front())
+        }
+    }
+
+    #[test]
+    fn test_bridge() {
+        let server = GridConnectServer::bind("127.0.0.1:0").unwrap();
+        let mut client = GridConnectClient::connect(server.local_addr()).unwrap();
+        wait_for_clients(&server, 1);
+
+        let mut transport = Loopback::default();
+        transport.incoming.push_back(frame(&[0x04]));
+        client.send(&frame(&[0x02])).unwrap();
+        client.send(&frame(&[0x00])).unwrap();
+
+        let result = server.bridge(&mut transport, Duration::from_millis(10));
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::BrokenPipe);
+        assert_eq!(transport.sent, [frame(&[0x02]), frame(&[0x00])]);
+        assert_eq!(client.receive(TIMEOUT).unwrap(), Some(frame(&[0x04])));
+    }
+}
@@ -0,0 +1,27 @@
+use std::io;
+use std::time::Duration;
+
+use crate::frame::CanFrame;
+
+/// A link carrying CAN frames, such as a CAN interface or a GridConnect connection
+///
+/// Used to connect the bridges and tools of the library to any interface.
+pub trait Transport {
+    /// Send a frame
+    fn send(&mut self, frame: &CanFrame) -> io::Result<()>;
+
+    /// Wait up to `timeout` for a frame
+    ///
+    /// Returns `None` if no frame has been received in time.
+    fn receive(&mut self, timeout: Duration) -> io::Result<Option<CanFrame>>;
+}
+
+impl<T: Transport + ?Sized> Transport for &mut T {
+    fn send(&mut self, frame: &CanFrame) -> io::Result<()> {
+        (**self).send(frame)
+    }
+
+    fn receive(&mut self, timeout: Duration) -> io::Result<Option<CanFrame>> {
+        (**self).receive(timeout)
+    }
+}
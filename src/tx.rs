@@ -0,0 +1,423 @@
+use heapless::Vec;
+
+use crate::builder::Message;
+use crate::can::{CanHeader, MajorPriority, MinorPriority};
+use crate::clock::Clock;
+use crate::error::VlcbError;
+use crate::frame::VlcbFrame;
+use crate::message_ref::MessageRef;
+use crate::OpCode;
+
+/// Number of lost arbitrations after which the major priority of a frame is raised
+pub const DEFAULT_ESCALATION_LIMIT: u8 = 16;
+
+/// Time in milliseconds after which a [`BusGate`] resumes transmission without a BON
+pub const DEFAULT_HALT_TIMEOUT_MS: u64 = 5_000;
+
+/// An owned standard frame waiting to be sent
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TxFrame {
+    /// Data frame carrying a message
+    Message { header: CanHeader, message: Message },
+
+    /// Data frame without data, answering a CAN ID enumeration
+    Empty { header: CanHeader },
+
+    /// Remote (RTR) frame, starting a CAN ID enumeration
+    Remote { header: CanHeader },
+}
+
+impl TxFrame {
+    /// Return the header of the frame
+    pub fn header(&self) -> CanHeader {
+        match *self {
+            Self::Message { header, .. } | Self::Empty { header } | Self::Remote { header } => {
+                header
+            }
+        }
+    }
+
+    /// Return a mutable reference to the header of the frame
+    pub fn header_mut(&mut self) -> &mut CanHeader {
+        match self {
+            Self::Message { header, .. } | Self::Empty { header } | Self::Remote { header } => {
+                header
+            }
+        }
+    }
+
+    /// Borrow the frame as a [`VlcbFrame`]
+    pub fn as_frame(&self) -> VlcbFrame<'_> {
+        match *self {
+            Self::Message {
+                header,
+                ref message,
+            } => VlcbFrame::Message {
+                header,
+                message: message.into(),
+            },
+            Self::Empty { header } => VlcbFrame::Empty { header },
+            Self::Remote { header } => VlcbFrame::Remote { header },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    frame: TxFrame,
+    seq: u64,
+    lost: u8,
+}
+
+impl Entry {
+    /// Sort key, the lowest one is sent first like in CAN arbitration
+    fn key(&self) -> (MajorPriority, MinorPriority, u64) {
+        let header = self.frame.header();
+        (header.major_priority, header.minor_priority, self.seq)
+    }
+}
+
+/// Fixed-capacity transmit queue
+///
+/// Frames are sent in the order of their [`CanHeader`] priorities, major priority
+/// first, and in the order they were queued within the same priority. The CAN ID
+/// of the header does not take part in the ordering.
+///
+/// A frame that keeps losing arbitration has its [`MajorPriority`] raised one step
+/// each time it loses `escalation_limit` times, see [`TxQueue::lost_arbitration`].
+#[derive(Debug, Clone)]
+pub struct TxQueue<const N: usize> {
+    entries: Vec<Entry, N>,
+    seq: u64,
+    escalation_limit: u8,
+}
+
+impl<const N: usize> TxQueue<N> {
+    /// Construct an empty queue escalating after [`DEFAULT_ESCALATION_LIMIT`] lost arbitrations
+    pub const fn new() -> Self {
+        Self::with_escalation_limit(DEFAULT_ESCALATION_LIMIT)
+    }
+
+    /// Construct an empty queue escalating after `limit` lost arbitrations
+    ///
+    /// A limit of 0 disables the escalation.
+    pub const fn with_escalation_limit(limit: u8) -> Self {
+        Self {
+            entries: Vec::new(),
+            seq: 0,
+            escalation_limit: limit,
+        }
+    }
+
+    /// Queue a frame
+    ///
+    /// Returns [`VlcbError::CapacityExceeded`] if `N` frames are already queued.
+    pub fn push(&mut self, frame: TxFrame) -> Result<(), VlcbError> {
+        let entry = Entry {
+            frame,
+            seq: self.seq,
+            lost: 0,
+        };
+        self.entries
+            .push(entry)
+            .map_err(|_| VlcbError::CapacityExceeded)?;
+        self.seq += 1;
+        Ok(())
+    }
+
+    /// Return the frame to send next without removing it
+    pub fn peek(&self) -> Option<&TxFrame> {
+        self.next_index().map(|index| &self.entries[index].frame)
+    }
+
+    /// Remove and return the frame to send next
+    pub fn pop(&mut self) -> Option<TxFrame> {
+        let index = self.next_index()?;
+        Some(self.entries.swap_remove(index).frame)
+    }
+
+    /// Record that the frame returned by [`TxQueue::peek`] lost arbitration
+    ///
+    /// Returns the new major priority of the frame if it was raised.
+    pub fn lost_arbitration(&mut self) -> Option<MajorPriority> {
+        let limit = self.escalation_limit;
+        let index = self.next_index()?;
+        let entry = &mut self.entries[index];
+        if limit == 0 {
+            return None;
+        }
+
+        entry.lost = entry.lost.saturating_add(1);
+        if entry.lost < limit {
+            return None;
+        }
+
+        entry.lost = 0;
+        let header = entry.frame.header_mut();
+        let raised = match header.major_priority {
+            MajorPriority::Low => MajorPriority::Normal,
+            MajorPriority::Normal => MajorPriority::High,
+            MajorPriority::High | MajorPriority::Highest => MajorPriority::Highest,
+        };
+        if raised == header.major_priority {
+            return None;
+        }
+        header.major_priority = raised;
+        Some(raised)
+    }
+
+    /// Return the number of queued frames
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check whether there are no queued frames
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Check whether the queue is full
+    pub fn is_full(&self) -> bool {
+        self.entries.is_full()
+    }
+
+    /// Drop all queued frames
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn next_index(&self) -> Option<usize> {
+        self.entries
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, entry)| entry.key())
+            .map(|(index, _)| index)
+    }
+}
+
+impl<const N: usize> Default for TxQueue<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Transmission flow control following [`OpCode::BusHalt`] (HLT)
+///
+/// After a HLT the node must not transmit until [`OpCode::BusResume`] (BON) or
+/// [`OpCode::RestartAllNodes`] (ARST) is received. Frames sent in the meantime
+/// are held in a [`TxQueue`] and released by [`BusGate::poll`] once transmission
+/// is allowed again.
+///
+/// So that a lost BON does not silence the node forever, the halt ends on its
+/// own after a fail-safe timeout. A timeout of `None` disables the fail-safe.
+#[derive(Debug, Clone)]
+pub struct BusGate<const N: usize> {
+    queue: TxQueue<N>,
+    timeout_ms: Option<u64>,
+    halted: Option<u64>,
+}
+
+impl<const N: usize> BusGate<N> {
+    /// Construct an open gate with the given fail-safe timeout in milliseconds
+    pub const fn new(timeout_ms: Option<u64>) -> Self {
+        Self {
+            queue: TxQueue::new(),
+            timeout_ms,
+            halted: None,
+        }
+    }
+
+    /// Process a received message
+    ///
+    /// Returns `true` if the message changed the state of the gate.
+    pub fn on_message(&mut self, clock: &impl Clock, message: MessageRef<'_>) -> bool {
+        match message.opcode() {
+            OpCode::BusHalt => {
+                let deadline = match self.timeout_ms {
+                    Some(timeout_ms) => clock.now_ms().saturating_add(timeout_ms),
+                    None => u64::MAX,
+                };
+                self.halted = Some(deadline);
+                true
+            }
+            OpCode::BusResume | OpCode::RestartAllNodes => self.halted.take().is_some(),
+            _ => false,
+        }
+    }
+
+    /// Check whether the node may transmit
+    pub fn can_transmit(&self, clock: &impl Clock) -> bool {
+        match self.halted {
+            Some(deadline) => clock.now_ms() >= deadline,
+            None => true,
+        }
+    }
+
+    /// Check whether the bus is halted
+    ///
+    /// Unlike [`BusGate::can_transmit`], an expired halt is reported until the
+    /// next call to [`BusGate::poll`].
+    pub fn is_halted(&self) -> bool {
+        self.halted.is_some()
+    }
+
+    /// Queue a frame for transmission
+    ///
+    /// Returns [`VlcbError::CapacityExceeded`] if `N` frames are already held.
+    pub fn send(&mut self, frame: TxFrame) -> Result<(), VlcbError> {
+        self.queue.push(frame)
+    }
+
+    /// Return the next frame to transmit, if transmission is allowed
+    pub fn poll(&mut self, clock: &impl Clock) -> Option<TxFrame> {
+        if !self.can_transmit(clock) {
+            return None;
+        }
+        self.halted = None;
+        self.queue.pop()
+    }
+
+    /// Return the queue of the held frames
+    pub fn queue(&self) -> &TxQueue<N> {
+        &self.queue
+    }
+
+    /// Return the queue of the held frames, e.g. to report lost arbitration
+    pub fn queue_mut(&mut self) -> &mut TxQueue<N> {
+        &mut self.queue
+    }
+}
+
+impl<const N: usize> Default for BusGate<N> {
+    fn default() -> Self {
+        Self::new(Some(DEFAULT_HALT_TIMEOUT_MS))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::can::VlcbCanId;
+    use crate::vlcb::VlcbNodeNumber;
+    use crate::OpCode;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::cell::Cell;
+
+    fn frame(message: Message) -> TxFrame {
+        TxFrame::Message {
+            header: CanHeader::for_opcode(VlcbCanId([5]), message.opcode()),
+            message,
+        }
+    }
+
+    #[test]
+    fn test_order() {
+        let nn = VlcbNodeNumber::new(0, 1);
+        let mut queue = TxQueue::<4>::new();
+        queue.push(frame(Message::paran(nn, 1, 2))).unwrap();
+        queue.push(frame(Message::paran(nn, 2, 3))).unwrap();
+        queue.push(frame(Message::hlt())).unwrap();
+        queue
+            .push(TxFrame::Remote {
+                header: CanHeader::default(),
+            })
+            .unwrap();
+        assert!(queue.is_full());
+        assert_eq!(
+            queue.push(frame(Message::ack())),
+            Err(VlcbError::CapacityExceeded)
+        );
+
+        let sent: Vec<_> = core::iter::from_fn(|| queue.pop())
+            .map(|frame| frame.as_frame().data().to_vec())
+            .collect();
+        assert_eq!(
+            sent,
+            [
+                vec![0x02],
+                vec![],
+                vec![0x9B, 0, 1, 1, 2],
+                vec![0x9B, 0, 1, 2, 3]
+            ]
+        );
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_escalation() {
+        let mut queue = TxQueue::<2>::with_escalation_limit(2);
+        let header = CanHeader::for_opcode(VlcbCanId([5]), OpCode::BusHalt);
+        queue.push(TxFrame::Empty { header }).unwrap();
+
+        assert_eq!(queue.lost_arbitration(), None);
+        assert_eq!(queue.lost_arbitration(), Some(MajorPriority::High));
+        assert_eq!(queue.lost_arbitration(), None);
+        assert_eq!(queue.lost_arbitration(), Some(MajorPriority::Highest));
+        assert_eq!(queue.lost_arbitration(), None);
+        assert_eq!(queue.lost_arbitration(), None);
+
+        let frame = queue.pop().unwrap();
+        assert_eq!(frame.header().major_priority, MajorPriority::Highest);
+        assert_eq!(frame.header().can_id, VlcbCanId([5]));
+        assert_eq!(queue.lost_arbitration(), None);
+
+        let mut queue = TxQueue::<1>::with_escalation_limit(0);
+        queue.push(TxFrame::Empty { header }).unwrap();
+        for _ in 0..100 {
+            assert_eq!(queue.lost_arbitration(), None);
+        }
+    }
+
+    #[test]
+    fn test_bus_gate() {
+        let now = Cell::new(0u64);
+        let clock = || now.get();
+        let mut gate = BusGate::<4>::new(Some(1000));
+
+        gate.send(frame(Message::ack())).unwrap();
+        assert!(gate.can_transmit(&clock));
+        assert!(gate.poll(&clock).is_some());
+
+        let hlt = Message::hlt();
+        assert!(gate.on_message(&clock, (&hlt).into()));
+        assert!(!gate.can_transmit(&clock));
+        gate.send(frame(Message::ack())).unwrap();
+        gate.send(frame(Message::nak())).unwrap();
+        assert_eq!(gate.poll(&clock), None);
+        assert_eq!(gate.queue().len(), 2);
+
+        let bon = Message::bon();
+        assert!(gate.on_message(&clock, (&bon).into()));
+        assert!(!gate.on_message(&clock, (&bon).into()));
+        assert_eq!(gate.poll(&clock), Some(frame(Message::ack())));
+
+        let arst = Message::arst();
+        gate.on_message(&clock, (&hlt).into());
+        assert!(gate.on_message(&clock, (&arst).into()));
+        assert!(gate.can_transmit(&clock));
+
+        // fail-safe
+        gate.on_message(&clock, (&hlt).into());
+        now.set(999);
+        assert_eq!(gate.poll(&clock), None);
+        now.set(1000);
+        assert!(gate.is_halted());
+        assert_eq!(gate.poll(&clock), Some(frame(Message::nak())));
+        assert!(!gate.is_halted());
+
+        let mut gate = BusGate::<1>::new(None);
+        gate.on_message(&clock, (&hlt).into());
+        now.set(u64::MAX - 1);
+        assert!(!gate.can_transmit(&clock));
+    }
+
+    #[test]
+    fn test_as_frame() {
+        let frame = frame(Message::hlt());
+        let borrowed = frame.as_frame();
+        assert_eq!(borrowed.header(), Some(frame.header()));
+        assert_eq!(borrowed.data(), &[0x02]);
+    }
+}
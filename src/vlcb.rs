@@ -1,9 +1,14 @@
+use crate::error::{check_len, VlcbError};
 use crate::ServiceType;
 use byteorder::{ByteOrder, NetworkEndian};
+use core::fmt;
+use core::ops::RangeInclusive;
+use core::str::FromStr;
 
 pub trait VlcbService {
     /// Runs the service initialization
     #[must_use]
+    #[allow(clippy::must_use_unit)]
     fn init() {}
 
     /// Returns the service ID
@@ -24,8 +29,38 @@ pub trait VlcbService {
 /// Size of an CBUS node number in octets.
 pub const NODENUM_SIZE: usize = 2;
 
+/// Node numbers of modules in SLiM mode, set by switches or by default.
+///
+/// Not allocated to FLiM nodes, as set out in the node number allocation of the
+/// MERG CBUS Developer's Guide.
+pub const SLIM_NODENUM_RANGE: RangeInclusive<u16> = 1..=99;
+
+/// Node numbers reserved for command stations and other fixed nodes.
+///
+/// Used as the default node numbers of command stations and cabs, e.g. 65534 of the
+/// CANCMD, as set out in the node number allocation of the MERG CBUS Developer's Guide.
+pub const RESERVED_NODENUM_RANGE: RangeInclusive<u16> = 0xFFF0..=0xFFFF;
+
+/// Class of a CBUS node number
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum NodeNumberClass {
+    /// Node number 0, of an unset node or of a short event
+    Zero,
+
+    /// Node number in [`SLIM_NODENUM_RANGE`]
+    Slim,
+
+    /// Node number in [`RESERVED_NODENUM_RANGE`]
+    Reserved,
+
+    /// Node number which can be allocated to a node in FLiM mode
+    Allocated,
+}
+
 /// A two-octet CBUS node number.
 #[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct VlcbNodeNumber(pub [u8; NODENUM_SIZE]);
 
 impl VlcbNodeNumber {
@@ -34,20 +69,61 @@ impl VlcbNodeNumber {
         Self([a0, a1])
     }
 
+    /// Construct an CBUS node number from its value.
+    pub const fn from_u16(nn: u16) -> Self {
+        Self(nn.to_be_bytes())
+    }
+
+    /// Construct a node number which can be allocated to a node in FLiM mode.
+    ///
+    /// Returns [`VlcbError::ReservedValue`] with the low octet of `nn` if `nn` is not
+    /// of the [`NodeNumberClass::Allocated`] class.
+    pub const fn new_allocated(nn: u16) -> Result<Self, VlcbError> {
+        let nn = Self::from_u16(nn);
+        match nn.class() {
+            NodeNumberClass::Allocated => Ok(nn),
+            _ => Err(VlcbError::ReservedValue {
+                type_name: "VlcbNodeNumber",
+                value: nn.0[1],
+            }),
+        }
+    }
+
     /// Construct an CBUS node number from a sequence of octets, in big-endian.
     ///
-    /// # Panics
-    /// The function panics if `data` is not two octets long.
-    pub fn from_bytes(data: &[u8]) -> Self {
-        let mut bytes = [0; NODENUM_SIZE];
-        bytes.copy_from_slice(data);
-        Self(bytes)
+    /// Returns [`VlcbError::WrongLength`] if `data` is not two octets long.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, VlcbError> {
+        check_len(data, NODENUM_SIZE)?;
+        Ok(Self([data[0], data[1]]))
     }
 
     /// Return an CBUS node number as a sequence of octets, in big-endian.
     pub const fn as_bytes(&self) -> &[u8] {
         &self.0
     }
+
+    /// Return the value of an CBUS node number.
+    pub const fn to_u16(self) -> u16 {
+        u16::from_be_bytes(self.0)
+    }
+
+    /// Return the class of an CBUS node number.
+    pub const fn class(self) -> NodeNumberClass {
+        match self.to_u16() {
+            0 => NodeNumberClass::Zero,
+            nn if nn <= *SLIM_NODENUM_RANGE.end() => NodeNumberClass::Slim,
+            nn if nn >= *RESERVED_NODENUM_RANGE.start() => NodeNumberClass::Reserved,
+            _ => NodeNumberClass::Allocated,
+        }
+    }
+
+    /// Check whether the node number is 0.
+    ///
+    /// Node number 0 is not allocated to any node. An event taught with node number 0
+    /// is a short event, see [`OpCode::TeachEvent`](crate::OpCode::TeachEvent).
+    pub const fn is_zero(self) -> bool {
+        self.0[0] == 0 && self.0[1] == 0
+    }
 }
 
 impl Default for VlcbNodeNumber {
@@ -56,6 +132,55 @@ impl Default for VlcbNodeNumber {
     }
 }
 
+impl From<u16> for VlcbNodeNumber {
+    fn from(nn: u16) -> Self {
+        Self::from_u16(nn)
+    }
+}
+
+impl From<VlcbNodeNumber> for u16 {
+    fn from(nn: VlcbNodeNumber) -> Self {
+        nn.to_u16()
+    }
+}
+
+impl TryFrom<&[u8]> for VlcbNodeNumber {
+    type Error = VlcbError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_bytes(data)
+    }
+}
+
+/// Error returned when parsing an invalid node number
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ParseNodeNumberError;
+
+impl fmt::Display for ParseNodeNumberError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid node number")
+    }
+}
+
+impl FromStr for VlcbNodeNumber {
+    type Err = ParseNodeNumberError;
+
+    /// Parse a node number from its decimal value (e.g. `256`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<u16>()
+            .map(Self::from_u16)
+            .map_err(|_| ParseNodeNumberError)
+    }
+}
+
+impl fmt::Display for VlcbNodeNumber {
+    /// Write the decimal value of the node number
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_u16())
+    }
+}
+
 /// Size of an CBUS P / C event in octets.
 pub const EVENT_SIZE: usize = 4;
 
@@ -79,10 +204,18 @@ pub enum EventType {
     /// Accessory event state response "OFF"
     /// used for responding to accessory state queries
     /// without producing event state change events.
-    AccessoryStatusOff
+    AccessoryStatusOff,
+
+    /// Accessory event state request
+    /// answered with [`EventType::AccessoryStatusOn`] or [`EventType::AccessoryStatusOff`].
+    AccessoryStatusRequest
 }
 
 /// A four-octet CBUS P / C event.
+///
+/// The event always keeps all four octets as they were sent, including the node number
+/// of the producer of a short event. Short events are matched on the event number only,
+/// so event stores compare events by their [`EventKey`] rather than with `==`.
 #[derive(Debug, Hash, Eq, PartialEq, PartialOrd, Ord, Clone, Copy, Default)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct EventId {
@@ -90,9 +223,6 @@ pub struct EventId {
     is_short: bool,
 }
 
-// TODO: drop is_short - we don't need this because actually we will send them anyway, it's just that OPCODE will specify that the consumer will ignore it
-// Though we will need another or modified funcitonality of this to make it work properly -> event store should ignore the node number, etc
-// it would be great if we could retain the sanitization where needed but allow the full 4 bytes to be passed around in the stack!
 impl EventId {
     /// Construct an CBUS P / C event from parts.
     pub const fn new(short: bool, a0: u8, a1: u8, a2: u8, a3: u8) -> Self {
@@ -102,44 +232,47 @@ impl EventId {
         }
     }
 
-    /// Construct a long CBUS P / C event from a sequence of octets, in big-endian.
-    ///
-    /// # Panics
-    /// The function panics if `data` is not four octets long.
-    pub fn from_bytes(data: &[u8]) -> Self {
-        let mut bytes = [0; EVENT_SIZE];
-        bytes.copy_from_slice(data);
+    /// Construct a long CBUS P / C event from four octets, in big-endian.
+    pub const fn from_array(data: [u8; EVENT_SIZE]) -> Self {
         Self {
-            data: bytes,
+            data,
             is_short: false
         }
     }
 
-    /// Construct a short CBUS P / C event from a sequence of octets, in big-endian.
-    ///
-    /// Short event is essentially the same size af "long" events, but node number is ignored.
-    /// The data is still 4 octets long, but with the node number part null-ed.
+    /// Construct a short CBUS P / C event from four octets, in big-endian.
     ///
-    /// # Panics
-    /// The function panics if `data` is not two octets long.
-    pub fn short_from_bytes(data: &[u8]) -> Self {
-        let mut bytes = [0; EVENT_SIZE];
-        bytes[2..].copy_from_slice(&data[2..]);
+    /// The node number part is kept, it is the node number of the producer of the event.
+    /// It is ignored when matching the event, see [`EventMatch::Short`].
+    pub const fn short_from_array(data: [u8; EVENT_SIZE]) -> Self {
         Self {
-            data: bytes,
+            data,
             is_short: true
         }
     }
 
+    /// Construct a long CBUS P / C event from a sequence of octets, in big-endian.
+    ///
+    /// Returns [`VlcbError::WrongLength`] if `data` is not four octets long.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, VlcbError> {
+        check_len(data, EVENT_SIZE)?;
+        Ok(Self::from_array([data[0], data[1], data[2], data[3]]))
+    }
+
+    /// Construct a short CBUS P / C event from a sequence of octets, in big-endian.
+    ///
+    /// See [`EventId::short_from_array`].
+    ///
+    /// Returns [`VlcbError::WrongLength`] if `data` is not four octets long.
+    pub fn short_from_bytes(data: &[u8]) -> Result<Self, VlcbError> {
+        check_len(data, EVENT_SIZE)?;
+        Ok(Self::short_from_array([data[0], data[1], data[2], data[3]]))
+    }
+
     /// Construct an CBUS P / C event from a node number and event id.
-    pub fn from_node_and_id(node_num: &VlcbNodeNumber, evt_id: u16, short: bool) -> Self {
-        let mut bytes = [0; EVENT_SIZE];
-        bytes.copy_from_slice(node_num.as_bytes());
-        NetworkEndian::write_u16(&mut bytes[2..], evt_id);
-        Self {
-            data: bytes,
-            is_short: short,
-        }
+    pub const fn from_node_and_id(node_num: &VlcbNodeNumber, evt_id: u16, short: bool) -> Self {
+        let [a2, a3] = evt_id.to_be_bytes();
+        Self::new(short, node_num.0[0], node_num.0[1], a2, a3)
     }
 
     /// Return an CBUS P / C event as a sequence of octets, in big-endian.
@@ -148,8 +281,10 @@ impl EventId {
     }
 
     /// Return a CBUS node number
+    ///
+    /// For a short event, this is the node number of the producer.
     pub fn node_num(&self) -> VlcbNodeNumber {
-        VlcbNodeNumber::from_bytes(&self.data[0..2])
+        VlcbNodeNumber::new(self.data[0], self.data[1])
     }
 
     /// Return a CBUS event number
@@ -166,4 +301,140 @@ impl EventId {
     pub fn is_long(&self) -> bool {
         !self.is_short
     }
+
+    /// Return the policy used to match the event
+    pub const fn match_policy(&self) -> EventMatch {
+        if self.is_short {
+            EventMatch::Short
+        } else {
+            EventMatch::Long
+        }
+    }
+
+    /// Return the key of the event, used to match it against other events
+    pub const fn key(&self) -> EventKey {
+        EventKey::new(self, self.match_policy())
+    }
+
+    /// Check whether two events match, according to the policy of this event
+    pub fn matches(&self, other: &EventId) -> bool {
+        self.key() == EventKey::new(other, self.match_policy())
+    }
+}
+
+/// Policy used to match events
+#[derive(Debug, Hash, Eq, PartialEq, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum EventMatch {
+    /// Match the node number and the event number
+    Long,
+
+    /// Match the event number only, the node number is ignored
+    Short,
+}
+
+/// Key of an event under a match policy
+///
+/// Two keys are equal, and hash the same, if the events match under the policy.
+/// A short key never equals a long key.
+#[derive(Debug, Hash, Eq, PartialEq, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EventKey {
+    data: [u8; EVENT_SIZE],
+    policy: EventMatch,
+}
+
+impl EventKey {
+    /// Construct the key of an event under a match policy
+    pub const fn new(event: &EventId, policy: EventMatch) -> Self {
+        let data = match policy {
+            EventMatch::Long => event.data,
+            EventMatch::Short => [0, 0, event.data[2], event.data[3]],
+        };
+        Self { data, policy }
+    }
+
+    /// Return the match policy of the key
+    pub const fn policy(&self) -> EventMatch {
+        self.policy
+    }
+
+    /// Return the octets of the event matched by the key
+    ///
+    /// The node number part of a short key is 0.
+    pub const fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl From<EventId> for EventKey {
+    fn from(event: EventId) -> Self {
+        event.key()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_node_number() {
+        let nn = VlcbNodeNumber::from(0x0102);
+        assert_eq!(nn, VlcbNodeNumber::new(1, 2));
+        assert_eq!(u16::from(nn), 258);
+        assert_eq!(nn.to_string(), "258");
+        assert_eq!("258".parse(), Ok(nn));
+        assert_eq!("65536".parse::<VlcbNodeNumber>(), Err(ParseNodeNumberError));
+        assert_eq!("0x102".parse::<VlcbNodeNumber>(), Err(ParseNodeNumberError));
+        assert_eq!(VlcbNodeNumber::try_from(&[1u8, 2][..]), Ok(nn));
+        assert!(VlcbNodeNumber::from_bytes(&[1]).is_err());
+    }
+
+    #[test]
+    fn test_node_number_class() {
+        assert_eq!(VlcbNodeNumber::from(0).class(), NodeNumberClass::Zero);
+        assert!(VlcbNodeNumber::default().is_zero());
+        assert!(!VlcbNodeNumber::from(0xFFFF).is_zero());
+        assert_eq!(VlcbNodeNumber::from(1).class(), NodeNumberClass::Slim);
+        assert_eq!(VlcbNodeNumber::from(99).class(), NodeNumberClass::Slim);
+        assert_eq!(VlcbNodeNumber::from(100).class(), NodeNumberClass::Allocated);
+        assert_eq!(VlcbNodeNumber::from(0xFFEF).class(), NodeNumberClass::Allocated);
+        assert_eq!(VlcbNodeNumber::from(0xFFF0).class(), NodeNumberClass::Reserved);
+        assert_eq!(VlcbNodeNumber::from(0xFFFF).class(), NodeNumberClass::Reserved);
+
+        assert_eq!(VlcbNodeNumber::new_allocated(256), Ok(VlcbNodeNumber::new(1, 0)));
+        for (nn, value) in [(0, 0), (99, 99), (0xFFFE, 0xFE)] {
+            assert_eq!(
+                VlcbNodeNumber::new_allocated(nn),
+                Err(VlcbError::ReservedValue {
+                    type_name: "VlcbNodeNumber",
+                    value
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_event_key() {
+        let nn = VlcbNodeNumber::from(0x0102);
+        let long = EventId::from_node_and_id(&nn, 5, false);
+        assert_eq!(long.as_bytes(), &[1, 2, 0, 5]);
+        let short = EventId::from_node_and_id(&nn, 5, true);
+        assert_eq!(short.node_num(), nn);
+        assert_eq!(EventId::short_from_array([1, 2, 0, 5]), short);
+
+        let other = EventId::new(true, 3, 4, 0, 5);
+        assert_ne!(short, other);
+        assert_eq!(short.key(), other.key());
+        assert!(short.matches(&other));
+        assert_eq!(short.key().as_bytes(), &[0, 0, 0, 5]);
+        assert_eq!(short.key().policy(), EventMatch::Short);
+
+        assert!(!long.matches(&EventId::new(false, 3, 4, 0, 5)));
+        assert!(long.matches(&short));
+        assert!(!short.matches(&EventId::new(true, 1, 2, 0, 6)));
+        assert_ne!(long.key(), short.key());
+        assert_eq!(EventKey::from(long), EventKey::new(&short, EventMatch::Long));
+    }
 }